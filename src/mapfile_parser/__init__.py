@@ -5,7 +5,7 @@
 
 from __future__ import annotations
 
-__version_info__ = (2, 9, 4)
+__version_info__ = (3, 0, 0)
 __version__ = ".".join(map(str, __version_info__)) # + "-dev0"
 __author__ = "Decompollaborate"
 
@@ -19,6 +19,11 @@ from .mapfile import FoundSymbolInfo as FoundSymbolInfo
 from .mapfile import SymbolComparisonInfo as SymbolComparisonInfo
 from .mapfile import MapsComparisonInfo as MapsComparisonInfo
 from .mapfile import ReportCategories as ReportCategories
+from .mapfile import MapFormat as MapFormat
+from .mapfile import MapParseWarning as MapParseWarning
+from .mapfile import MapParseWarningKind as MapParseWarningKind
+from .mapfile import ElfMismatch as ElfMismatch
+from .mapfile import SymbolAddrsCheck as SymbolAddrsCheck
 
 from .progress_stats import ProgressStats as ProgressStats
 
@@ -13,15 +13,18 @@ from pathlib import Path
 from .. import mapfile
 
 
-def doJsonify(mapPath: Path, outputPath: Path|None, humanReadable: bool=True, applyFixes: bool=False) -> int:
+def doJsonify(mapPath: Path, outputPath: Path|None, humanReadable: bool=True, applyFixes: bool=False, document: bool=False) -> int:
     if not mapPath.exists():
         print(f"Could not find mapfile at '{mapPath}'")
         return 1
 
-    mapFile = mapfile.MapFile()
-    mapFile.readMapFile(mapPath)
+    if document:
+        jsonStr = mapfile.MapFileRs.newFromMapFile(mapPath).toJsonDocument()
+    else:
+        mapFile = mapfile.MapFile()
+        mapFile.readMapFile(mapPath)
 
-    jsonStr = json.dumps(mapFile.toJson(humanReadable=humanReadable), indent=4)
+        jsonStr = json.dumps(mapFile.toJson(humanReadable=humanReadable), indent=4)
 
     if outputPath is None:
         print(jsonStr)
@@ -44,8 +47,9 @@ def processArguments(args: argparse.Namespace, decompConfig: decomp_settings.Con
     outputPath: Path|None = Path(args.output) if args.output is not None else None
     machine: bool = args.machine
     applyFixes: bool = args.apply_fixes
+    document: bool = args.document
 
-    exit(doJsonify(mapPath, outputPath, humanReadable=not machine, applyFixes=applyFixes))
+    exit(doJsonify(mapPath, outputPath, humanReadable=not machine, applyFixes=applyFixes, document=document))
 
 def addSubparser(subparser: argparse._SubParsersAction[argparse.ArgumentParser], decompConfig: decomp_settings.Config|None=None):
     parser = subparser.add_parser("jsonify", help="Converts a mapfile into a json format.")
@@ -65,6 +69,7 @@ def addSubparser(subparser: argparse._SubParsersAction[argparse.ArgumentParser],
 
     parser.add_argument("-o", "--output", help="Output path of for the generated json. If omitted then stdout is used instead.")
     parser.add_argument("-m", "--machine", help="Emit numbers as numbers instead of outputting them as pretty strings.", action="store_true")
+    parser.add_argument("-d", "--document", help="Emit a versioned JSON document (see `MapFile.toJsonDocument`) instead of the legacy layout. `--machine` is implied.", action="store_true")
     parser.add_argument("-f", "--apply-fixes", help="DEPRECATED, this is applied automatically now. Apply certain fixups, like fixing size calculation of because of the existence of fake `.NON_MATCHING` symbols.", action="store_true")
 
     parser.set_defaults(func=processArguments)
@@ -18,6 +18,11 @@ from .mapfile_rs import Segment as SegmentRs
 from .mapfile_rs import Section as SectionRs
 from .mapfile_rs import Symbol as SymbolRs
 from .mapfile_rs import ReportCategories as ReportCategories
+from .mapfile_rs import MapFormat as MapFormat
+from .mapfile_rs import MapParseWarning as MapParseWarning
+from .mapfile_rs import MapParseWarningKind as MapParseWarningKind
+from .mapfile_rs import ElfMismatch as ElfMismatch
+from .mapfile_rs import SymbolAddrsCheck as SymbolAddrsCheck
 
 regex_fileDataEntry = re.compile(r"^\s+(?P<section>\.[^\s]+)\s+(?P<vram>0x[^\s]+)\s+(?P<size>0x[^\s]+)\s+(?P<name>[^\s]+)$")
 regex_functionEntry = re.compile(r"^\s+(?P<vram>0x[^\s]+)\s+(?P<name>[^\s]+)$")
@@ -635,6 +640,7 @@ class MapFile:
     #! @deprecated: Use either `newFromMapFile` or `newFromMapStr` instead.
     def __init__(self):
         self._segmentsList: list[Segment] = list()
+        self._parseWarnings: list[MapParseWarning] = list()
 
         #! @deprecated
         self.debugging: bool = False
@@ -651,6 +657,18 @@ class MapFile:
         mapfile.parseMapContents(mapContents)
         return mapfile
 
+    @staticmethod
+    def newFromMapStrWithFormat(mapContents: str, format: MapFormat) -> MapFile:
+        nativeMapFile = MapFileRs.newFromMapStrWithFormat(mapContents, format)
+
+        mapfile = MapFile()
+        mapfile._transferContentsFromNativeMapFile(nativeMapFile)
+        return mapfile
+
+    @staticmethod
+    def detectFormat(mapContents: str) -> tuple[MapFormat, float]|None:
+        return MapFileRs.detectFormat(mapContents)
+
     @staticmethod
     def newFromGnuMapStr(mapContents: str) -> MapFile:
         mapfile = MapFile()
@@ -678,6 +696,10 @@ class MapFile:
         return mapfile
 
     def _transferContentsFromNativeMapFile(self, nativeMapFile: MapFileRs):
+        self._transferSegmentsFromNativeMapFile(nativeMapFile)
+        self._parseWarnings.extend(nativeMapFile.getParseWarnings())
+
+    def _transferSegmentsFromNativeMapFile(self, nativeMapFile: MapFileRs):
         for segment in nativeMapFile:
             newSegment = Segment(segment.name, segment.vram, segment.size, segment.vrom, segment.align)
             for section in segment:
@@ -1015,6 +1037,26 @@ class MapFile:
 
         return compInfo
 
+    def checkSplatSymbolAddrs(self, symbolAddrsContents: str) -> list[SymbolAddrsCheck]:
+        """Checks every entry of a splat `symbol_addrs.txt` against the map, returning the status of each one"""
+        nativeMapFile = self._transferContentsToNativeMapFile()
+        return nativeMapFile.checkSplatSymbolAddrs(symbolAddrsContents)
+
+    def getParseWarnings(self) -> list[MapParseWarning]:
+        """Returns each inconsistency found while parsing the map"""
+        return list(self._parseWarnings)
+
+    def enrichFromElf(self, elfBytes: bytes) -> list[ElfMismatch]:
+        """Replaces the inferred symbol sizes with the ones from the linked ELF and inserts the missing local symbols.
+
+        Returns each disagreement between the map and the ELF"""
+        nativeMapFile = self._transferContentsToNativeMapFile()
+        mismatches = nativeMapFile.enrichFromElf(elfBytes)
+
+        self._segmentsList = list()
+        self._transferSegmentsFromNativeMapFile(nativeMapFile)
+        return mismatches
+
 
     def printAsCsv(self, printVram: bool=True, skipWithoutSymbols: bool=True):
         print(self.toCsv(printVram=printVram, skipWithoutSymbols=skipWithoutSymbols), end="")
@@ -1065,6 +1107,7 @@ class MapFile:
         m.debugging = self.debugging
         for s in self._segmentsList:
             m._segmentsList.append(s.clone())
+        m._parseWarnings = list(self._parseWarnings)
         return m
 
 
@@ -6,7 +6,7 @@
 from __future__ import annotations
 
 from pathlib import Path
-from typing import Any, Generator
+from typing import Any, Generator, Literal
 
 
 class FoundSymbolInfo:
@@ -82,6 +82,7 @@ class Symbol:
     vrom: int|None
     align: int|None
     nonmatchingSymExists: bool
+    sizeIsUnknown: bool
 
     def __init__(self, name: str, vram: int, size: int=0, vrom: int|None=None, align: int|None=None, nonmatchingSymExists: bool=False): ...
 
@@ -172,6 +173,7 @@ class Segment:
     size: int
     vrom: int|None
     align: int|None
+    selector: int|None
     # _filesList: list[Section] = dataclasses.field(default_factory=list)
 
     def __init__(self, name: str, vram: int, size: int, vrom: int|None, align: int|None=None): ...
@@ -231,6 +233,93 @@ class Segment:
     def __hash__(self): ...
 
 
+class MapFormat:
+    Gnu: MapFormat
+    Lld: MapFormat
+    Mw: MapFormat
+    Msvc: MapFormat
+    Ld64: MapFormat
+    Psyq: MapFormat
+    Armlink: MapFormat
+    Rgbds: MapFormat
+    Ld65: MapFormat
+    Watcom: MapFormat
+    Nm: MapFormat
+    Objdump: MapFormat
+
+    def getName(self) -> str: ...
+
+
+class MapParseWarningKind:
+    SymbolBeforeSection: MapParseWarningKind
+    UnsortedSymbol: MapParseWarningKind
+    SectionSizeExceeded: MapParseWarningKind
+    UnknownSectionIndex: MapParseWarningKind
+    UnknownSegmentAddress: MapParseWarningKind
+
+
+class MapParseWarning:
+    """A non-fatal problem found while parsing a mapfile"""
+    @property
+    def segmentName(self) -> str: ...
+    @property
+    def sectionFilepath(self) -> Path: ...
+    @property
+    def symbolName(self) -> str: ...
+    @property
+    def kind(self) -> MapParseWarningKind: ...
+
+
+class ElfMismatch:
+    """A disagreement found while enriching a mapfile with the symbol table of its linked ELF"""
+    @property
+    def segmentName(self) -> str: ...
+    @property
+    def sectionFilepath(self) -> Path: ...
+    @property
+    def symbolName(self) -> str: ...
+    @property
+    def kind(self) -> Literal["SizeMismatch", "AddressMismatch", "InsertedSymbol", "MissingFromMap", "MissingFromElf"]: ...
+    @property
+    def mapSize(self) -> int|None:
+        """Only set for `SizeMismatch`"""
+    @property
+    def elfSize(self) -> int|None:
+        """Only set for `SizeMismatch`"""
+    @property
+    def mapVram(self) -> int|None:
+        """Only set for `AddressMismatch`"""
+    @property
+    def elfVram(self) -> int|None:
+        """Only set for `AddressMismatch`"""
+
+
+class SymbolAddrsCheck:
+    """The result of checking a single `symbol_addrs.txt` entry against a map"""
+    @property
+    def name(self) -> str: ...
+    @property
+    def vram(self) -> int: ...
+    @property
+    def lineNumber(self) -> int: ...
+    @property
+    def statuses(self) -> list[Literal["Matching", "AddressMismatch", "SizeMismatch", "RomMismatch", "Absent"]]: ...
+    @property
+    def mapVram(self) -> int|None:
+        """The address of the symbol on the map, only set for `AddressMismatch`"""
+    @property
+    def mapSize(self) -> int|None:
+        """The size of the symbol on the map, only set for `SizeMismatch`"""
+    @property
+    def mapVrom(self) -> int|None:
+        """The rom address of the symbol on the map, if it has one, only set for `RomMismatch`"""
+    @property
+    def mapSymbol(self) -> FoundSymbolInfo|None:
+        """The symbol with the same name on the map, or the one at the address of the entry for `Absent` ones"""
+
+    def isMatching(self) -> bool: ...
+
+
 class MapFile:
     #! @deprecated: Use either `newFromMapFile` or `newFromMapStr` instead.
     def __init__(self) -> None: ...
@@ -240,11 +329,61 @@ class MapFile:
     @staticmethod
     def newFromMapStr(mapContents: str) -> MapFile: ...
     @staticmethod
+    def newFromMapStrWithFormat(mapContents: str, format: MapFormat) -> MapFile:
+        """Parses the map with the given format, skipping the format detection"""
+    @staticmethod
+    def detectFormat(mapContents: str) -> tuple[MapFormat, float]|None:
+        """Guesses the format of the map, returning it alongside a confidence value between 0.0 and 1.0, or `None` if it doesn't look like any supported format"""
+    @staticmethod
     def newFromGnuMapStr(mapContents: str) -> MapFile: ...
     @staticmethod
     def newFromLldMapStr(mapContents: str) -> MapFile: ...
     @staticmethod
     def newFromMwMapStr(mapContents: str) -> MapFile: ...
+    @staticmethod
+    def newFromMsvcMapStr(mapContents: str) -> MapFile: ...
+    @staticmethod
+    def newFromLd64MapStr(mapContents: str) -> MapFile: ...
+    @staticmethod
+    def newFromPsyqMapStr(mapContents: str) -> MapFile: ...
+    @staticmethod
+    def newFromArmlinkMapStr(mapContents: str) -> MapFile: ...
+    @staticmethod
+    def newFromRgbdsMapStr(mapContents: str) -> MapFile: ...
+    @staticmethod
+    def newFromRgbdsSymStr(symContents: str) -> MapFile: ...
+    def addRgbdsSymStr(self, symContents: str) -> None:
+        """Adds the symbols of a RGBDS symbol file that are missing from this MapFile"""
+    @staticmethod
+    def newFromLd65MapStr(mapContents: str) -> MapFile: ...
+    @staticmethod
+    def newFromWatcomMapStr(mapContents: str) -> MapFile: ...
+    @staticmethod
+    def newFromWatcomMapStrWithSegmentBases(mapContents: str, segmentBases: dict[int, int]) -> MapFile: ...
+
+    @staticmethod
+    def newFromElf(elfBytes: bytes) -> MapFile: ...
+    @staticmethod
+    def newFromNmStr(nmContents: str) -> MapFile: ...
+    @staticmethod
+    def newFromObjdumpStr(objdumpContents: str) -> MapFile: ...
+    @staticmethod
+    def newFromCsvStr(csvContents: str, name: int, vram: int, size: int|None=None, vrom: int|None=None, section: int|None=None, filepath: int|None=None, delimiter: str=",", hasHeader: bool=True) -> MapFile: ...
+    @staticmethod
+    def newFromSplatSymbolAddrsStr(symbolAddrsContents: str) -> MapFile: ...
+
+    @staticmethod
+    def newFromMapFileCached(mapPath: Path, cachePath: Path) -> MapFile:
+        """Same as `newFromMapFile`, but reuses the binary cache at `cachePath` if it is up to date with the map, or writes it otherwise"""
+    @staticmethod
+    def loadCache(cachePath: Path, mapPath: Path) -> MapFile|None:
+        """Returns `None` if the cache is missing, from an incompatible version, or if the map changed since it was written"""
+    def saveCache(self, cachePath: Path, mapPath: Path) -> None: ...
+    @staticmethod
+    def newFromJsonDocument(document: str) -> MapFile:
+        """Loads a map from a document written by `toJsonDocument`, migrating it if it was written by an older version"""
+    def toJsonDocument(self) -> str:
+        """Serializes this map as a versioned JSON document, with a `format_version` field"""
 
     #! @deprecated: Use either `newFromMapFile` instead.
     def readMapFile(self, mapPath: Path) -> None: ...
@@ -286,6 +425,25 @@ class MapFile:
 
     def toJson(self, humanReadable: bool=True) -> dict[str, Any]: ...
 
+    def toSplatSymbolAddrs(self) -> str:
+        """Generates a splat `symbol_addrs.txt` listing every symbol of the map"""
+    def toGnuMapString(self) -> str:
+        """Writes this map as a GNU ld map, which can be parsed back with `newFromGnuMapStr`"""
+
+    def toGnuLinkerScript(self) -> str:
+        """Generates a GNU ld linker script which reproduces the layout of this map"""
+
+    def checkSplatSymbolAddrs(self, symbolAddrsContents: str) -> list[SymbolAddrsCheck]:
+        """Checks every entry of a splat `symbol_addrs.txt` against the map, returning the status of each one"""
+
+    def getParseWarnings(self) -> list[MapParseWarning]:
+        """Returns each inconsistency found while parsing the map"""
+
+    def enrichFromElf(self, elfBytes: bytes) -> list[ElfMismatch]:
+        """Replaces the inferred symbol sizes with the ones from the linked ELF and inserts the missing local symbols.
+
+        Returns each disagreement between the map and the ELF"""
+
     def copySegmentList(self) -> list[Segment]:
         """Returns a copy (not a reference) of the internal segment list"""
 
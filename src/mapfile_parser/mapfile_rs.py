@@ -15,6 +15,11 @@ from .mapfile_parser import Symbol as Symbol
 from .mapfile_parser import Section as Section
 from .mapfile_parser import Segment as Segment
 from .mapfile_parser import MapFile as MapFile
+from .mapfile_parser import MapFormat as MapFormat
+from .mapfile_parser import MapParseWarning as MapParseWarning
+from .mapfile_parser import MapParseWarningKind as MapParseWarningKind
+from .mapfile_parser import ElfMismatch as ElfMismatch
+from .mapfile_parser import SymbolAddrsCheck as SymbolAddrsCheck
 from .mapfile_parser import ReportCategories as ReportCategories
 
 # Renamed types
@@ -0,0 +1,354 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT */
+
+//! A minimal reader for the section headers, program headers and symbol
+//! table of ELF files.
+
+use crate::map_parse_error::{MapParseError, MapParseErrorKind};
+
+const ELFCLASS32: u8 = 1;
+const ELFCLASS64: u8 = 2;
+const ELFDATA2LSB: u8 = 1;
+const ELFDATA2MSB: u8 = 2;
+
+pub(crate) const EM_ARM: u16 = 40;
+
+const PT_LOAD: u32 = 1;
+
+const SHT_SYMTAB: u32 = 2;
+pub(crate) const SHT_NOBITS: u32 = 8;
+
+pub(crate) const SHF_ALLOC: u64 = 0x2;
+
+const SHN_LORESERVE: u16 = 0xFF00;
+const SHN_XINDEX: u16 = 0xFFFF;
+
+pub(crate) const STT_NOTYPE: u8 = 0;
+pub(crate) const STT_OBJECT: u8 = 1;
+pub(crate) const STT_FUNC: u8 = 2;
+pub(crate) const STT_FILE: u8 = 4;
+
+pub(crate) const STB_LOCAL: u8 = 0;
+
+pub(crate) struct ElfSection {
+    pub name: String,
+    pub sh_type: u32,
+    pub flags: u64,
+    pub addr: u64,
+    pub offset: u64,
+    pub size: u64,
+    pub link: u32,
+    pub entsize: u64,
+}
+
+impl ElfSection {
+    pub fn is_alloc(&self) -> bool {
+        self.flags & SHF_ALLOC != 0
+    }
+
+    pub fn is_nobits(&self) -> bool {
+        self.sh_type == SHT_NOBITS
+    }
+}
+
+pub(crate) struct ElfSegment {
+    pub vaddr: u64,
+    pub paddr: u64,
+    pub memsz: u64,
+}
+
+pub(crate) struct ElfSymbol {
+    pub name: String,
+    pub value: u64,
+    pub size: u64,
+    pub sym_type: u8,
+    pub bind: u8,
+    /// Index of the section containing this symbol, `None` for absolute,
+    /// common and undefined symbols.
+    pub section_index: Option<usize>,
+}
+
+pub(crate) struct Elf<'a> {
+    data: &'a [u8],
+    is_64: bool,
+    is_little_endian: bool,
+    pub machine: u16,
+    pub sections: Vec<ElfSection>,
+    pub segments: Vec<ElfSegment>,
+}
+
+fn invalid(description: &str) -> MapParseError {
+    MapParseError::new(
+        None,
+        "",
+        MapParseErrorKind::InvalidElf(description.to_string()),
+    )
+}
+
+impl<'a> Elf<'a> {
+    pub fn parse(data: &'a [u8]) -> Result<Self, MapParseError> {
+        if data.len() < 0x34 || &data[..4] != b"\x7FELF" {
+            return Err(invalid("missing ELF magic"));
+        }
+
+        let is_64 = match data[4] {
+            ELFCLASS32 => false,
+            ELFCLASS64 => true,
+            _ => return Err(invalid("unknown ELF class")),
+        };
+        let is_little_endian = match data[5] {
+            ELFDATA2LSB => true,
+            ELFDATA2MSB => false,
+            _ => return Err(invalid("unknown ELF data encoding")),
+        };
+
+        let mut elf = Self {
+            data,
+            is_64,
+            is_little_endian,
+            machine: 0,
+            sections: Vec::new(),
+            segments: Vec::new(),
+        };
+
+        elf.machine = elf.read_u16(0x12)?;
+
+        // Offsets of the fields that come after the word sized ones differ
+        // between 32 and 64 bits.
+        let (phoff, shoff, rest) = if is_64 {
+            (elf.read_u64(0x20)?, elf.read_u64(0x28)?, 0x34)
+        } else {
+            (elf.read_u32(0x1C)? as u64, elf.read_u32(0x20)? as u64, 0x28)
+        };
+        let phentsize = elf.read_u16(rest + 2)? as u64;
+        let phnum = elf.read_u16(rest + 4)? as u64;
+        let shentsize = elf.read_u16(rest + 6)? as u64;
+        let mut shnum = elf.read_u16(rest + 8)? as u64;
+        let mut shstrndx = elf.read_u16(rest + 10)? as u64;
+
+        let (min_phentsize, min_shentsize) = if is_64 { (0x38, 0x40) } else { (0x20, 0x28) };
+        if phnum != 0 && phentsize < min_phentsize {
+            return Err(invalid("program header entry size too small"));
+        }
+
+        if shoff != 0 {
+            if shentsize < min_shentsize {
+                return Err(invalid("section header entry size too small"));
+            }
+
+            let first_section = elf.read_section_header(shoff)?;
+            // Files with a lot of sections store the actual values on the
+            // first section header.
+            if shnum == 0 {
+                shnum = first_section.size;
+            }
+            if shstrndx == SHN_XINDEX as u64 {
+                shstrndx = first_section.link as u64;
+            }
+            // Don't trust the count further than the headers that fit on the
+            // file.
+            shnum = shnum.min((data.len() as u64).saturating_sub(shoff) / shentsize);
+
+            for i in 0..shnum {
+                let header_offset = entry_offset(shoff, i, shentsize)?;
+                elf.sections.push(elf.read_section_header(header_offset)?);
+            }
+
+            // The names are read after all the headers, since we need the
+            // section containing them.
+            let names_section = elf
+                .sections
+                .get(shstrndx as usize)
+                .map(|x| (x.offset, x.size));
+            if let Some((names_offset, names_size)) = names_section {
+                let names_table = elf.slice(names_offset, names_size)?;
+                let mut names = Vec::with_capacity(elf.sections.len());
+                for i in 0..elf.sections.len() {
+                    let name_offset = elf.read_u32(entry_offset(shoff, i as u64, shentsize)?)?;
+                    names.push(read_str(names_table, name_offset as u64));
+                }
+                for (section, name) in elf.sections.iter_mut().zip(names) {
+                    section.name = name;
+                }
+            }
+        }
+
+        for i in 0..phnum {
+            let offset = entry_offset(phoff, i, phentsize)?;
+            let p_type = elf.read_u32(offset)?;
+            if p_type != PT_LOAD {
+                continue;
+            }
+
+            let segment = if is_64 {
+                ElfSegment {
+                    vaddr: elf.read_u64(offset + 0x10)?,
+                    paddr: elf.read_u64(offset + 0x18)?,
+                    memsz: elf.read_u64(offset + 0x28)?,
+                }
+            } else {
+                ElfSegment {
+                    vaddr: elf.read_u32(offset + 0x08)? as u64,
+                    paddr: elf.read_u32(offset + 0x0C)? as u64,
+                    memsz: elf.read_u32(offset + 0x14)? as u64,
+                }
+            };
+            elf.segments.push(segment);
+        }
+
+        Ok(elf)
+    }
+
+    /// Reads every symbol of the `.symtab` section, in the order they appear
+    /// on the file.
+    pub fn symbols(&self) -> Result<Vec<ElfSymbol>, MapParseError> {
+        let Some(symtab) = self.sections.iter().find(|x| x.sh_type == SHT_SYMTAB) else {
+            return Ok(Vec::new());
+        };
+        let strtab = self
+            .sections
+            .get(symtab.link as usize)
+            .ok_or_else(|| invalid("missing symbol string table"))?;
+        let names_table = self.slice(strtab.offset, strtab.size)?;
+
+        let entsize = if symtab.entsize != 0 {
+            symtab.entsize
+        } else if self.is_64 {
+            24
+        } else {
+            16
+        };
+
+        let count = symtab.size / entsize;
+        let mut symbols = Vec::new();
+
+        // The first symbol is always the null one
+        for i in 1..count {
+            let offset = entry_offset(symtab.offset, i, entsize)?;
+
+            let (name_offset, value, size, info, shndx) = if self.is_64 {
+                (
+                    self.read_u32(offset)?,
+                    self.read_u64(offset + 8)?,
+                    self.read_u64(offset + 16)?,
+                    self.read_u8(offset + 4)?,
+                    self.read_u16(offset + 6)?,
+                )
+            } else {
+                (
+                    self.read_u32(offset)?,
+                    self.read_u32(offset + 4)? as u64,
+                    self.read_u32(offset + 8)? as u64,
+                    self.read_u8(offset + 12)?,
+                    self.read_u16(offset + 14)?,
+                )
+            };
+
+            symbols.push(ElfSymbol {
+                name: read_str(names_table, name_offset as u64),
+                value,
+                size,
+                sym_type: info & 0xF,
+                bind: info >> 4,
+                section_index: (shndx != 0 && shndx < SHN_LORESERVE).then_some(shndx as usize),
+            });
+        }
+
+        Ok(symbols)
+    }
+
+    /// Calculates the load address of the given allocated section by using
+    /// the program header containing it.
+    pub fn load_address_of(&self, section: &ElfSection) -> Option<u64> {
+        self.segments
+            .iter()
+            .find(|x| section.addr >= x.vaddr && section.addr - x.vaddr < x.memsz.max(1))
+            .and_then(|x| x.paddr.checked_add(section.addr - x.vaddr))
+    }
+
+    fn read_section_header(&self, offset: u64) -> Result<ElfSection, MapParseError> {
+        if self.is_64 {
+            Ok(ElfSection {
+                name: String::new(),
+                sh_type: self.read_u32(offset + 0x04)?,
+                flags: self.read_u64(offset + 0x08)?,
+                addr: self.read_u64(offset + 0x10)?,
+                offset: self.read_u64(offset + 0x18)?,
+                size: self.read_u64(offset + 0x20)?,
+                link: self.read_u32(offset + 0x28)?,
+                entsize: self.read_u64(offset + 0x38)?,
+            })
+        } else {
+            Ok(ElfSection {
+                name: String::new(),
+                sh_type: self.read_u32(offset + 0x04)?,
+                flags: self.read_u32(offset + 0x08)? as u64,
+                addr: self.read_u32(offset + 0x0C)? as u64,
+                offset: self.read_u32(offset + 0x10)? as u64,
+                size: self.read_u32(offset + 0x14)? as u64,
+                link: self.read_u32(offset + 0x18)?,
+                entsize: self.read_u32(offset + 0x24)? as u64,
+            })
+        }
+    }
+
+    fn slice(&self, offset: u64, size: u64) -> Result<&'a [u8], MapParseError> {
+        let start = usize::try_from(offset).ok();
+        let end = offset
+            .checked_add(size)
+            .and_then(|x| usize::try_from(x).ok());
+
+        match (start, end) {
+            (Some(start), Some(end)) if end <= self.data.len() => Ok(&self.data[start..end]),
+            _ => Err(invalid("data out of bounds")),
+        }
+    }
+
+    fn read_u8(&self, offset: u64) -> Result<u8, MapParseError> {
+        Ok(self.slice(offset, 1)?[0])
+    }
+
+    fn read_u16(&self, offset: u64) -> Result<u16, MapParseError> {
+        let bytes = self.slice(offset, 2)?.try_into().unwrap();
+        Ok(if self.is_little_endian {
+            u16::from_le_bytes(bytes)
+        } else {
+            u16::from_be_bytes(bytes)
+        })
+    }
+
+    fn read_u32(&self, offset: u64) -> Result<u32, MapParseError> {
+        let bytes = self.slice(offset, 4)?.try_into().unwrap();
+        Ok(if self.is_little_endian {
+            u32::from_le_bytes(bytes)
+        } else {
+            u32::from_be_bytes(bytes)
+        })
+    }
+
+    fn read_u64(&self, offset: u64) -> Result<u64, MapParseError> {
+        let bytes = self.slice(offset, 8)?.try_into().unwrap();
+        Ok(if self.is_little_endian {
+            u64::from_le_bytes(bytes)
+        } else {
+            u64::from_be_bytes(bytes)
+        })
+    }
+}
+
+/// Calculates the offset of the `index`th entry of a table.
+fn entry_offset(table_offset: u64, index: u64, entry_size: u64) -> Result<u64, MapParseError> {
+    index
+        .checked_mul(entry_size)
+        .and_then(|x| x.checked_add(table_offset))
+        .ok_or_else(|| invalid("table entry out of bounds"))
+}
+
+fn read_str(table: &[u8], offset: u64) -> String {
+    let Some(bytes) = usize::try_from(offset).ok().and_then(|x| table.get(x..)) else {
+        return String::new();
+    };
+    let end = bytes.iter().position(|x| *x == 0).unwrap_or(bytes.len());
+
+    String::from_utf8_lossy(&bytes[..end]).into_owned()
+}
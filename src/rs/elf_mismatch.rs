@@ -0,0 +1,202 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT */
+
+use std::{fmt, path::PathBuf};
+
+#[cfg(feature = "python_bindings")]
+use pyo3::prelude::*;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// The kind of disagreement found between a mapfile and the symbol table of
+/// its linked ELF.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ElfMismatchKind {
+    /// The size of the symbol on the map differs from its `st_size`. The size
+    /// of the symbol was replaced with the one from the ELF.
+    SizeMismatch { map_size: u64, elf_size: u64 },
+
+    /// The symbol is placed at a different address on the map and on the ELF.
+    /// The symbol was left untouched.
+    AddressMismatch { map_vram: u64, elf_vram: u64 },
+
+    /// The local symbol was missing from the map, so it was inserted into the
+    /// section containing its address.
+    InsertedSymbol,
+
+    /// The symbol is missing from the map and it could not be inserted,
+    /// either because it is a global symbol or because no section of the map
+    /// contains its address.
+    MissingFromMap,
+
+    /// The symbol of the map is missing from the symbol table of the ELF.
+    MissingFromElf,
+}
+
+impl fmt::Display for ElfMismatchKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ElfMismatchKind::SizeMismatch { map_size, elf_size } => {
+                write!(
+                    f,
+                    "size 0x{:X} on the map, but 0x{:X} on the ELF",
+                    map_size, elf_size
+                )
+            }
+            ElfMismatchKind::AddressMismatch { map_vram, elf_vram } => {
+                write!(
+                    f,
+                    "address 0x{:08X} on the map, but 0x{:08X} on the ELF",
+                    map_vram, elf_vram
+                )
+            }
+            ElfMismatchKind::InsertedSymbol => {
+                write!(f, "local symbol missing from the map was inserted")
+            }
+            ElfMismatchKind::MissingFromMap => {
+                write!(f, "symbol is missing from the map")
+            }
+            ElfMismatchKind::MissingFromElf => {
+                write!(f, "symbol is missing from the ELF")
+            }
+        }
+    }
+}
+
+/// A disagreement found while enriching a mapfile with the symbol table of
+/// its linked ELF.
+///
+/// The segment name and section path are empty for symbols of the ELF that
+/// could not be placed on any section of the map.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "python_bindings",
+    pyclass(module = "mapfile_parser", frozen)
+)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ElfMismatch {
+    pub segment_name: String,
+
+    pub section_filepath: PathBuf,
+
+    pub symbol_name: String,
+
+    pub kind: ElfMismatchKind,
+}
+
+impl ElfMismatch {
+    pub fn new(
+        segment_name: String,
+        section_filepath: PathBuf,
+        symbol_name: String,
+        kind: ElfMismatchKind,
+    ) -> Self {
+        Self {
+            segment_name,
+            section_filepath,
+            symbol_name,
+            kind,
+        }
+    }
+}
+
+impl fmt::Display for ElfMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} ({}): {}",
+            self.segment_name,
+            self.section_filepath.display(),
+            self.symbol_name,
+            self.kind
+        )
+    }
+}
+
+#[cfg(feature = "python_bindings")]
+#[allow(non_snake_case)]
+pub(crate) mod python_bindings {
+    use pyo3::{intern, prelude::*, IntoPyObjectExt};
+
+    use super::ElfMismatchKind;
+
+    #[pymethods]
+    impl super::ElfMismatch {
+        /* Getters */
+
+        #[getter]
+        fn get_segmentName(&self) -> &str {
+            &self.segment_name
+        }
+
+        // Manually convert PathBuf into a pathlib.Path object since pyo3 refuses to do so
+        #[getter]
+        fn get_sectionFilepath(&self) -> PyResult<PyObject> {
+            Python::with_gil(|py| {
+                let pathlib = py.import("pathlib")?;
+                let pathlib_path = pathlib.getattr(intern!(py, "Path"))?;
+                let args = (self.section_filepath.as_path(),);
+
+                pathlib_path.call1(args)?.into_py_any(py)
+            })
+        }
+
+        #[getter]
+        fn get_symbolName(&self) -> &str {
+            &self.symbol_name
+        }
+
+        /// The name of the `ElfMismatchKind` variant, like `SizeMismatch`.
+        #[getter]
+        fn get_kind(&self) -> &'static str {
+            match self.kind {
+                ElfMismatchKind::SizeMismatch { .. } => "SizeMismatch",
+                ElfMismatchKind::AddressMismatch { .. } => "AddressMismatch",
+                ElfMismatchKind::InsertedSymbol => "InsertedSymbol",
+                ElfMismatchKind::MissingFromMap => "MissingFromMap",
+                ElfMismatchKind::MissingFromElf => "MissingFromElf",
+            }
+        }
+
+        #[getter]
+        fn get_mapSize(&self) -> Option<u64> {
+            match self.kind {
+                ElfMismatchKind::SizeMismatch { map_size, .. } => Some(map_size),
+                _ => None,
+            }
+        }
+
+        #[getter]
+        fn get_elfSize(&self) -> Option<u64> {
+            match self.kind {
+                ElfMismatchKind::SizeMismatch { elf_size, .. } => Some(elf_size),
+                _ => None,
+            }
+        }
+
+        #[getter]
+        fn get_mapVram(&self) -> Option<u64> {
+            match self.kind {
+                ElfMismatchKind::AddressMismatch { map_vram, .. } => Some(map_vram),
+                _ => None,
+            }
+        }
+
+        #[getter]
+        fn get_elfVram(&self) -> Option<u64> {
+            match self.kind {
+                ElfMismatchKind::AddressMismatch { elf_vram, .. } => Some(elf_vram),
+                _ => None,
+            }
+        }
+
+        /* Methods */
+
+        fn __str__(&self) -> String {
+            self.to_string()
+        }
+    }
+}
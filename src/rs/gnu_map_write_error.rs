@@ -0,0 +1,112 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT */
+
+use std::{error, fmt, path::PathBuf};
+
+/// The reason why a map can't be written as a GNU ld map which parses back
+/// into the same map.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum GnuMapWriteErrorKind {
+    /// The filepath of the section contains whitespace, which the GNU ld map
+    /// parser takes as the end of the input section line.
+    WhitespaceOnFilepath,
+
+    /// The section has a size of zero. GNU ld lists these, but the GNU ld map
+    /// parser skips them.
+    EmptySection,
+
+    /// The name of the segment contains whitespace. The `section_filepath`
+    /// of these errors is empty.
+    WhitespaceOnSegmentName,
+
+    /// The section type contains whitespace.
+    WhitespaceOnSectionType,
+
+    /// The name of the symbol contains whitespace, so the GNU ld map parser
+    /// skips its line.
+    WhitespaceOnSymbolName,
+
+    /// The name of the symbol is empty, looks like a jump table label (like
+    /// `.L80001234`) or is one of the names the GNU ld map parser ignores, so
+    /// the symbol would be dropped.
+    DiscardedSymbolName,
+}
+
+impl fmt::Display for GnuMapWriteErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GnuMapWriteErrorKind::WhitespaceOnFilepath => {
+                write!(f, "filepath contains whitespace")
+            }
+            GnuMapWriteErrorKind::EmptySection => write!(f, "section has a size of zero"),
+            GnuMapWriteErrorKind::WhitespaceOnSegmentName => {
+                write!(f, "segment name contains whitespace")
+            }
+            GnuMapWriteErrorKind::WhitespaceOnSectionType => {
+                write!(f, "section type contains whitespace")
+            }
+            GnuMapWriteErrorKind::WhitespaceOnSymbolName => {
+                write!(f, "symbol name contains whitespace")
+            }
+            GnuMapWriteErrorKind::DiscardedSymbolName => {
+                write!(f, "symbol name is discarded when parsing")
+            }
+        }
+    }
+}
+
+/// A segment, section or symbol of a map that would be lost or altered by
+/// writing the map as a GNU ld map and parsing it back.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GnuMapWriteError {
+    pub segment_name: String,
+
+    pub section_filepath: PathBuf,
+
+    /// Empty unless the error is about a symbol.
+    pub symbol_name: String,
+
+    pub kind: GnuMapWriteErrorKind,
+}
+
+impl GnuMapWriteError {
+    pub fn new(
+        segment_name: String,
+        section_filepath: PathBuf,
+        symbol_name: String,
+        kind: GnuMapWriteErrorKind,
+    ) -> Self {
+        Self {
+            segment_name,
+            section_filepath,
+            symbol_name,
+            kind,
+        }
+    }
+}
+
+impl fmt::Display for GnuMapWriteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.symbol_name.is_empty() {
+            write!(
+                f,
+                "{}: {}: {}",
+                self.segment_name,
+                self.section_filepath.display(),
+                self.kind
+            )
+        } else {
+            write!(
+                f,
+                "{}: {} ({}): {}",
+                self.segment_name,
+                self.section_filepath.display(),
+                self.symbol_name,
+                self.kind
+            )
+        }
+    }
+}
+
+impl error::Error for GnuMapWriteError {}
@@ -0,0 +1,202 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT */
+
+use std::{fmt::Write, path::Path};
+
+use crate::{
+    gnu_map_write_error::{GnuMapWriteError, GnuMapWriteErrorKind},
+    map_lexer,
+    mapfile::MapFile,
+    parser::BANNED_SYMBOL_NAMES,
+};
+
+/// The width of the name column of the memory map. Names that don't leave
+/// room for a space after them get printed on their own line, as GNU ld
+/// does.
+const NAME_COLUMN_WIDTH: usize = 16;
+
+impl MapFile {
+    /// Writes this map as a GNU ld map, for tools which only understand that
+    /// format.
+    ///
+    /// Each segment is written as an output section with its load address,
+    /// each section as an input section line followed by the lines of its
+    /// symbols, and each fill section (see [`Section::is_fill`]) as a
+    /// `*fill*` line.
+    ///
+    /// Parsing the output with [`MapFile::new_from_gnu_map_str`] gives back
+    /// an equivalent `MapFile`, except for what GNU ld maps can't express:
+    /// - Symbol sizes and the rom addresses of sections and symbols are
+    ///   inferred again from the addresses, so they only match if the
+    ///   symbols of each section are laid out contiguously.
+    /// - Fill sections are placed at the end of the section before them.
+    /// - Alignments and segment selectors are lost.
+    ///
+    /// Sections with a size of zero and names and filepaths with whitespace
+    /// are written as GNU ld writes them, even if parsing the output drops the
+    /// former and misreads the latter. Symbols whose names the parser ignores,
+    /// like jump table labels, are written too. Use
+    /// [`MapFile::try_to_gnu_map_string`] to get an error for those instead. Empty segment names, section types and
+    /// filepaths are written as `.unnamed`, the name of the segment and
+    /// `unknown`, respectively.
+    ///
+    /// [`Section::is_fill`]: crate::Section::is_fill
+    pub fn to_gnu_map_string(&self) -> String {
+        let mut ret = String::new();
+
+        ret.push_str("\nMemory Configuration\n\n");
+        writeln!(
+            ret,
+            "{:<17}{:<19}{:<19}Attributes",
+            "Name", "Origin", "Length"
+        )
+        .unwrap();
+        writeln!(ret, "{:<17}0x{:016x} 0x{:016x}", "*default*", 0, u64::MAX).unwrap();
+        ret.push_str("\nLinker script and memory map\n");
+
+        for segment in &self.segments_list {
+            let segment_name = non_empty(&segment.name, ".unnamed");
+
+            ret.push('\n');
+            write_name_column(&mut ret, "", segment_name);
+            write!(ret, "0x{:016x} {:>#10x}", segment.vram, segment.size).unwrap();
+            if let Some(vrom) = segment.vrom {
+                write!(ret, " load address 0x{:016x}", vrom).unwrap();
+            }
+            ret.push('\n');
+
+            for section in &segment.sections_list {
+                if section.is_fill {
+                    writeln!(
+                        ret,
+                        " *fill*         0x{:016x} {:>#10x} ",
+                        section.vram, section.size
+                    )
+                    .unwrap();
+                    continue;
+                }
+
+                let section_type = non_empty(&section.section_type, segment_name);
+                let filepath = section.filepath.to_string_lossy();
+
+                write_name_column(&mut ret, " ", section_type);
+                writeln!(
+                    ret,
+                    "0x{:016x} {:>#10x} {}",
+                    section.vram,
+                    section.size,
+                    non_empty(&filepath, "unknown")
+                )
+                .unwrap();
+
+                for sym in &section.symbols {
+                    writeln!(
+                        ret,
+                        "{:w$}0x{:016x}{:w$}{}",
+                        "",
+                        sym.vram,
+                        "",
+                        sym.name,
+                        w = NAME_COLUMN_WIDTH
+                    )
+                    .unwrap();
+                }
+            }
+        }
+
+        ret
+    }
+
+    /// Same as [`MapFile::to_gnu_map_string`], but returns an error for the
+    /// first segment, section or symbol that would not be parsed back as it
+    /// is. See [`GnuMapWriteErrorKind`] for the reasons.
+    pub fn try_to_gnu_map_string(&self) -> Result<String, GnuMapWriteError> {
+        for segment in &self.segments_list {
+            let error = |section_filepath: &Path, symbol_name: &str, kind| {
+                GnuMapWriteError::new(
+                    segment.name.clone(),
+                    section_filepath.to_path_buf(),
+                    symbol_name.to_string(),
+                    kind,
+                )
+            };
+
+            if segment.name.contains(char::is_whitespace) {
+                return Err(error(
+                    Path::new(""),
+                    "",
+                    GnuMapWriteErrorKind::WhitespaceOnSegmentName,
+                ));
+            }
+
+            for section in &segment.sections_list {
+                if section.is_fill {
+                    continue;
+                }
+
+                let kind = if section.size == 0 {
+                    Some(GnuMapWriteErrorKind::EmptySection)
+                } else if section
+                    .filepath
+                    .to_string_lossy()
+                    .contains(char::is_whitespace)
+                {
+                    Some(GnuMapWriteErrorKind::WhitespaceOnFilepath)
+                } else if section.section_type.contains(char::is_whitespace) {
+                    Some(GnuMapWriteErrorKind::WhitespaceOnSectionType)
+                } else {
+                    None
+                };
+                if let Some(kind) = kind {
+                    return Err(error(&section.filepath, "", kind));
+                }
+
+                for sym in &section.symbols {
+                    let kind = if sym.name.contains(char::is_whitespace) {
+                        GnuMapWriteErrorKind::WhitespaceOnSymbolName
+                    } else if sym.name.is_empty()
+                        || map_lexer::gnu_is_label(&sym.name)
+                        || BANNED_SYMBOL_NAMES.contains(&*sym.name)
+                    {
+                        GnuMapWriteErrorKind::DiscardedSymbolName
+                    } else {
+                        continue;
+                    };
+
+                    return Err(error(&section.filepath, &sym.name, kind));
+                }
+            }
+        }
+
+        Ok(self.to_gnu_map_string())
+    }
+}
+
+/// Writes `name` padded to the name column, or on a line of its own if it
+/// is too long to fit.
+fn write_name_column(ret: &mut String, indent: &str, name: &str) {
+    let width = indent.len() + name.len();
+
+    if width < NAME_COLUMN_WIDTH - 1 {
+        write!(
+            ret,
+            "{}{}{:w$}",
+            indent,
+            name,
+            "",
+            w = NAME_COLUMN_WIDTH - width
+        )
+        .unwrap();
+    } else {
+        writeln!(ret, "{}{}", indent, name).unwrap();
+        write!(ret, "{:w$}", "", w = NAME_COLUMN_WIDTH).unwrap();
+    }
+}
+
+fn non_empty<'a>(value: &'a str, fallback: &'a str) -> &'a str {
+    if value.is_empty() {
+        fallback
+    } else {
+        value
+    }
+}
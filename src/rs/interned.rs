@@ -0,0 +1,360 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT */
+
+use std::{
+    borrow::Borrow,
+    collections::HashSet,
+    ffi::OsStr,
+    fmt,
+    ops::Deref,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+#[cfg(any(feature = "parallel", feature = "serde"))]
+use crate::segment::Segment;
+
+/// An immutable string which can be shared between many symbols and
+/// sections of a map without copying it.
+///
+/// Cloning an `InternedStr` only bumps a reference count. It dereferences to
+/// a [`str`], so it can be used mostly like a [`String`].
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct InternedStr(Arc<str>);
+
+impl InternedStr {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Deref for InternedStr {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for InternedStr {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<OsStr> for InternedStr {
+    fn as_ref(&self) -> &OsStr {
+        self.0.as_ref().as_ref()
+    }
+}
+
+impl AsRef<Path> for InternedStr {
+    fn as_ref(&self) -> &Path {
+        self.0.as_ref().as_ref()
+    }
+}
+
+impl Borrow<str> for InternedStr {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Debug for InternedStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&*self.0, f)
+    }
+}
+
+impl fmt::Display for InternedStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&*self.0, f)
+    }
+}
+
+impl From<&str> for InternedStr {
+    fn from(value: &str) -> Self {
+        Self(value.into())
+    }
+}
+
+impl From<String> for InternedStr {
+    fn from(value: String) -> Self {
+        Self(value.into())
+    }
+}
+
+impl From<&String> for InternedStr {
+    fn from(value: &String) -> Self {
+        Self(value.as_str().into())
+    }
+}
+
+impl From<InternedStr> for String {
+    fn from(value: InternedStr) -> Self {
+        value.0.as_ref().to_owned()
+    }
+}
+
+impl PartialEq<str> for InternedStr {
+    fn eq(&self, other: &str) -> bool {
+        &*self.0 == other
+    }
+}
+
+impl PartialEq<&str> for InternedStr {
+    fn eq(&self, other: &&str) -> bool {
+        &*self.0 == *other
+    }
+}
+
+impl PartialEq<String> for InternedStr {
+    fn eq(&self, other: &String) -> bool {
+        &*self.0 == other.as_str()
+    }
+}
+
+impl PartialEq<InternedStr> for str {
+    fn eq(&self, other: &InternedStr) -> bool {
+        self == &*other.0
+    }
+}
+
+impl PartialEq<InternedStr> for &str {
+    fn eq(&self, other: &InternedStr) -> bool {
+        *self == &*other.0
+    }
+}
+
+impl PartialEq<InternedStr> for String {
+    fn eq(&self, other: &InternedStr) -> bool {
+        self.as_str() == &*other.0
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for InternedStr {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for InternedStr {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(Self::from)
+    }
+}
+
+/// An immutable path which can be shared between many sections of a map
+/// without copying it.
+///
+/// Cloning an `InternedPath` only bumps a reference count. It dereferences
+/// to a [`Path`], so it can be used mostly like a [`PathBuf`].
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct InternedPath(Arc<Path>);
+
+impl InternedPath {
+    pub fn as_path(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Default for InternedPath {
+    fn default() -> Self {
+        Self(Path::new("").into())
+    }
+}
+
+impl Deref for InternedPath {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl AsRef<Path> for InternedPath {
+    fn as_ref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl AsRef<OsStr> for InternedPath {
+    fn as_ref(&self) -> &OsStr {
+        self.0.as_os_str()
+    }
+}
+
+impl Borrow<Path> for InternedPath {
+    fn borrow(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl fmt::Debug for InternedPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&*self.0, f)
+    }
+}
+
+impl From<&Path> for InternedPath {
+    fn from(value: &Path) -> Self {
+        Self(value.into())
+    }
+}
+
+impl From<PathBuf> for InternedPath {
+    fn from(value: PathBuf) -> Self {
+        Self(value.into())
+    }
+}
+
+impl From<&PathBuf> for InternedPath {
+    fn from(value: &PathBuf) -> Self {
+        Self(value.as_path().into())
+    }
+}
+
+impl From<&str> for InternedPath {
+    fn from(value: &str) -> Self {
+        Self(Path::new(value).into())
+    }
+}
+
+impl From<String> for InternedPath {
+    fn from(value: String) -> Self {
+        Self(PathBuf::from(value).into())
+    }
+}
+
+impl From<InternedPath> for PathBuf {
+    fn from(value: InternedPath) -> Self {
+        value.0.to_path_buf()
+    }
+}
+
+impl PartialEq<Path> for InternedPath {
+    fn eq(&self, other: &Path) -> bool {
+        &*self.0 == other
+    }
+}
+
+impl PartialEq<&Path> for InternedPath {
+    fn eq(&self, other: &&Path) -> bool {
+        &*self.0 == *other
+    }
+}
+
+impl PartialEq<PathBuf> for InternedPath {
+    fn eq(&self, other: &PathBuf) -> bool {
+        &*self.0 == other.as_path()
+    }
+}
+
+impl PartialEq<InternedPath> for Path {
+    fn eq(&self, other: &InternedPath) -> bool {
+        self == &*other.0
+    }
+}
+
+impl PartialEq<InternedPath> for PathBuf {
+    fn eq(&self, other: &InternedPath) -> bool {
+        self.as_path() == &*other.0
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for InternedPath {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for InternedPath {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        PathBuf::deserialize(deserializer).map(Self::from)
+    }
+}
+
+/// A table of the strings and paths used by a map, so every symbol and
+/// section repeating one of them points to the same copy.
+///
+/// Parsers build their symbols and sections through one of these, so
+/// repeated names are only allocated once.
+#[derive(Default)]
+pub(crate) struct Interner {
+    strs: HashSet<InternedStr>,
+    paths: HashSet<InternedPath>,
+}
+
+impl Interner {
+    pub fn intern_str(&mut self, value: &str) -> InternedStr {
+        if let Some(x) = self.strs.get(value) {
+            return x.clone();
+        }
+
+        let new_str = InternedStr::from(value);
+        self.strs.insert(new_str.clone());
+        new_str
+    }
+
+    pub fn intern_path(&mut self, value: &Path) -> InternedPath {
+        if let Some(x) = self.paths.get(value) {
+            return x.clone();
+        }
+
+        let new_path = InternedPath::from(value);
+        self.paths.insert(new_path.clone());
+        new_path
+    }
+
+    /// Same as [`Interner::intern_str`], but reuses `value` instead of
+    /// copying it if it's not on the table yet.
+    #[cfg(any(feature = "parallel", feature = "serde"))]
+    pub fn share_str(&mut self, value: &InternedStr) -> InternedStr {
+        if let Some(x) = self.strs.get(value.as_str()) {
+            return x.clone();
+        }
+
+        self.strs.insert(value.clone());
+        value.clone()
+    }
+
+    /// Same as [`Interner::intern_path`], but reuses `value` instead of
+    /// copying it if it's not on the table yet.
+    #[cfg(any(feature = "parallel", feature = "serde"))]
+    pub fn share_path(&mut self, value: &InternedPath) -> InternedPath {
+        if let Some(x) = self.paths.get(value.as_path()) {
+            return x.clone();
+        }
+
+        self.paths.insert(value.clone());
+        value.clone()
+    }
+}
+
+/// Makes every symbol and section of `segments` that shares a name, filepath
+/// or section type with another one point to the same copy of it.
+///
+/// Only needed for segments that were not built through a single
+/// [`Interner`], like the ones parsed concurrently or deserialized.
+#[cfg(any(feature = "parallel", feature = "serde"))]
+pub(crate) fn intern_segment_strings(segments: &mut [Segment]) {
+    let mut interner = Interner::default();
+
+    for segment in segments {
+        for section in &mut segment.sections_list {
+            section.filepath = interner.share_path(&section.filepath);
+            section.section_type = interner.share_str(&section.section_type);
+
+            for sym in &mut section.symbols {
+                sym.name = interner.share_str(&sym.name);
+            }
+        }
+    }
+}
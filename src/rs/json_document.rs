@@ -0,0 +1,504 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT */
+
+use std::borrow::Cow;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    interned::intern_segment_strings,
+    map_parse_error::{MapParseError, MapParseErrorKind},
+    map_parse_warning::{MapParseWarning, MapParseWarningKind},
+    mapfile::MapFile,
+    section::Section,
+    segment::Segment,
+    symbol::Symbol,
+    utils,
+};
+
+impl MapFile {
+    /// The version of the documents written by [`MapFile::to_json_document`].
+    pub const JSON_DOCUMENT_FORMAT_VERSION: u64 = 2;
+
+    /// Serializes this map as a versioned JSON document, meant to be archived
+    /// and reloaded with [`MapFile::from_json_document`] by any later version
+    /// of this crate.
+    ///
+    /// Unlike the `serde` implementation of `MapFile`, the layout of the
+    /// document doesn't follow the layout of the structs, and it only changes
+    /// together with [`MapFile::JSON_DOCUMENT_FORMAT_VERSION`].
+    ///
+    /// The current version (2) looks like this. Addresses and sizes are
+    /// written as plain integers, and missing values as `null`.
+    ///
+    /// ```json
+    /// {
+    ///   "format_version": 2,
+    ///   "segments": [
+    ///     {
+    ///       "name": ".main",
+    ///       "vram": 2147484672,
+    ///       "size": 48,
+    ///       "vrom": 4096,
+    ///       "align": null,
+    ///       "selector": null,
+    ///       "sections": [
+    ///         {
+    ///           "filepath": "build/src/main.o",
+    ///           "section_type": ".text",
+    ///           "vram": 2147484672,
+    ///           "size": 32,
+    ///           "vrom": 4096,
+    ///           "align": null,
+    ///           "is_fill": false,
+    ///           "symbols": [
+    ///             {
+    ///               "name": "main",
+    ///               "vram": 2147484672,
+    ///               "size": 32,
+    ///               "vrom": 4096,
+    ///               "align": null,
+    ///               "nonmatching_sym_exists": false,
+    ///               "size_is_unknown": false
+    ///             }
+    ///           ]
+    ///         }
+    ///       ]
+    ///     }
+    ///   ],
+    ///   "parse_warnings": [
+    ///     {
+    ///       "segment_name": ".main",
+    ///       "section_filepath": "build/src/main.o",
+    ///       "symbol_name": "main",
+    ///       "kind": "unsorted_symbol"
+    ///     }
+    ///   ]
+    /// }
+    /// ```
+    ///
+    /// The `kind` of a warning is one of `symbol_before_section`,
+    /// `unsorted_symbol`, `section_size_exceeded`, `unknown_section_index` or
+    /// `unknown_segment_address`.
+    ///
+    /// Version 1 is the layout printed by the `jsonify` frontend, which has no
+    /// `format_version` field.
+    pub fn to_json_document(&self) -> String {
+        let document = Document {
+            format_version: Self::JSON_DOCUMENT_FORMAT_VERSION,
+            segments: self
+                .segments_list
+                .iter()
+                .map(DocumentSegment::from)
+                .collect(),
+            parse_warnings: self
+                .parse_warnings
+                .iter()
+                .map(DocumentWarning::from)
+                .collect(),
+        };
+
+        serde_json::to_string_pretty(&document).unwrap()
+    }
+
+    /// Loads a map from a JSON document written by
+    /// [`MapFile::to_json_document`].
+    ///
+    /// Documents written by older versions are migrated to the current one.
+    /// Documents without a `format_version` field are read as version 1, the
+    /// layout printed by the `jsonify` frontend, with numbers written either
+    /// as integers or as hexadecimal strings.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the document is malformed or was written by a newer version
+    /// of this crate. Use [`MapFile::try_from_json_document`] to handle those
+    /// cases gracefully.
+    #[must_use]
+    pub fn from_json_document(document: &str) -> Self {
+        Self::try_from_json_document(document).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Same as [`MapFile::from_json_document`], but returns an error instead
+    /// of panicking if the document is malformed or was written by a newer
+    /// version of this crate.
+    pub fn try_from_json_document(document: &str) -> Result<Self, MapParseError> {
+        let value: Value = serde_json::from_str(document).map_err(|e| {
+            let line = document
+                .lines()
+                .nth(e.line().saturating_sub(1))
+                .unwrap_or("");
+            MapParseError::new(
+                Some(e.line()),
+                line,
+                MapParseErrorKind::InvalidJsonDocument(e.to_string()),
+            )
+        })?;
+
+        let format_version = match value.get("format_version") {
+            None => 1,
+            Some(x) => x
+                .as_u64()
+                .ok_or_else(|| invalid_document(format!("invalid format_version `{}`", x)))?,
+        };
+
+        let document = match format_version {
+            1 => migrate_v1(from_value(value)?)?,
+            2 => from_value(value)?,
+            _ => {
+                return Err(MapParseError::new(
+                    None,
+                    "",
+                    MapParseErrorKind::UnsupportedJsonDocumentVersion(format_version),
+                ))
+            }
+        };
+
+        let mut m = Self::new_impl();
+        m.segments_list = document.segments.into_iter().map(Segment::from).collect();
+        m.parse_warnings = document
+            .parse_warnings
+            .into_iter()
+            .map(MapParseWarning::from)
+            .collect();
+        intern_segment_strings(&mut m.segments_list);
+
+        Ok(m)
+    }
+}
+
+fn invalid_document(description: String) -> MapParseError {
+    MapParseError::new(
+        None,
+        "",
+        MapParseErrorKind::InvalidJsonDocument(description),
+    )
+}
+
+fn from_value<T: serde::de::DeserializeOwned>(value: Value) -> Result<T, MapParseError> {
+    serde_json::from_value(value).map_err(|e| invalid_document(e.to_string()))
+}
+
+/* Version 2 */
+
+#[derive(Serialize, Deserialize)]
+struct Document<'a> {
+    format_version: u64,
+    segments: Vec<DocumentSegment<'a>>,
+    #[serde(default)]
+    parse_warnings: Vec<DocumentWarning<'a>>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct DocumentSegment<'a> {
+    name: Cow<'a, str>,
+    vram: u64,
+    size: u64,
+    vrom: Option<u64>,
+    #[serde(default)]
+    align: Option<u64>,
+    #[serde(default)]
+    selector: Option<u64>,
+    sections: Vec<DocumentSection<'a>>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct DocumentSection<'a> {
+    filepath: Cow<'a, str>,
+    section_type: Cow<'a, str>,
+    vram: u64,
+    size: u64,
+    vrom: Option<u64>,
+    #[serde(default)]
+    align: Option<u64>,
+    #[serde(default)]
+    is_fill: bool,
+    symbols: Vec<DocumentSymbol<'a>>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct DocumentSymbol<'a> {
+    name: Cow<'a, str>,
+    vram: u64,
+    size: u64,
+    vrom: Option<u64>,
+    #[serde(default)]
+    align: Option<u64>,
+    #[serde(default)]
+    nonmatching_sym_exists: bool,
+    #[serde(default)]
+    size_is_unknown: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+struct DocumentWarning<'a> {
+    segment_name: Cow<'a, str>,
+    section_filepath: Cow<'a, str>,
+    symbol_name: Cow<'a, str>,
+    kind: DocumentWarningKind,
+}
+
+/// Kept apart from [`MapParseWarningKind`] so renaming a variant doesn't
+/// change the documents.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum DocumentWarningKind {
+    SymbolBeforeSection,
+    UnsortedSymbol,
+    SectionSizeExceeded,
+    UnknownSectionIndex,
+    UnknownSegmentAddress,
+}
+
+impl<'a> From<&'a Segment> for DocumentSegment<'a> {
+    fn from(value: &'a Segment) -> Self {
+        Self {
+            name: Cow::Borrowed(&value.name),
+            vram: value.vram,
+            size: value.size,
+            vrom: value.vrom,
+            align: value.align,
+            selector: value.selector,
+            sections: value
+                .sections_list
+                .iter()
+                .map(DocumentSection::from)
+                .collect(),
+        }
+    }
+}
+
+impl<'a> From<&'a Section> for DocumentSection<'a> {
+    fn from(value: &'a Section) -> Self {
+        Self {
+            filepath: value.filepath.to_string_lossy(),
+            section_type: Cow::Borrowed(&value.section_type),
+            vram: value.vram,
+            size: value.size,
+            vrom: value.vrom,
+            align: value.align,
+            is_fill: value.is_fill,
+            symbols: value.symbols.iter().map(DocumentSymbol::from).collect(),
+        }
+    }
+}
+
+impl<'a> From<&'a Symbol> for DocumentSymbol<'a> {
+    fn from(value: &'a Symbol) -> Self {
+        Self {
+            name: Cow::Borrowed(&value.name),
+            vram: value.vram,
+            size: value.size,
+            vrom: value.vrom,
+            align: value.align,
+            nonmatching_sym_exists: value.nonmatching_sym_exists,
+            size_is_unknown: value.size_is_unknown,
+        }
+    }
+}
+
+impl<'a> From<&'a MapParseWarning> for DocumentWarning<'a> {
+    fn from(value: &'a MapParseWarning) -> Self {
+        Self {
+            segment_name: Cow::Borrowed(&value.segment_name),
+            section_filepath: value.section_filepath.to_string_lossy(),
+            symbol_name: Cow::Borrowed(&value.symbol_name),
+            kind: match value.kind {
+                MapParseWarningKind::SymbolBeforeSection => {
+                    DocumentWarningKind::SymbolBeforeSection
+                }
+                MapParseWarningKind::UnsortedSymbol => DocumentWarningKind::UnsortedSymbol,
+                MapParseWarningKind::SectionSizeExceeded => {
+                    DocumentWarningKind::SectionSizeExceeded
+                }
+                MapParseWarningKind::UnknownSectionIndex => {
+                    DocumentWarningKind::UnknownSectionIndex
+                }
+                MapParseWarningKind::UnknownSegmentAddress => {
+                    DocumentWarningKind::UnknownSegmentAddress
+                }
+            },
+        }
+    }
+}
+
+impl From<DocumentSegment<'_>> for Segment {
+    fn from(value: DocumentSegment<'_>) -> Self {
+        let mut segment = Segment::new(
+            value.name.into_owned(),
+            value.vram,
+            value.size,
+            value.vrom,
+            value.align,
+        );
+        segment.selector = value.selector;
+        segment.sections_list = value.sections.into_iter().map(Section::from).collect();
+        segment
+    }
+}
+
+impl From<DocumentSection<'_>> for Section {
+    fn from(value: DocumentSection<'_>) -> Self {
+        let mut section = Section::new_impl(
+            value.filepath.into_owned().into(),
+            value.vram,
+            value.size,
+            value.section_type.into_owned().into(),
+            value.vrom,
+            value.align,
+            value.is_fill,
+        );
+        section.symbols = value.symbols.into_iter().map(Symbol::from).collect();
+        section
+    }
+}
+
+impl From<DocumentSymbol<'_>> for Symbol {
+    fn from(value: DocumentSymbol<'_>) -> Self {
+        let mut sym = Symbol::new(
+            value.name.into_owned(),
+            value.vram,
+            value.size,
+            value.vrom,
+            value.align,
+        );
+        sym.nonmatching_sym_exists = value.nonmatching_sym_exists;
+        sym.size_is_unknown = value.size_is_unknown;
+        sym
+    }
+}
+
+impl From<DocumentWarning<'_>> for MapParseWarning {
+    fn from(value: DocumentWarning<'_>) -> Self {
+        MapParseWarning::new(
+            value.segment_name.into_owned(),
+            value.section_filepath.into_owned().into(),
+            value.symbol_name.into_owned(),
+            match value.kind {
+                DocumentWarningKind::SymbolBeforeSection => {
+                    MapParseWarningKind::SymbolBeforeSection
+                }
+                DocumentWarningKind::UnsortedSymbol => MapParseWarningKind::UnsortedSymbol,
+                DocumentWarningKind::SectionSizeExceeded => {
+                    MapParseWarningKind::SectionSizeExceeded
+                }
+                DocumentWarningKind::UnknownSectionIndex => {
+                    MapParseWarningKind::UnknownSectionIndex
+                }
+                DocumentWarningKind::UnknownSegmentAddress => {
+                    MapParseWarningKind::UnknownSegmentAddress
+                }
+            },
+        )
+    }
+}
+
+/* Version 1, as printed by the `jsonify` frontend */
+
+#[derive(Deserialize)]
+struct DocumentV1 {
+    segments: Vec<SegmentV1>,
+}
+
+#[derive(Deserialize)]
+struct SegmentV1 {
+    name: String,
+    vram: NumberV1,
+    size: NumberV1,
+    vrom: Option<NumberV1>,
+    files: Vec<SectionV1>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SectionV1 {
+    filepath: String,
+    section_type: String,
+    vram: NumberV1,
+    size: NumberV1,
+    vrom: Option<NumberV1>,
+    symbols: Vec<SymbolV1>,
+}
+
+#[derive(Deserialize)]
+struct SymbolV1 {
+    name: String,
+    vram: NumberV1,
+    size: Option<NumberV1>,
+    vrom: Option<NumberV1>,
+}
+
+/// Numbers were written as hexadecimal strings unless `jsonify` was run with
+/// `--machine`.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum NumberV1 {
+    Int(u64),
+    Hex(String),
+}
+
+impl NumberV1 {
+    fn value(self) -> Result<u64, MapParseError> {
+        match self {
+            NumberV1::Int(x) => Ok(x),
+            NumberV1::Hex(x) => utils::try_parse_hex(&x)
+                .map_err(|_| MapParseError::new(None, "", MapParseErrorKind::InvalidHexNumber(x))),
+        }
+    }
+}
+
+fn optional_v1(value: Option<NumberV1>) -> Result<Option<u64>, MapParseError> {
+    value.map(NumberV1::value).transpose()
+}
+
+fn migrate_v1(document: DocumentV1) -> Result<Document<'static>, MapParseError> {
+    let mut segments = Vec::with_capacity(document.segments.len());
+
+    for segment in document.segments {
+        let mut sections = Vec::with_capacity(segment.files.len());
+
+        for section in segment.files {
+            let mut symbols = Vec::with_capacity(section.symbols.len());
+
+            for sym in section.symbols {
+                symbols.push(DocumentSymbol {
+                    name: sym.name.into(),
+                    vram: sym.vram.value()?,
+                    size: optional_v1(sym.size)?.unwrap_or(0),
+                    vrom: optional_v1(sym.vrom)?,
+                    align: None,
+                    nonmatching_sym_exists: false,
+                    size_is_unknown: false,
+                });
+            }
+
+            sections.push(DocumentSection {
+                filepath: section.filepath.into(),
+                section_type: section.section_type.into(),
+                vram: section.vram.value()?,
+                size: section.size.value()?,
+                vrom: optional_v1(section.vrom)?,
+                align: None,
+                is_fill: false,
+                symbols,
+            });
+        }
+
+        segments.push(DocumentSegment {
+            name: segment.name.into(),
+            vram: segment.vram.value()?,
+            size: segment.size.value()?,
+            vrom: optional_v1(segment.vrom)?,
+            align: None,
+            selector: None,
+            sections,
+        });
+    }
+
+    Ok(Document {
+        format_version: MapFile::JSON_DOCUMENT_FORMAT_VERSION,
+        segments,
+        parse_warnings: Vec::new(),
+    })
+}
@@ -3,14 +3,40 @@
 
 #![warn(clippy::manual_let_else)]
 
+mod elf;
+mod elf_mismatch;
 mod found_symbol_info;
+mod gnu_map_write_error;
+mod gnu_map_writer;
+mod interned;
+#[cfg(feature = "serde")]
+mod json_document;
+mod linker_script;
+mod map_cache;
+mod map_format;
+mod map_lexer;
+mod map_parse_error;
+mod map_parse_warning;
 mod mapfile;
 mod maps_comparison_info;
 mod parser;
+mod parser_armlink;
+mod parser_csv;
+mod parser_elf;
+mod parser_ld64;
+mod parser_ld65;
+mod parser_msvc;
+mod parser_nm;
+mod parser_objdump;
+mod parser_psyq;
+mod parser_rgbds;
+mod parser_watcom;
 mod progress_stats;
 mod section;
 mod segment;
 mod symbol;
+mod symbol_addrs;
+mod symbol_addrs_check;
 mod symbol_comparison_info;
 mod symbol_decomp_state;
 pub mod utils;
@@ -18,13 +44,22 @@ pub mod utils;
 #[cfg(feature = "objdiff_report")]
 pub mod report;
 
+pub use elf_mismatch::{ElfMismatch, ElfMismatchKind};
 pub use found_symbol_info::FoundSymbolInfo;
+pub use gnu_map_write_error::{GnuMapWriteError, GnuMapWriteErrorKind};
+pub use interned::{InternedPath, InternedStr};
+pub use map_format::MapFormat;
+pub use map_parse_error::{MapParseError, MapParseErrorKind};
+pub use map_parse_warning::{MapParseWarning, MapParseWarningKind};
 pub use mapfile::MapFile;
 pub use maps_comparison_info::MapsComparisonInfo;
+pub use parser_csv::{CsvColumnMapping, CsvNumberBase};
 pub use progress_stats::ProgressStats;
 pub use section::{PathDecompSettings, Section};
 pub use segment::Segment;
 pub use symbol::Symbol;
+pub use symbol_addrs::SymbolAddrsEntry;
+pub use symbol_addrs_check::{SymbolAddrsCheck, SymbolAddrsStatus};
 pub use symbol_comparison_info::SymbolComparisonInfo;
 pub use symbol_decomp_state::{SymbolDecompState, SymbolDecompStateIter};
 
@@ -45,6 +80,11 @@ fn mapfile_parser(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<segment::Segment>()?;
     m.add_class::<section::Section>()?;
     m.add_class::<symbol::Symbol>()?;
+    m.add_class::<map_format::MapFormat>()?;
+    m.add_class::<map_parse_warning::MapParseWarning>()?;
+    m.add_class::<map_parse_warning::MapParseWarningKind>()?;
+    m.add_class::<elf_mismatch::ElfMismatch>()?;
+    m.add_class::<symbol_addrs_check::python_bindings::PySymbolAddrsCheck>()?;
     m.add_class::<found_symbol_info::python_bindings::PyFoundSymbolInfo>()?;
     m.add_class::<symbol_comparison_info::python_bindings::PySymbolComparisonInfo>()?;
     m.add_class::<maps_comparison_info::python_bindings::PyMapsComparisonInfo>()?;
@@ -57,7 +97,10 @@ fn mapfile_parser(m: &Bound<'_, PyModule>) -> PyResult<()> {
 mod tests {
     use std::path::PathBuf;
 
-    use crate::mapfile::MapFile;
+    use crate::{
+        mapfile::MapFile, CsvColumnMapping, CsvNumberBase, ElfMismatchKind, GnuMapWriteErrorKind,
+        MapFormat, MapParseErrorKind, MapParseWarningKind, SymbolAddrsEntry, SymbolAddrsStatus,
+    };
 
     // TODO: tests
 
@@ -65,4 +108,2153 @@ mod tests {
     fn w0_000_map() {
         let _ = MapFile::new_from_map_file(&PathBuf::from("tests/maps/gnuld/misc/w0_000.map"));
     }
+
+    #[test]
+    fn invalid_hex_is_an_error() {
+        let map_contents = "\
+Linker script and memory map
+
+ .text          0x80000400       0x10 build/src/main.o
+                0x8000040Z                main
+";
+
+        let err = MapFile::try_new_from_gnu_map_str(map_contents).unwrap_err();
+        assert_eq!(err.line_number, Some(4));
+        assert!(matches!(err.kind, MapParseErrorKind::InvalidHexNumber(_)));
+    }
+
+    #[test]
+    fn symbol_outside_section_is_an_error() {
+        let map_contents = "\
+     VMA      LMA     Size Align Out     In      Symbol
+80000400 80000400       10    10 .text
+80000400 80000400        0     1         main
+";
+
+        let err = MapFile::try_new_from_lld_map_str(map_contents).unwrap_err();
+        assert_eq!(err.line_number, Some(3));
+        assert!(matches!(err.kind, MapParseErrorKind::SymbolOutsideSection));
+    }
+
+    #[test]
+    fn mw_rom_overflow_is_an_error() {
+        let map_contents = "\
+Link map of __start
+
+.text section layout
+  Starting        Virtual
+  address  Size   address
+  -----------------------
+  00000010 000078 00000010  1 .text \tmain.o 
+
+Memory map:
+                   Starting Size     File
+                   address           Offset
+            .text  00000000 00000088 fffffffffffffff8
+";
+
+        let err = MapFile::try_new_from_mw_map_str(map_contents).unwrap_err();
+        assert_eq!(err.line_number, Some(7));
+        assert!(matches!(err.kind, MapParseErrorKind::AddressOverflow));
+    }
+
+    #[test]
+    fn unsorted_symbols_are_reported() {
+        let map_contents = "\
+Linker script and memory map
+
+ .text          0x80000400       0x10 build/src/main.o
+                0x80000408                main
+                0x80000404                func
+";
+
+        let map = MapFile::try_new_from_gnu_map_str(map_contents).unwrap();
+        let symbols = &map.segments_list[0].sections_list[0].symbols;
+
+        assert_eq!(symbols[0].size, 0);
+        assert!(symbols[0].size_is_unknown);
+        assert!(!symbols[1].size_is_unknown);
+        assert_eq!(map.parse_warnings.len(), 1);
+        assert_eq!(map.parse_warnings[0].symbol_name, "main");
+        assert_eq!(
+            map.parse_warnings[0].kind,
+            MapParseWarningKind::UnsortedSymbol
+        );
+    }
+
+    #[test]
+    fn detect_format() {
+        assert_eq!(MapFile::detect_format(""), None);
+        assert_eq!(
+            MapFile::detect_format("Link map of __start\n"),
+            Some((MapFormat::Mw, 1.0))
+        );
+        assert_eq!(
+            MapFile::detect_format("     VMA      LMA     Size Align Out     In      Symbol\n"),
+            Some((MapFormat::Lld, 1.0))
+        );
+        assert_eq!(
+            MapFile::detect_format("\nMemory Configuration\n\nLinker script and memory map\n"),
+            Some((MapFormat::Gnu, 0.9))
+        );
+        assert_eq!(
+            MapFile::detect_format(" .text          0x80000400       0x10 build/src/main.o\n"),
+            Some((MapFormat::Gnu, 0.5))
+        );
+
+        let not_a_map = "hello world\nthis is not a map\n";
+        assert_eq!(MapFile::detect_format(not_a_map), None);
+        let err = MapFile::try_new_from_map_str(not_a_map).unwrap_err();
+        assert!(matches!(err.kind, MapParseErrorKind::UnknownFormat));
+        let err = MapFile::try_from_reader(not_a_map.as_bytes()).unwrap_err();
+        assert!(matches!(err.kind, MapParseErrorKind::UnknownFormat));
+
+        // The constructors that predate format detection fall back to GNU ld
+        assert!(MapFile::new_from_map_str(not_a_map)
+            .segments_list
+            .is_empty());
+        assert!(MapFile::new_from_map_str("").segments_list.is_empty());
+
+        // An explicit format skips the detection
+        let map = MapFile::try_new_from_map_str_with_format(not_a_map, MapFormat::Gnu).unwrap();
+        assert!(map.segments_list.is_empty());
+    }
+
+    #[test]
+    fn msvc_map() {
+        let map_contents = "\
+ game
+
+ Timestamp is 3b8fd5c3 (Fri Aug 31 15:11:31 2001)
+
+ Preferred load address is 00400000
+
+ Start         Length     Name                   Class
+ 0001:00000000 00000040H .text                   CODE
+ 0001:00000040 00000010H .text$x                 CODE
+ 0002:00000000 00000010H .data                   DATA
+ 0002:00000010 00000020H .bss                    DATA
+ 0003:00000000 00000010H .tls                    DATA
+
+  Address         Publics by Value              Rva+Base       Lib:Object
+
+ 0000:00000000       ___safe_se_handler_count   00000000     <absolute>
+ 0001:00000000       _main                      00401000 f   main.obj
+ 0001:00000030       ?foo@@YAXXZ                00401030 f   libfoo:foo.obj
+ 0001:00000040       _unwind                    00401040 f   libfoo:foo.obj
+ 0002:00000000       _gData                     00402000     main.obj
+ 0002:00000010       _gBss                      00402010     main.obj
+ 0004:00000000       _gOrphan                   00405000     main.obj
+
+ entry point at        0001:00000000
+
+ Static symbols
+
+ 0001:00000010       _helper                    00401010 f   main.obj
+";
+
+        assert_eq!(
+            MapFile::detect_format(map_contents),
+            Some((MapFormat::Msvc, 1.0))
+        );
+
+        let map = MapFile::new_from_map_str(map_contents);
+        assert_eq!(map.segments_list.len(), 2);
+
+        let text = &map.segments_list[0];
+        assert_eq!(text.name, ".text");
+        assert_eq!(text.vram, 0x00401000);
+        assert_eq!(text.sections_list.len(), 3);
+        assert_eq!(text.sections_list[0].symbols.len(), 2);
+        assert_eq!(text.sections_list[0].size, 0x30);
+        assert_eq!(
+            text.sections_list[1].filepath,
+            PathBuf::from("libfoo:foo.obj")
+        );
+        assert_eq!(text.sections_list[2].section_type, ".text$x");
+
+        let info = map.find_symbol_by_vram(0x00401014).0.unwrap();
+        assert_eq!(info.symbol.name, "_helper");
+        assert_eq!(info.symbol.size, 0x20);
+
+        let data = &map.segments_list[1];
+        assert_eq!(data.sections_list.len(), 2);
+        assert!(data.sections_list[1].is_noload_section());
+
+        // Nothing tells the address of `.tls`, and `_gOrphan` has no section
+        assert_eq!(map.parse_warnings.len(), 2);
+        assert_eq!(map.parse_warnings[0].symbol_name, "_gOrphan");
+        assert_eq!(
+            map.parse_warnings[0].kind,
+            MapParseWarningKind::UnknownSectionIndex
+        );
+        assert_eq!(map.parse_warnings[1].segment_name, ".tls");
+        assert_eq!(
+            map.parse_warnings[1].kind,
+            MapParseWarningKind::UnknownSegmentAddress
+        );
+
+        // A map produced by `lld-link /map`
+        let map = MapFile::new_from_map_file(&PathBuf::from("tests/maps/lld-link/win64/game.map"));
+        assert!(map.parse_warnings.is_empty());
+        let info = map.find_symbol_by_name("helper").unwrap();
+        assert_eq!(info.symbol.vram, 0x140001016);
+        assert_eq!(info.symbol.size, 0x10);
+        assert_eq!(info.section.filepath, PathBuf::from("main.obj"));
+        let info = map.find_symbol_by_name("gFooState").unwrap();
+        assert_eq!(info.section.filepath, PathBuf::from("libfoo:foo.obj"));
+        assert!(info.section.is_noload_section());
+    }
+
+    #[test]
+    fn ld64_map() {
+        let map_contents = "\
+# Path: build/tool
+# Arch: arm64
+# Object files:
+[  0] linker synthesized
+[  1] build/main.o
+[  2] build/libfoo.a(foo.o)
+# Sections:
+# Address\tSize    \tSegment\tSection
+0x100003F50\t0x00000040\t__TEXT\t__text
+0x100003F90\t0x0000000C\t__TEXT\t__cstring
+0x100008000\t0x00000010\t__DATA\t__data
+0x100008010\t0x00000020\t__DATA\t__bss
+# Symbols:
+# Address\tSize    \tFile  Name
+0x100003F50\t0x00000010\t[  1] _main
+0x100003F60\t0x00000010\t[  1] _helper
+0x100003F70\t0x00000020\t[  2] _foo
+0x100003F90\t0x0000000C\t[  1] literal string: Hello world
+0x100008000\t0x00000010\t[  1] _gData
+0x100008010\t0x00000020\t[  2] _gBss
+# Dead Stripped Symbols:
+#        \tSize    \tFile  Name
+<<dead>> \t0x00000018\t[  1] _unused
+";
+
+        assert_eq!(
+            MapFile::detect_format(map_contents),
+            Some((MapFormat::Ld64, 1.0))
+        );
+
+        let map = MapFile::new_from_map_str(map_contents);
+        assert_eq!(map.segments_list.len(), 2);
+
+        let text = &map.segments_list[0];
+        assert_eq!(text.name, "__TEXT");
+        assert_eq!(text.sections_list.len(), 3);
+        assert_eq!(text.sections_list[0].size, 0x20);
+        assert_eq!(
+            text.sections_list[1].filepath,
+            PathBuf::from("build/libfoo.a(foo.o)")
+        );
+        assert_eq!(
+            text.sections_list[2].symbols[0].name,
+            "literal string: Hello world"
+        );
+
+        let data = &map.segments_list[1];
+        assert!(data.sections_list[1].is_noload_section());
+        assert!(map.find_symbol_by_name("_unused").is_none());
+
+        // A map produced by `ld64.lld -map`
+        let map = MapFile::new_from_map_file(&PathBuf::from("tests/maps/ld64.lld/macos/game.map"));
+        assert_eq!(map.segments_list.len(), 2);
+        let info = map.find_symbol_by_name("_foo_step").unwrap();
+        assert_eq!(info.symbol.vram, 0x100000410);
+        assert_eq!(info.symbol.size, 0x10);
+        assert_eq!(info.section.filepath, PathBuf::from("foo.o"));
+        assert!(map.find_symbol_by_name("_foo_unused").is_none());
+    }
+
+    #[test]
+    fn psyq_map() {
+        let map_contents = "\
+  Start     Stop   Length      Obj Group            Section name
+ 80010000 8001003F 00000040      text                .rdata
+ 80010040 8001013F 00000100      text                .text
+ 80010140 8001015F 00000020      text                .data
+ 80010160 800101DF 00000080      bss                 .bss
+ 80010040 8001013F 00000100   
+
+  Address  Names alphabetical order
+
+ 80010040 main
+ 80010160 gBss
+ 80010080 func
+
+  Address  Names in address order
+
+ 80010040 main
+ 80010080 func
+ 80010160 gBss
+";
+
+        assert_eq!(
+            MapFile::detect_format(map_contents),
+            Some((MapFormat::Psyq, 1.0))
+        );
+
+        let map = MapFile::new_from_map_str(map_contents);
+        assert_eq!(map.segments_list.len(), 2);
+
+        let text = &map.segments_list[0];
+        assert_eq!(text.name, "text");
+        assert_eq!(text.size, 0x160);
+        assert_eq!(text.sections_list[1].vrom, Some(0x840));
+        assert_eq!(text.sections_list[1].symbols.len(), 2);
+        assert_eq!(text.sections_list[1].symbols[1].size, 0xC0);
+
+        let info = map.find_symbol_by_vrom(0x8C4).0.unwrap();
+        assert_eq!(info.symbol.name, "func");
+
+        let bss = &map.segments_list[1];
+        assert_eq!(bss.vrom, None);
+        assert_eq!(bss.sections_list[0].symbols[0].vrom, None);
+    }
+
+    #[test]
+    fn armlink_map() {
+        let map_contents = "\
+==============================================================================
+
+Image Symbol Table
+
+    Local Symbols
+
+    Symbol Name                              Value     Ov Type        Size  Object(Section)
+
+    RESET                                    0x08000000   Section      192  startup.o(RESET)
+    helper                                   0x08000101   Thumb Code    16  main.o(.text)
+
+    Global Symbols
+
+    Symbol Name                              Value     Ov Type        Size  Object(Section)
+
+    __Vectors                                0x08000000   Data           4  startup.o(RESET)
+    main                                     0x08000111   Thumb Code    32  main.o(.text)
+    __aeabi_memcpy                           0x08000140   ARM Code      36  c_w.l(memcpy.o)
+    memcpy_inner                             0x08000140   ARM Code       0  memcpy.o(.text)
+    gCounter                                 0x20000000   Data           4  main.o(.data)
+    gBuffer                                  0x20000004   Data          64  main.o(.bss)
+
+==============================================================================
+
+Memory Map of the image
+
+  Image Entry point : 0x08000101
+
+  Load Region LR_IROM1 (Base: 0x08000000, Size: 0x00000178, Max: 0x00080000, ABSOLUTE)
+
+    Execution Region ER_IROM1 (Exec base: 0x08000000, Load base: 0x08000000, Size: 0x00000174, Max: 0x00080000, ABSOLUTE)
+
+    Exec Addr    Load Addr    Size         Type   Attr      Idx    E Section Name        Object
+
+    0x08000000   0x08000000   0x000000c0   Data   RO            3    RESET               startup.o
+    0x080000c0   0x080000c0   0x00000040   PAD
+    0x08000100   0x08000100   0x00000040   Code   RO            8  * .text               main.o
+    0x08000140   0x08000140   0x00000034   Code   RO           20    .text               c_w.l(memcpy.o)
+
+    Execution Region RW_IRAM1 (Exec base: 0x20000000, Load base: 0x08000174, Size: 0x00000044, Max: 0x00005000, ABSOLUTE)
+
+    Exec Addr    Load Addr    Size         Type   Attr      Idx    E Section Name        Object
+
+    0x20000000   0x08000174   0x00000004   Data   RW            5    .data               main.o
+    0x20000004        -       0x00000040   Zero   RW            6    .bss                main.o
+";
+
+        assert_eq!(
+            MapFile::detect_format(map_contents),
+            Some((MapFormat::Armlink, 1.0))
+        );
+
+        let map = MapFile::new_from_map_str(map_contents);
+        assert_eq!(map.segments_list.len(), 1);
+
+        let segment = &map.segments_list[0];
+        assert_eq!(segment.name, "LR_IROM1");
+        assert_eq!(segment.vrom, Some(0));
+        assert_eq!(segment.sections_list.len(), 6);
+        assert!(segment.sections_list[1].is_fill);
+
+        let text = &segment.sections_list[2];
+        assert_eq!(text.filepath, PathBuf::from("main.o"));
+        assert_eq!(text.symbols[0].name, "helper");
+        assert_eq!(text.symbols[0].vram, 0x08000100);
+        assert_eq!(text.symbols[1].vram, 0x08000110);
+
+        // Thumb function pointers have the lowest bit set
+        let info = map.find_symbol_by_vram(0x08000111).0.unwrap();
+        assert_eq!(info.symbol.name, "main");
+
+        let memcpy = &segment.sections_list[3];
+        assert_eq!(memcpy.symbols.len(), 2);
+        assert_eq!(memcpy.symbols[0].name, "__aeabi_memcpy");
+        assert_eq!(memcpy.symbols[0].size, 36);
+        assert_eq!(memcpy.symbols[1].name, "memcpy_inner");
+        assert_eq!(memcpy.symbols[1].size, 0x34);
+
+        let data = &segment.sections_list[4];
+        assert_eq!(data.vram, 0x20000000);
+        assert_eq!(data.vrom, Some(0x174));
+        assert_eq!(data.symbols[0].vrom, Some(0x174));
+
+        let bss = &segment.sections_list[5];
+        assert_eq!(bss.vrom, None);
+        assert_eq!(bss.symbols[0].name, "gBuffer");
+    }
+
+    #[test]
+    fn rgbds_map_and_sym() {
+        let map_contents = "\
+SUMMARY:
+	ROM0: 336 bytes used / 16048 free
+	ROMX: 32 bytes used / 16352 free in 1 bank
+
+ROM0 bank #0:
+	SECTION: $0000-$0007 ($0008 bytes) [\"RST_00\"]
+	         $0000 = RST_00
+	SECTION: $0100-$014f ($0050 bytes) [\"Header\"]
+	         $0100 = Start
+	EMPTY: $0150-$3fff ($3eb0 bytes)
+
+ROMX bank #2:
+	SECTION: $4000-$401f ($0020 bytes) [\"Bank2Code\"]
+	         $4000 = Func
+	         $4010 = Func2
+	EMPTY: $4020-$7fff ($3fe0 bytes)
+
+WRAM0 bank #0:
+	SECTION: $c000-$c0ff ($0100 bytes) [\"WRAM\"]
+	         $c000 = wBuffer
+";
+        let sym_contents = "\
+; File generated by rgblink
+00:0000 RST_00
+00:0100 Start
+02:4000 Func
+02:4008 Func.loop
+02:4010 Func2
+02:5000 Orphan
+00:c000 wBuffer
+";
+
+        assert_eq!(
+            MapFile::detect_format(map_contents),
+            Some((MapFormat::Rgbds, 1.0))
+        );
+
+        let mut map = MapFile::new_from_map_str(map_contents);
+        assert_eq!(map.segments_list.len(), 3);
+        assert_eq!(map.segments_list[1].name, "ROMX bank #2");
+        assert_eq!(map.segments_list[1].vrom, Some(0x8000));
+
+        let info = map.find_symbol_by_vrom(0x8014).0.unwrap();
+        assert_eq!(info.symbol.name, "Func2");
+        assert_eq!(info.offset, 4);
+        assert_eq!(map.segments_list[2].sections_list[0].vrom, None);
+
+        map.add_rgbds_sym_str(sym_contents);
+        assert_eq!(map.segments_list.len(), 3);
+
+        let bank2 = &map.segments_list[1];
+        assert_eq!(bank2.sections_list.len(), 2);
+        assert_eq!(bank2.sections_list[0].symbols[0].size, 8);
+        assert_eq!(bank2.sections_list[0].symbols[1].name, "Func.loop");
+
+        let info = map.find_symbol_by_vrom(0x9000).0.unwrap();
+        assert_eq!(info.symbol.name, "Orphan");
+        assert_eq!(info.symbol.size, 0x3000);
+
+        let sym_only = MapFile::new_from_rgbds_sym_str(sym_contents);
+        assert_eq!(sym_only.segments_list.len(), 3);
+        assert_eq!(sym_only.segments_list[1].sections_list[0].symbols.len(), 4);
+    }
+
+    #[test]
+    fn ld65_map() {
+        let map_contents = "\
+Modules list:
+-------------
+crt0.o:
+    STARTUP           Offs=000000  Size=000010  Align=00001  Fill=0000
+main.o:
+    CODE              Offs=000000  Size=000020  Align=00001  Fill=0000
+    BSS               Offs=000000  Size=000008  Align=00001  Fill=0000
+/usr/share/cc65/lib/nes.lib(copydata.o):
+    CODE              Offs=000020  Size=000010  Align=00001  Fill=0000
+
+
+Segment list:
+-------------
+Name                   Start     End    Size  Align
+----------------------------------------------------
+STARTUP               008000  00800F  000010  00001
+CODE                  008010  00803F  000030  00001
+BSS                   000300  000307  000008  00001
+
+
+Exports list by name:
+---------------------
+__STACKSIZE__             000300 REA    _main                     008010 RLA
+_helper                   008018 RLA    copydata                  008030 RLA
+start                     008000 RLA
+
+Exports list by value:
+----------------------
+start                     008000 RLA    _main                     008010 RLA
+_helper                   008018 RLA    copydata                  008030 RLA
+__STACKSIZE__             000300 REA
+
+Imports list:
+-------------
+_main (main.o):
+    crt0.o                    crt0.s(12)
+";
+
+        assert_eq!(
+            MapFile::detect_format(map_contents),
+            Some((MapFormat::Ld65, 1.0))
+        );
+
+        let map = MapFile::new_from_map_str(map_contents);
+        assert_eq!(map.segments_list.len(), 3);
+
+        let code = &map.segments_list[1];
+        assert_eq!(code.name, "CODE");
+        assert_eq!(code.sections_list.len(), 2);
+        assert_eq!(code.sections_list[0].filepath, PathBuf::from("main.o"));
+        assert_eq!(code.sections_list[0].symbols.len(), 2);
+        assert_eq!(code.sections_list[0].symbols[0].size, 8);
+        assert_eq!(code.sections_list[0].symbols[1].size, 0x18);
+        assert_eq!(
+            code.sections_list[1].filepath,
+            PathBuf::from("/usr/share/cc65/lib/nes.lib(copydata.o)")
+        );
+        assert_eq!(code.sections_list[1].vram, 0x8030);
+
+        assert!(map.find_symbol_by_name("__STACKSIZE__").is_none());
+        assert!(map.segments_list[2].sections_list[0].symbols.is_empty());
+    }
+
+    #[test]
+    fn watcom_map() {
+        let map_contents = "\
+Open Watcom Linker Version 2.0 beta Oct  9 2023 02:19:55 (64-bit)
+Created on:       25/01/01 12:00:00
+Executable Image: game.exe
+creating a DOS executable
+
+
+                        +--------------+
+                        |   Segments   |
+                        +--------------+
+
+Segment                Class          Group          Address         Size
+=======                =====          =====          =======         ====
+
+_TEXT                  CODE           AUTO           0000:0000       00000100
+_DATA                  DATA           DGROUP         0010:0000       00000020
+
+
+                        +----------------+
+                        |   Memory Map   |
+                        +----------------+
+
+* = unreferenced symbol
++ = symbol only referenced locally
+
+Address        Symbol
+=======        ======
+
+Module: main.obj(main.c)
+0000:0000      main_
+0000:0040*     helper_
+0010:0000+     _gCounter
+Module: clib3s.lib(printf)
+0000:0080      printf_
+
+
+                        +---------------------+
+                        |   Module Segments   |
+                        +---------------------+
+
+! = 32-bit segment
+c = initialized communal data
+
+Module          Segment               Class           Address         Size
+======          =======               =====           =======         ====
+
+main.c          _TEXT                 CODE            0000:0000       00000080
+                _DATA                 DATA            0010:0000       00000020
+printf          _TEXT                 CODE            0000:0080       00000080
+";
+
+        assert_eq!(
+            MapFile::detect_format(map_contents),
+            Some((MapFormat::Watcom, 1.0))
+        );
+
+        let map = MapFile::new_from_map_str(map_contents);
+        assert_eq!(map.segments_list.len(), 2);
+
+        let text = &map.segments_list[0];
+        assert_eq!(text.selector, Some(0));
+        assert_eq!(text.sections_list.len(), 2);
+        assert_eq!(text.sections_list[0].filepath, PathBuf::from("main.c"));
+        assert_eq!(text.sections_list[0].symbols[1].size, 0x40);
+
+        let data = &map.segments_list[1];
+        assert_eq!(data.selector, Some(0x10));
+        assert_eq!(data.vram, 0x100);
+        assert_eq!(data.sections_list[0].symbols[0].name, "_gCounter");
+
+        let bases = std::collections::HashMap::from([(0x10, 0x2000)]);
+        let map = MapFile::new_from_watcom_map_str_with_segment_bases(map_contents, &bases);
+        let info = map.find_symbol_by_vram(0x2004).0.unwrap();
+        assert_eq!(info.symbol.name, "_gCounter");
+        assert_eq!(info.offset, 4);
+    }
+
+    #[test]
+    fn mwldarm_map() {
+        let map_contents = "\
+# Link map of NitroMain
+  1] NitroMain (func,global) found in crt0.o
+
+# .main
+  02000000 00000000 .text   $a (crt0.o)
+  02000000 00000020 .text   _start (crt0.o)
+  02000020 00000000 .text   $t (main.o)
+  02000020 00000010 .text   NitroMain (main.o)
+  02000030 00000008 .text   Helper (main.o)
+  02000038 00000000 .rodata $d (main.o)
+  02000038 00000008 .rodata sTable (main.o)
+#>02000040          SDK_STATIC_BSS_START (linker command file)
+  02000040 00000010 .bss    sBuffer (main.o)
+
+# .OVY_0
+  021D0000 00000000 .text   $t (ov00.o)
+  021D0000 00000040 .text   Ov0_Init (ov00.o)
+#>021D0040          SDK_OVERLAY.OVY_0.END (linker command file)
+";
+
+        assert_eq!(
+            MapFile::detect_format(map_contents),
+            Some((MapFormat::Mw, 1.0))
+        );
+
+        let map = MapFile::new_from_map_str(map_contents);
+        assert_eq!(map.segments_list.len(), 2);
+
+        let main = &map.segments_list[0];
+        assert_eq!(main.name, "main");
+        assert_eq!(main.vram, 0x02000000);
+        assert_eq!(main.size, 0x50);
+        assert_eq!(main.sections_list.len(), 4);
+        assert_eq!(main.sections_list[1].filepath, PathBuf::from("main.o"));
+        assert_eq!(main.sections_list[1].size, 0x18);
+        assert_eq!(main.sections_list[1].symbols.len(), 2);
+        assert_eq!(main.sections_list[2].section_type, ".rodata");
+
+        let overlay = &map.segments_list[1];
+        assert_eq!(overlay.name, "OVY_0");
+        assert_eq!(overlay.sections_list[0].symbols[0].name, "Ov0_Init");
+        assert!(map.find_symbol_by_name("SDK_OVERLAY.OVY_0.END").is_none());
+    }
+
+    /// Builds a small linked ELF with two objects, `main.c` and `util.c`.
+    fn build_test_elf(is_64: bool, is_little_endian: bool) -> Vec<u8> {
+        fn put(buf: &mut Vec<u8>, value: u64, size: usize, is_little_endian: bool) {
+            let bytes = value.to_le_bytes();
+            if is_little_endian {
+                buf.extend_from_slice(&bytes[..size]);
+            } else {
+                buf.extend(bytes[..size].iter().rev());
+            }
+        }
+        let word = if is_64 { 8 } else { 4 };
+        let (ehsize, phentsize, shentsize, symentsize) = if is_64 {
+            (0x40, 0x38, 0x40, 24)
+        } else {
+            (0x34, 0x20, 0x28, 16)
+        };
+
+        let strtab = b"\0main.c\0func_static\0gBssLocal\0util.c\0helper_local\0main\0util\0";
+        let shstrtab = b"\0.text\0.bss\0.symtab\0.strtab\0.shstrtab\0";
+        // Name offset, value, size, info and section index
+        let symbols: [(u64, u64, u64, u64, u64); 8] = [
+            (0, 0, 0, 0, 0),
+            (1, 0, 0, 0x04, 0xFFF1),
+            (8, 0x80000400, 0x10, 0x02, 1),
+            (20, 0x80000440, 0x8, 0x01, 2),
+            (30, 0, 0, 0x04, 0xFFF1),
+            (37, 0x80000420, 0, 0x02, 1),
+            (50, 0x80000410, 0x10, 0x12, 1),
+            (55, 0x80000430, 0x10, 0x12, 1),
+        ];
+
+        let text_offset = ehsize + phentsize;
+        let symtab_offset = text_offset + 0x40;
+        let strtab_offset = symtab_offset + symbols.len() * symentsize;
+        let shstrtab_offset = strtab_offset + strtab.len();
+        let shoff = shstrtab_offset + shstrtab.len();
+
+        let mut elf = b"\x7FELF".to_vec();
+        elf.extend([
+            if is_64 { 2 } else { 1 },
+            if is_little_endian { 1 } else { 2 },
+            1,
+        ]);
+        elf.resize(0x10, 0);
+        let le = is_little_endian;
+        put(&mut elf, 2, 2, le); // e_type
+        put(&mut elf, 8, 2, le); // e_machine
+        put(&mut elf, 1, 4, le); // e_version
+        put(&mut elf, 0x80000400, word, le); // e_entry
+        put(&mut elf, ehsize as u64, word, le); // e_phoff
+        put(&mut elf, shoff as u64, word, le); // e_shoff
+        put(&mut elf, 0, 4, le); // e_flags
+        for x in [ehsize, phentsize, 1, shentsize, 6, 5] {
+            put(&mut elf, x as u64, 2, le);
+        }
+
+        // PT_LOAD
+        put(&mut elf, 1, 4, le);
+        if is_64 {
+            put(&mut elf, 5, 4, le);
+        }
+        put(&mut elf, text_offset as u64, word, le);
+        put(&mut elf, 0x80000400, word, le);
+        put(&mut elf, 0x1000, word, le);
+        put(&mut elf, 0x40, word, le);
+        put(&mut elf, 0x60, word, le);
+        if !is_64 {
+            put(&mut elf, 5, 4, le);
+        }
+        put(&mut elf, 0x10, word, le);
+
+        elf.resize(symtab_offset, 0);
+        for (name, value, size, info, shndx) in symbols {
+            put(&mut elf, name, 4, le);
+            if is_64 {
+                put(&mut elf, info, 1, le);
+                put(&mut elf, 0, 1, le);
+                put(&mut elf, shndx, 2, le);
+                put(&mut elf, value, 8, le);
+                put(&mut elf, size, 8, le);
+            } else {
+                put(&mut elf, value, 4, le);
+                put(&mut elf, size, 4, le);
+                put(&mut elf, info, 1, le);
+                put(&mut elf, 0, 1, le);
+                put(&mut elf, shndx, 2, le);
+            }
+        }
+        elf.extend_from_slice(strtab);
+        elf.extend_from_slice(shstrtab);
+
+        // Name, type, flags, address, offset, size, link and entry size
+        let sections = [
+            (0, 0, 0, 0, 0, 0, 0, 0),
+            (1, 1, 6, 0x80000400, text_offset, 0x40, 0, 0),
+            (7, 8, 3, 0x80000440, symtab_offset, 0x20, 0, 0),
+            (
+                12,
+                2,
+                0,
+                0,
+                symtab_offset,
+                symbols.len() * symentsize,
+                4,
+                symentsize,
+            ),
+            (20, 3, 0, 0, strtab_offset, strtab.len(), 0, 0),
+            (28, 3, 0, 0, shstrtab_offset, shstrtab.len(), 0, 0),
+        ];
+        for (name, sh_type, flags, addr, offset, size, link, entsize) in sections {
+            put(&mut elf, name, 4, le);
+            put(&mut elf, sh_type, 4, le);
+            put(&mut elf, flags, word, le);
+            put(&mut elf, addr, word, le);
+            put(&mut elf, offset as u64, word, le);
+            put(&mut elf, size as u64, word, le);
+            put(&mut elf, link, 4, le);
+            put(&mut elf, 0, 4, le);
+            put(&mut elf, 4, word, le);
+            put(&mut elf, entsize as u64, word, le);
+        }
+
+        elf
+    }
+
+    #[test]
+    fn mapfile_from_elf() {
+        for (is_64, is_little_endian) in [(false, false), (true, true)] {
+            let map = MapFile::new_from_elf(&build_test_elf(is_64, is_little_endian));
+            assert_eq!(map.segments_list.len(), 2);
+
+            let text = &map.segments_list[0];
+            assert_eq!(text.name, ".text");
+            assert_eq!(text.vrom, Some(0x1000));
+            assert_eq!(text.sections_list.len(), 2);
+            assert_eq!(text.sections_list[0].filepath, PathBuf::from("main.c"));
+            assert_eq!(text.sections_list[0].symbols[1].name, "main");
+            assert_eq!(text.sections_list[1].filepath, PathBuf::from("util.c"));
+            assert_eq!(text.sections_list[1].symbols[0].size, 0x10);
+
+            let info = map.find_symbol_by_vrom(0x1034).0.unwrap();
+            assert_eq!(info.symbol.name, "util");
+            assert_eq!(info.offset, 4);
+
+            let bss = &map.segments_list[1];
+            assert_eq!(bss.vrom, None);
+            assert_eq!(bss.sections_list[0].symbols[0].name, "gBssLocal");
+            assert_eq!(bss.sections_list[0].symbols[0].size, 8);
+        }
+
+        assert!(MapFile::try_new_from_elf(b"not an elf").is_err());
+
+        // Section headers smaller than the ones of the ELF class
+        let mut elf = build_test_elf(true, true);
+        elf[0x3A] = 0x10;
+        assert!(MapFile::try_new_from_elf(&elf).is_err());
+
+        // Only the section headers that fit on the file are read
+        let mut elf = build_test_elf(true, true);
+        elf[0x3C] = 0xFF;
+        elf[0x3D] = 0xFF;
+        assert_eq!(MapFile::new_from_elf(&elf).segments_list.len(), 2);
+    }
+
+    #[test]
+    fn enrich_map_from_elf() {
+        let map_contents = "\
+Linker script and memory map
+
+.text           0x0000000080000400       0x40
+ .text          0x0000000080000400       0x20 build/src/main.o
+                0x0000000080000400                func_static
+ .text          0x0000000080000420       0x20 build/src/util.o
+                0x0000000080000430                util
+
+.bss            0x0000000080000440       0x20
+ .bss           0x0000000080000440       0x20 build/src/main.o
+                0x0000000080000448                gBssGlobal
+";
+        let elf = build_test_elf(false, false);
+
+        let mut map = MapFile::new_from_gnu_map_str(map_contents);
+        let mismatches = map.enrich_from_elf(&elf);
+        let kinds: Vec<(&str, ElfMismatchKind)> = mismatches
+            .iter()
+            .map(|x| (x.symbol_name.as_str(), x.kind))
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                (
+                    "func_static",
+                    ElfMismatchKind::SizeMismatch {
+                        map_size: 0x20,
+                        elf_size: 0x10
+                    }
+                ),
+                ("main", ElfMismatchKind::MissingFromMap),
+                ("gBssGlobal", ElfMismatchKind::MissingFromElf),
+                ("gBssLocal", ElfMismatchKind::InsertedSymbol),
+                ("helper_local", ElfMismatchKind::InsertedSymbol),
+            ]
+        );
+
+        let text = &map.segments_list[0];
+        assert_eq!(text.sections_list[0].symbols[0].size, 0x10);
+        let util_symbols = &text.sections_list[1].symbols;
+        assert_eq!(util_symbols[0].name, "helper_local");
+        assert_eq!(util_symbols[0].size, 0x10);
+        assert_eq!(util_symbols[1].name, "util");
+
+        let bss_symbols = &map.segments_list[1].sections_list[0].symbols;
+        assert_eq!(bss_symbols[0].name, "gBssLocal");
+        assert_eq!(bss_symbols[0].size, 8);
+        assert_eq!(bss_symbols[1].name, "gBssGlobal");
+
+        let mut map = MapFile::new_from_gnu_map_str(&map_contents.replace("0430", "0434"));
+        let mismatches = map.enrich_from_elf(&elf);
+        assert!(mismatches.iter().any(|x| x.symbol_name == "util"
+            && x.kind
+                == ElfMismatchKind::AddressMismatch {
+                    map_vram: 0x80000434,
+                    elf_vram: 0x80000430
+                }));
+    }
+
+    #[test]
+    fn enrich_map_from_linked_elf() {
+        let mut map = MapFile::new_from_map_file(&PathBuf::from("tests/elf/gnuld/x86_64/game.map"));
+        let elf = std::fs::read("tests/elf/gnuld/x86_64/game.elf").unwrap();
+
+        let mismatches = map.enrich_from_elf(&elf);
+        let inserted: Vec<&str> = mismatches
+            .iter()
+            .filter(|x| x.kind == ElfMismatchKind::InsertedSymbol)
+            .map(|x| x.symbol_name.as_str())
+            .collect();
+        assert_eq!(inserted, ["step", "sState", "scale", "sTable", "sCalls"]);
+        assert!(!mismatches
+            .iter()
+            .any(|x| matches!(x.kind, ElfMismatchKind::SizeMismatch { .. })));
+
+        let text = &map.segments_list[0];
+        assert_eq!(text.name, ".text");
+        let main_symbols = &text.sections_list[0].symbols;
+        assert_eq!(main_symbols[0].name, "step");
+        assert_eq!(main_symbols[0].size, 0x16);
+        assert_eq!(main_symbols[1].name, "_start");
+        assert_eq!(main_symbols[1].size, 0x1E);
+
+        let util_symbols = &text.sections_list[1].symbols;
+        assert_eq!(util_symbols[0].name, "scale");
+        assert_eq!(util_symbols[0].size, 0x10);
+        assert_eq!(util_symbols[1].name, "util");
+        assert_eq!(util_symbols[1].size, 0x19);
+
+        let info = map.find_symbol_by_name("sCalls").unwrap();
+        assert_eq!(info.section.filepath, PathBuf::from("build/src/util.o"));
+        assert_eq!(info.symbol.vram, 0x403040);
+        assert_eq!(info.symbol.size, 4);
+    }
+
+    #[test]
+    fn nm_and_objdump_listings() {
+        let nm_contents = "\
+build/src/util.o:80000430 00000010 T util
+build/src/main.o:80000400 00000010 t func_static
+build/src/main.o:80000440 00000008 b gBssLocal
+build/src/util.o:80000420 t helper_local
+build/src/main.o:80000410 00000010 T main
+build/src/main.o:80000444 00000004 A absolute_sym
+";
+
+        assert_eq!(
+            MapFile::detect_format(nm_contents).map(|x| x.0),
+            Some(MapFormat::Nm)
+        );
+        let map = MapFile::new_from_nm_str(nm_contents);
+        assert_eq!(map.segments_list.len(), 2);
+
+        let text = &map.segments_list[0];
+        assert_eq!(text.name, ".text");
+        assert_eq!(text.vram, 0x80000400);
+        assert_eq!(text.size, 0x40);
+        assert_eq!(text.sections_list.len(), 2);
+        assert_eq!(
+            text.sections_list[0].filepath,
+            PathBuf::from("build/src/main.o")
+        );
+        assert_eq!(text.sections_list[0].symbols[1].name, "main");
+        assert_eq!(text.sections_list[1].symbols[0].name, "helper_local");
+        assert_eq!(text.sections_list[1].symbols[0].size, 0x10);
+
+        let info = map.find_symbol_by_vram(0x80000434).0.unwrap();
+        assert_eq!(info.symbol.name, "util");
+        assert_eq!(map.segments_list[1].name, ".bss");
+
+        let objdump_contents = "\
+build/game.elf:     file format elf32-tradbigmips
+
+SYMBOL TABLE:
+80000400 l    d  .text	00000000 .text
+80000440 l    d  .bss	00000000 .bss
+00000000 l    df *ABS*	00000000 main.c
+80000400 l     F .text	00000010 func_static
+80000440 l     O .bss	00000008 gBssLocal
+80000420 l       .text	00000000 helper_local
+80000410 g     F .text	00000010 .hidden main
+80000430 g     F .text	00000010 util
+00000000         *UND*	00000000 puts
+";
+
+        assert_eq!(
+            MapFile::detect_format(objdump_contents).map(|x| x.0),
+            Some(MapFormat::Objdump)
+        );
+        let map = MapFile::new_from_objdump_str(objdump_contents);
+        assert_eq!(map.segments_list.len(), 2);
+
+        let text = &map.segments_list[0];
+        assert_eq!(text.sections_list.len(), 1);
+        assert_eq!(
+            text.sections_list[0].filepath,
+            PathBuf::from("build/game.elf")
+        );
+        let names: Vec<&str> = text.sections_list[0]
+            .symbols
+            .iter()
+            .map(|x| x.name.as_str())
+            .collect();
+        assert_eq!(names, ["func_static", "main", "helper_local", "util"]);
+        assert_eq!(text.sections_list[0].symbols[2].size, 0x10);
+        assert_eq!(map.segments_list[1].sections_list[0].symbols[0].size, 8);
+    }
+
+    #[test]
+    fn mapfile_from_csv() {
+        let map_contents = "\
+Linker script and memory map
+
+.text           0x0000000080000400       0x40
+ .text          0x0000000080000400       0x20 build/src/main.o
+                0x0000000080000400                func_static
+                0x0000000080000410                main
+ .text          0x0000000080000420       0x20 build/src/util.o
+                0x0000000080000420                util
+";
+        let map = MapFile::new_from_gnu_map_str(map_contents);
+        let csv_map = MapFile::new_from_csv_str(
+            &map.to_csv_symbols(),
+            &CsvColumnMapping::mapfile_symbols_csv(),
+        );
+        assert_eq!(csv_map.to_csv_symbols(), map.to_csv_symbols());
+
+        let tsv_contents = "\
+Name\tLocation\tSize\tSegment
+\"func_static\"\t80000400\t16\t.text
+\"main\"\t80000410\t\t.text
+\"util\"\t8000042a\t22\t.text
+\"gBss\"\t80000440\t8\t.bss
+";
+        let mapping = CsvColumnMapping {
+            delimiter: '\t',
+            size: Some(2),
+            section: Some(3),
+            ..CsvColumnMapping::new(0, 1)
+        };
+        let tsv_map = MapFile::new_from_csv_str(tsv_contents, &mapping);
+        assert_eq!(tsv_map.segments_list.len(), 2);
+
+        let text_symbols = &tsv_map.segments_list[0].sections_list[0].symbols;
+        assert_eq!(text_symbols[1].name, "main");
+        assert_eq!(text_symbols[1].size, 0x1A);
+        assert_eq!(text_symbols[2].vram, 0x8000042A);
+        assert_eq!(text_symbols[2].size, 22);
+        assert_eq!(tsv_map.segments_list[1].name, ".bss");
+
+        // Addresses made only of decimal digits are still hexadecimal
+        let digits_contents = "Name,Address\nfunc_static,80000400\nmain,80000410\n";
+        let digits_map = MapFile::new_from_csv_str(digits_contents, &CsvColumnMapping::new(0, 1));
+        let digits_symbols = &digits_map.segments_list[0].sections_list[0].symbols;
+        assert_eq!(digits_symbols[0].vram, 0x80000400);
+        assert_eq!(digits_symbols[0].size, 0x10);
+
+        let detect_mapping = CsvColumnMapping {
+            vram_base: CsvNumberBase::Detect,
+            ..CsvColumnMapping::new(0, 1)
+        };
+        let detect_map = MapFile::new_from_csv_str(digits_contents, &detect_mapping);
+        assert_eq!(
+            detect_map.segments_list[0].sections_list[0].symbols[1].vram,
+            0x80000410
+        );
+
+        let err = MapFile::try_new_from_csv_str(
+            "Name,Address\nmain,80000400\nutil\n",
+            &CsvColumnMapping::new(0, 1),
+        )
+        .unwrap_err();
+        assert_eq!(err.line_number, Some(3));
+        assert!(matches!(err.kind, MapParseErrorKind::MissingColumn(1)));
+    }
+
+    #[test]
+    fn splat_symbol_addrs() {
+        let symbol_addrs_contents = "\
+// Comments and blank lines are skipped
+
+func_80000400 = 0x80000400; // type:func size:0x10 rom:0x1000
+D_80000420 = 0x80000420; // type:data rom:0x1020 dont_allow_addend:true
+gBss = 0x80000440; // size:8
+";
+
+        let entries = SymbolAddrsEntry::parse_list(symbol_addrs_contents).unwrap();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].sym_type.as_deref(), Some("func"));
+        assert_eq!(entries[1].size, None);
+        assert_eq!(entries[1].vrom, Some(0x1020));
+        assert_eq!(entries[2].line_number, 5);
+        assert_eq!(
+            entries[0].to_line(),
+            "func_80000400 = 0x80000400; // type:func size:0x10 rom:0x1000"
+        );
+
+        let map = MapFile::new_from_splat_symbol_addrs_str(symbol_addrs_contents);
+        let info = map.find_symbol_by_vram(0x80000424).0.unwrap();
+        assert_eq!(info.symbol.name, "D_80000420");
+        assert_eq!(info.symbol.size, 0x20);
+
+        assert_eq!(
+            map.to_splat_symbol_addrs(),
+            "\
+func_80000400 = 0x80000400; // size:0x10 rom:0x1000
+D_80000420 = 0x80000420; // size:0x20 rom:0x1020
+gBss = 0x80000440; // size:0x8
+"
+        );
+    }
+
+    #[test]
+    fn check_splat_symbol_addrs() {
+        let map_contents = "\
+Linker script and memory map
+
+.text           0x0000000080000400       0x40 load address 0x0000000000001000
+ .text          0x0000000080000400       0x40 build/src/main.o
+                0x0000000080000400                func_80000400
+                0x0000000080000410                main
+                0x0000000080000420                renamed_func
+                0x0000000080000430                util
+
+.ovl_a          0x0000000080100000       0x20 load address 0x0000000000002000
+ .text          0x0000000080100000       0x20 build/src/ovl_a.o
+                0x0000000080100000                ovl_func
+
+.ovl_b          0x0000000080100000       0x20 load address 0x0000000000003000
+ .text          0x0000000080100000       0x20 build/src/ovl_b.o
+                0x0000000080100000                ovl_func
+";
+        let symbol_addrs_contents = "\
+func_80000400 = 0x80000400; // type:func size:0x10 rom:0x1000
+main = 0x80000414; // type:func size:0x4
+renamed_func = 0x80000420; // size:0x8
+util = 0x80000430; // rom:0x1034
+func_80000420 = 0x80000420;
+func_80000480 = 0x80000480;
+ovl_func = 0x80100000; // rom:0x3000 segment:ovl_b
+";
+        let map = MapFile::new_from_gnu_map_str(map_contents);
+        let entries = SymbolAddrsEntry::parse_list(symbol_addrs_contents).unwrap();
+        let checks = map.check_symbol_addrs(&entries);
+
+        let statuses: Vec<&[SymbolAddrsStatus]> =
+            checks.iter().map(|x| x.statuses.as_slice()).collect();
+        assert_eq!(
+            statuses,
+            [
+                &[SymbolAddrsStatus::Matching][..],
+                &[
+                    SymbolAddrsStatus::AddressMismatch {
+                        map_vram: 0x80000410
+                    },
+                    SymbolAddrsStatus::SizeMismatch { map_size: 0x10 },
+                ],
+                &[SymbolAddrsStatus::SizeMismatch { map_size: 0x10 }],
+                &[SymbolAddrsStatus::RomMismatch {
+                    map_vrom: Some(0x1030)
+                }],
+                &[SymbolAddrsStatus::Absent],
+                &[SymbolAddrsStatus::Absent],
+                &[SymbolAddrsStatus::Matching],
+            ]
+        );
+        assert_eq!(
+            checks[1].to_string(),
+            "2:main: address is 0x80000410 on the map, size is 0x10 on the map"
+        );
+        assert_eq!(
+            checks[6].map_symbol.as_ref().unwrap().section.filepath,
+            PathBuf::from("build/src/ovl_b.o")
+        );
+        assert_eq!(
+            checks[4].map_symbol.as_ref().unwrap().symbol.name,
+            "renamed_func"
+        );
+        assert!(checks[5].map_symbol.is_none());
+    }
+
+    /// Every map under `tests/maps`.
+    fn test_map_paths() -> Vec<PathBuf> {
+        fn map_paths(dir: PathBuf, paths: &mut Vec<PathBuf>) {
+            for entry in std::fs::read_dir(dir).unwrap() {
+                let path = entry.unwrap().path();
+                if path.is_dir() {
+                    map_paths(path, paths);
+                } else {
+                    paths.push(path);
+                }
+            }
+        }
+
+        let mut paths = Vec::new();
+        map_paths(PathBuf::from("tests/maps"), &mut paths);
+        assert!(!paths.is_empty());
+        paths.sort();
+        paths
+    }
+
+    #[test]
+    fn test_maps_match_outputs() {
+        // The same outputs `tests/update_outputs.py` generates
+        for map_path in test_map_paths() {
+            let output_path =
+                PathBuf::from("tests/output").join(map_path.strip_prefix("tests/maps").unwrap());
+            let map = MapFile::new_from_map_file(&map_path);
+
+            // The Python frontend names the second column differently
+            let expected_csv = std::fs::read_to_string(output_path.with_extension("csv")).unwrap();
+            let csv = map.to_csv(true, true);
+            assert_eq!(
+                csv.split_once('\n').unwrap().1,
+                expected_csv.split_once('\n').unwrap().1,
+                "{}",
+                map_path.display()
+            );
+
+            let expected_symbols_csv =
+                std::fs::read_to_string(output_path.with_extension("symbols.csv")).unwrap();
+            assert_eq!(
+                map.to_csv_symbols(),
+                expected_symbols_csv,
+                "{}",
+                map_path.display()
+            );
+        }
+    }
+
+    #[test]
+    fn mapfile_from_reader() {
+        // The bogus entry before the header is discarded, like when parsing
+        // from a string.
+        let map_contents = "\
+Archive member included to satisfy reference by file (symbol)
+
+bogus_entry     0xZZZZ       0x10
+
+Linker script and memory map
+
+.text           0x0000000080000400       0x20 load address 0x0000000000001000
+ .text          0x0000000080000400       0x20 build/src/main.o
+                0x0000000080000400                func_static
+                0x0000000080000410                main
+";
+
+        let map = MapFile::from_reader(map_contents.as_bytes());
+        assert_eq!(map.segments_list.len(), 1);
+        assert_eq!(
+            map.to_csv_symbols(),
+            MapFile::new_from_map_str(map_contents).to_csv_symbols()
+        );
+
+        // Without the header, the bogus entry is reported
+        let header_start = map_contents.find("Linker script").unwrap();
+        let err = MapFile::try_from_reader(&map_contents.as_bytes()[..header_start]).unwrap_err();
+        assert_eq!(err.line_number, Some(3));
+        assert!(matches!(err.kind, MapParseErrorKind::InvalidHexNumber(_)));
+
+        // Entries in a map cut before the header aren't kept either
+        let preamble = "\
+Archive member included to satisfy reference by file (symbol)
+
+.text           0x0000000080000400       0x20
+ .text          0x0000000080000400       0x20 build/src/main.o
+                0x0000000080000400                func_static
+";
+        let map = MapFile::try_from_reader(preamble.as_bytes()).unwrap();
+        assert!(map.segments_list.is_empty());
+
+        let lld_contents = "     VMA      LMA     Size Align Out     In      Symbol\n\
+80000400     1000       20    10 .text
+80000400     1000       20    10         build/src/main.o:(.text)
+80000400     1000        0     1                 func_static
+80000410     1010       10     1                 main
+";
+        let map = MapFile::from_reader(lld_contents.as_bytes());
+        assert_eq!(map.segments_list[0].sections_list[0].symbols[0].size, 0x10);
+
+        let ld65_contents = "\
+Modules list:
+-------------
+main.o:
+    CODE              Offs=000000  Size=000010  Align=00001  Fill=0000
+
+Segment list:
+-------------
+Name                   Start     End    Size  Align
+----------------------------------------------------
+CODE                  008000  00800F  000010  00001
+";
+        let map = MapFile::from_reader(ld65_contents.as_bytes());
+        assert_eq!(map.segments_list[0].name, "CODE");
+
+        for path in test_map_paths() {
+            let map_contents = std::fs::read_to_string(&path).unwrap();
+            let from_reader = MapFile::new_from_map_file(&path);
+            let from_str = MapFile::new_from_map_str(&map_contents);
+
+            assert_eq!(
+                format!("{:?}", from_reader.segments_list),
+                format!("{:?}", from_str.segments_list),
+                "{}",
+                path.display()
+            );
+            assert_eq!(
+                format!("{:?}", from_reader.parse_warnings),
+                format!("{:?}", from_str.parse_warnings),
+                "{}",
+                path.display()
+            );
+        }
+    }
+
+    /// The regexes the GNU and LLD lexer replaced.
+    struct LineRegexes {
+        gnu_section_alone: regex::Regex,
+        gnu_section_data: regex::Regex,
+        gnu_symbol: regex::Regex,
+        gnu_label: regex::Regex,
+        gnu_fill: regex::Regex,
+        gnu_segment: regex::Regex,
+        gnu_romless_segment: regex::Regex,
+        lld_row: regex::Regex,
+        lld_segment: regex::Regex,
+        lld_fill: regex::Regex,
+        lld_section_data: regex::Regex,
+        lld_label: regex::Regex,
+        lld_symbol: regex::Regex,
+    }
+
+    impl LineRegexes {
+        fn new() -> Self {
+            use regex::Regex;
+
+            let gnu_section_alone = Regex::new(r"^\s+(?P<section>[^*][^\s]+)\s*$").unwrap();
+            let gnu_section_data = Regex::new(r"^\s+(?P<section>([^*][^\s]+)?)\s+(?P<vram>0x[^\s]+)\s+(?P<size>0x[^\s]+)\s+(?P<name>[^\s]+)$").unwrap();
+            let gnu_symbol = Regex::new(r"^\s+(?P<vram>0x[^\s]+)\s+(?P<name>[^\s]+)$").unwrap();
+            let gnu_label = Regex::new(r"(?P<name>\.?L[0-9A-F]{8})$").unwrap();
+            let gnu_fill = Regex::new(r"^\s+(?P<fill>\*[^\s\*]+\*)\s+(?P<vram>0x[^\s]+)\s+(?P<size>0x[^\s]+)\s+(?P<fillValue>[0-9a-zA-Z]*)$").unwrap();
+            let gnu_segment = Regex::new(r"(?P<name>([^\s]+)?)\s+(?P<vram>0x[^\s]+)\s+(?P<size>0x[^\s]+)\s+(?P<loadaddress>(load address)?)\s+(?P<vrom>0x[^\s]+)$").unwrap();
+            let gnu_romless_segment =
+                Regex::new(r"(?P<name>([^\s]+)?)\s+(?P<vram>0x[^\s]+)\s+(?P<size>0x[^\s]+)$")
+                    .unwrap();
+
+            let lld_row = Regex::new(r"^\s*(?P<vram>[0-9a-fA-F]+)\s+(?P<vrom>[0-9a-fA-F]+)\s+(?P<size>[0-9a-fA-F]+)\s+(?P<align>[0-9a-fA-F]+) ").unwrap();
+            let lld_segment = Regex::new(r"^(?P<name>[^\s]+)$").unwrap();
+            let lld_fill = Regex::new(r"^\s+(?P<expr>\.\s*\+=\s*.+)$").unwrap();
+            let lld_section_data =
+                Regex::new(r"^\s+(?P<name>[^\s]+):\((?P<section>[^\s()]+)\)$").unwrap();
+            let lld_label = Regex::new(r"^\s+(?P<name>\.?L[0-9A-F]{8})$").unwrap();
+            let lld_symbol = Regex::new(r"^\s+(?P<name>[^\s]+)$").unwrap();
+
+            Self {
+                gnu_section_alone,
+                gnu_section_data,
+                gnu_symbol,
+                gnu_label,
+                gnu_fill,
+                gnu_segment,
+                gnu_romless_segment,
+                lld_row,
+                lld_segment,
+                lld_fill,
+                lld_section_data,
+                lld_label,
+                lld_symbol,
+            }
+        }
+    }
+
+    #[test]
+    fn lexer_matches_regexes() {
+        use crate::map_lexer;
+
+        let LineRegexes {
+            gnu_section_alone,
+            gnu_section_data,
+            gnu_symbol,
+            gnu_label,
+            gnu_fill,
+            gnu_segment,
+            gnu_romless_segment,
+            lld_row,
+            lld_segment,
+            lld_fill,
+            lld_section_data,
+            lld_label,
+            lld_symbol,
+        } = LineRegexes::new();
+
+        let paths = test_map_paths();
+
+        let mut contents: Vec<String> = paths
+            .iter()
+            .map(|x| String::from_utf8_lossy(&std::fs::read(x).unwrap()).into_owned())
+            .collect();
+        // Lines the maps don't have, but which the regexes accept or reject
+        // in non-obvious ways.
+        contents.push(
+            [
+                "  *foo",
+                "   *foo 0x1 0x2 name",
+                "  a 0x1 0x2 name",
+                " a 0x1 0x2 name",
+                " 0x1 0x2 name",
+                "  0x1 0x2 name",
+                "  .text 0x1 0x2 name ",
+                "  .text\t0x1\t0x2\tname",
+                " *fill* 0x1 0x2 ",
+                " *fill* 0x1 0x2",
+                " *fill* 0x1 0x2 00",
+                " ** 0x1 0x2 00",
+                " *a*b* 0x1 0x2 00",
+                ".seg 0x1 0x2 load address 0x3",
+                ".seg 0x1 0x2 load  address 0x3",
+                ".seg 0x1 0x2 load\taddress 0x3",
+                "0x1 0x2 load address 0x3",
+                " 0x1 0x2 load address 0x3",
+                "a b 0x1 0x2 0x3",
+                "a b 0x1 0x2  0x3",
+                "0x1 0x2  0x3",
+                " 0x1 0x2  0x3",
+                "0x1 0x2",
+                " 0x1 0x2",
+                "0x 0x2",
+                "x.L80001234",
+                "   .L8000123",
+                "80000400     1000       20    10 .text",
+                "80000400     1000       20    10\t.text",
+                "80000400     1000       20    10z .text",
+                "80000400     1000       20    10 ",
+                "80000400     1000       20    10         . += 0x10",
+                "80000400     1000       20    10         .+=  ",
+                "80000400     1000       20    10         .+= ",
+                "80000400     1000       20    10         a:(b)c:(.text)",
+                "80000400     1000       20    10         a:(.te)xt)",
+                "80000400     1000       20    10         a(.text)",
+                "80000400     1000       20    10         :(.text)",
+                "80000400     1000       20    10         a:()",
+                "80000400     1000       20    10         .LABCDEF01",
+                "80000400     1000       20    10         LABCDEF012",
+                "\u{3000}\u{3000}.text 0x1 0x2 name",
+                "\u{3000}*foo 0x1 0x2 name",
+            ]
+            .join("\n"),
+        );
+
+        fn group<'a>(caps: &Option<regex::Captures<'a>>, name: &str) -> Option<&'a str> {
+            caps.as_ref()
+                .map(|x| x.name(name).map_or("", |x| x.as_str()))
+        }
+
+        for map_contents in &contents {
+            for line in map_contents.lines() {
+                let expected = gnu_section_alone.captures(line);
+                assert_eq!(
+                    map_lexer::gnu_section_alone(line),
+                    group(&expected, "section"),
+                    "{line:?}"
+                );
+
+                let expected = gnu_section_data.captures(line);
+                let lexed = map_lexer::gnu_section_data(line);
+                assert_eq!(
+                    lexed.map(|x| (x.section, x.vram, x.size, x.name)),
+                    expected.as_ref().map(|_| (
+                        group(&expected, "section").unwrap(),
+                        group(&expected, "vram").unwrap(),
+                        group(&expected, "size").unwrap(),
+                        group(&expected, "name").unwrap()
+                    )),
+                    "{line:?}"
+                );
+
+                let expected = gnu_symbol.captures(line);
+                let lexed = map_lexer::gnu_symbol(line);
+                assert_eq!(
+                    lexed.map(|x| (x.vram, x.name)),
+                    expected.as_ref().map(|_| (
+                        group(&expected, "vram").unwrap(),
+                        group(&expected, "name").unwrap()
+                    )),
+                    "{line:?}"
+                );
+
+                assert_eq!(
+                    map_lexer::gnu_is_label(line),
+                    gnu_label.is_match(line),
+                    "{line:?}"
+                );
+
+                let expected = gnu_fill.captures(line);
+                assert_eq!(
+                    map_lexer::gnu_fill(line).map(|x| x.size),
+                    group(&expected, "size"),
+                    "{line:?}"
+                );
+
+                let mut expected = gnu_segment.captures(line);
+                let mut expected_vrom = group(&expected, "vrom");
+                if expected.is_none() {
+                    expected = gnu_romless_segment.captures(line);
+                }
+                if expected.is_none() {
+                    expected_vrom = None;
+                }
+                let lexed = map_lexer::gnu_segment(line);
+                assert_eq!(
+                    lexed.map(|x| (x.name, x.vram, x.size, x.vrom)),
+                    expected.as_ref().map(|_| (
+                        group(&expected, "name").unwrap(),
+                        group(&expected, "vram").unwrap(),
+                        group(&expected, "size").unwrap(),
+                        expected_vrom
+                    )),
+                    "{line:?}"
+                );
+
+                let expected = lld_row.captures(line);
+                let lexed = map_lexer::lld_row(line);
+                assert_eq!(
+                    lexed
+                        .as_ref()
+                        .map(|x| (x.vram, x.vrom, x.size, x.align, x.rest)),
+                    expected.as_ref().map(|x| (
+                        &x["vram"],
+                        &x["vrom"],
+                        &x["size"],
+                        &x["align"],
+                        &line[x.get(0).unwrap().len()..]
+                    )),
+                    "{line:?}"
+                );
+
+                let Some(row) = lexed else {
+                    continue;
+                };
+                let rest = row.rest;
+
+                let expected = lld_segment.captures(rest);
+                assert_eq!(
+                    map_lexer::lld_segment(rest),
+                    group(&expected, "name"),
+                    "{line:?}"
+                );
+
+                assert_eq!(
+                    map_lexer::lld_is_fill(rest),
+                    lld_fill.is_match(rest),
+                    "{line:?}"
+                );
+
+                let expected = lld_section_data.captures(rest);
+                assert_eq!(
+                    map_lexer::lld_section_data(rest).map(|x| (x.name, x.section)),
+                    expected.as_ref().map(|_| (
+                        group(&expected, "name").unwrap(),
+                        group(&expected, "section").unwrap()
+                    )),
+                    "{line:?}"
+                );
+
+                assert_eq!(
+                    map_lexer::lld_is_label(rest),
+                    lld_label.is_match(rest),
+                    "{line:?}"
+                );
+
+                let expected = lld_symbol.captures(rest);
+                assert_eq!(
+                    map_lexer::lld_symbol(rest),
+                    group(&expected, "name"),
+                    "{line:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    #[ignore = "benchmark, run with `cargo test --release -- --ignored --nocapture`"]
+    fn lexer_is_faster_than_regexes() {
+        use crate::map_lexer;
+        use std::time::{Duration, Instant};
+
+        // Classifies the lines in the same order the GNU and LLD parsers do,
+        // returning which kind of entry each line is.
+        fn classify_with_regexes(regexes: &LineRegexes, line: &str) -> usize {
+            if let Some(row) = regexes.lld_row.captures(line) {
+                let rest = &line[row.get(0).unwrap().len()..];
+                if regexes.lld_segment.captures(rest).is_some() {
+                    1
+                } else if regexes.lld_fill.is_match(rest) {
+                    2
+                } else if regexes.lld_section_data.captures(rest).is_some() {
+                    3
+                } else if regexes.lld_label.is_match(rest) {
+                    4
+                } else if regexes.lld_symbol.captures(rest).is_some() {
+                    5
+                } else {
+                    6
+                }
+            } else if regexes.gnu_label.is_match(line) {
+                7
+            } else if regexes.gnu_symbol.captures(line).is_some() {
+                8
+            } else if regexes.gnu_section_data.captures(line).is_some() {
+                9
+            } else if regexes.gnu_section_alone.captures(line).is_some() {
+                10
+            } else if regexes.gnu_segment.captures(line).is_some()
+                || regexes.gnu_romless_segment.captures(line).is_some()
+            {
+                11
+            } else if regexes.gnu_fill.captures(line).is_some() {
+                12
+            } else {
+                0
+            }
+        }
+
+        fn classify_with_lexer(line: &str) -> usize {
+            if let Some(row) = map_lexer::lld_row(line) {
+                let rest = row.rest;
+                if map_lexer::lld_segment(rest).is_some() {
+                    1
+                } else if map_lexer::lld_is_fill(rest) {
+                    2
+                } else if map_lexer::lld_section_data(rest).is_some() {
+                    3
+                } else if map_lexer::lld_is_label(rest) {
+                    4
+                } else if map_lexer::lld_symbol(rest).is_some() {
+                    5
+                } else {
+                    6
+                }
+            } else if map_lexer::gnu_is_label(line) {
+                7
+            } else if map_lexer::gnu_symbol(line).is_some() {
+                8
+            } else if map_lexer::gnu_section_data(line).is_some() {
+                9
+            } else if map_lexer::gnu_section_alone(line).is_some() {
+                10
+            } else if map_lexer::gnu_segment(line).is_some() {
+                11
+            } else if map_lexer::gnu_fill(line).is_some() {
+                12
+            } else {
+                0
+            }
+        }
+
+        fn best_of<T>(mut f: impl FnMut() -> T) -> (T, Duration) {
+            let mut best = Duration::MAX;
+            let mut result = f();
+            for _ in 0..10 {
+                let start = Instant::now();
+                result = std::hint::black_box(f());
+                best = best.min(start.elapsed());
+            }
+            (result, best)
+        }
+
+        let regexes = LineRegexes::new();
+        let contents: Vec<String> = test_map_paths()
+            .iter()
+            .filter(|x| x.starts_with("tests/maps/gnuld") || x.starts_with("tests/maps/ld.lld"))
+            .map(|x| std::fs::read_to_string(x).unwrap())
+            .collect();
+        let lines: Vec<&str> = contents.iter().flat_map(|x| x.lines()).collect();
+
+        let (expected, regexes_time) = best_of(|| {
+            lines
+                .iter()
+                .map(|x| classify_with_regexes(&regexes, x))
+                .collect::<Vec<_>>()
+        });
+        let (lexed, lexer_time) = best_of(|| {
+            lines
+                .iter()
+                .map(|x| classify_with_lexer(x))
+                .collect::<Vec<_>>()
+        });
+
+        println!(
+            "{} lines: regexes {:?}, lexer {:?}",
+            lines.len(),
+            regexes_time,
+            lexer_time
+        );
+        assert_eq!(lexed, expected);
+        assert!(lexer_time < regexes_time);
+    }
+
+    #[test]
+    fn segments_are_parsed_independently() {
+        // Parsed in one chunk per segment with the `parallel` feature
+        let map_contents = "\
+Memory Configuration
+
+Linker script and memory map
+
+.main           0x80000400       0x20 load address 0x00001000
+ .text          0x80000400       0x20 build/src/main.o
+                0x80000400                main
+                0x80000410                func
+.very_long_segment_name_that_does_not_fit
+                0x80000500       0x10
+ .data          0x80000500       0x10 build/src/data.o
+                0x80000500                some_data
+.bss            0x80000600       0x10
+ .bss           0x80000600       0x10 build/src/data.o
+                0x80000600                some_bss
+.last           0x80000700        0x8
+ .data          0x80000700        0x8 build/src/last.o
+                0x80000700                last_data
+";
+
+        let map = MapFile::new_from_gnu_map_str(map_contents);
+        let names: Vec<&str> = map.segments_list.iter().map(|x| x.name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                ".main",
+                ".very_long_segment_name_that_does_not_fit",
+                ".bss",
+                ".last"
+            ]
+        );
+        // Segments without a load address continue the rom of the previous ones
+        let vroms: Vec<Option<u64>> = map.segments_list.iter().map(|x| x.vrom).collect();
+        assert_eq!(
+            vroms,
+            [Some(0x1000), Some(0x1020), Some(0x1030), Some(0x1030)]
+        );
+        assert_eq!(
+            map.find_symbol_by_name("last_data").unwrap().symbol.vrom,
+            Some(0x1030)
+        );
+        assert_eq!(map.find_symbol_by_name("func").unwrap().symbol.size, 0x10);
+
+        // The first error of the map is reported
+        let broken = map_contents
+            .replace(
+                "0x80000600                some_bss",
+                "0x8000060Z                some_bss",
+            )
+            .replace(
+                "0x80000700                last_data",
+                "0x8000070Z                last_data",
+            );
+        let err = MapFile::try_new_from_gnu_map_str(&broken).unwrap_err();
+        assert_eq!(err.line_number, Some(15));
+
+        let lld_contents = "     VMA      LMA     Size Align Out     In      Symbol\n\
+80000400     1000       20    10 .text
+80000400     1000       20    10         build/src/main.o:(.text)
+80000400     1000        0     1                 main
+80000410     1010       10     1                 func
+80000500     1020       10    10 .data
+80000500     1020       10    10         build/src/data.o:(.data)
+80000500     1020        0     1                 some_data
+";
+        let map = MapFile::new_from_lld_map_str(lld_contents);
+        assert_eq!(map.segments_list.len(), 2);
+        assert_eq!(map.segments_list[1].name, ".data");
+        assert_eq!(map.find_symbol_by_name("main").unwrap().symbol.size, 0x10);
+        assert_eq!(
+            map.find_symbol_by_name("some_data").unwrap().symbol.size,
+            0x10
+        );
+    }
+
+    #[test]
+    fn sections_share_strings() {
+        let map_contents = "\
+Linker script and memory map
+
+.main           0x80000400       0x30 load address 0x00001000
+ .text          0x80000400       0x20 build/src/main.o
+                0x80000400                main
+ .data          0x80000420       0x10 build/src/main.o
+                0x80000420                some_data
+";
+
+        let map = MapFile::new_from_gnu_map_str(map_contents);
+        let sections = &map.segments_list[0].sections_list;
+        assert_eq!(sections.len(), 2);
+
+        // Both sections point to the same copy of the path
+        assert_eq!(sections[0].filepath, sections[1].filepath);
+        assert!(std::ptr::eq(
+            sections[0].filepath.as_path(),
+            sections[1].filepath.as_path()
+        ));
+        assert_eq!(sections[0].filepath, PathBuf::from("build/src/main.o"));
+
+        // The interned strings still work like regular strings
+        let sym = &sections[0].symbols[0];
+        assert_eq!(sym.name, "main");
+        assert!(sym.name.starts_with("ma"));
+        assert_eq!(String::from(sym.name.clone()), "main");
+        assert_eq!(sections[1].section_type.as_str(), ".data");
+
+        let filtered = map.filter_by_section_type(".data");
+        assert!(std::ptr::eq(
+            filtered.segments_list[0].sections_list[0]
+                .filepath
+                .as_path(),
+            sections[1].filepath.as_path()
+        ));
+
+        // Every parser shares them, not only the GNU one
+        let psyq_contents = "\
+  Start     Stop   Length      Obj Group            Section name
+ 80010000 8001003F 00000040      text                .rdata   (main.obj)
+ 80010040 8001013F 00000100      text                .text    (main.obj)
+
+  Address  Names in address order
+
+ 80010040 main
+";
+        let map = MapFile::new_from_psyq_map_str(psyq_contents);
+        let sections = &map.segments_list[0].sections_list;
+        assert_eq!(sections.len(), 2);
+        assert!(std::ptr::eq(
+            sections[0].filepath.as_path(),
+            sections[1].filepath.as_path()
+        ));
+    }
+
+    #[test]
+    fn map_cache_roundtrip() {
+        let dir = std::env::temp_dir().join(format!("mapfile_parser_cache_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let cache_path = dir.join("map.cache");
+
+        let map_path = PathBuf::from("tests/maps/gnuld/misc/w0_000.map");
+        let map = MapFile::new_from_map_file(&map_path);
+        map.save_cache(&cache_path, &map_path).unwrap();
+        let cached = MapFile::load_cache(&cache_path, &map_path).unwrap();
+        assert_eq!(cached.segments_list, map.segments_list);
+
+        // A cache made from another map is rejected
+        assert!(MapFile::load_cache(
+            &cache_path,
+            &PathBuf::from("tests/maps/gnuld/n64/drmario64.cn.map")
+        )
+        .is_none());
+
+        let map_contents = "\
+Linker script and memory map
+
+.main           0x80000400       0x30 load address 0x00001000
+ .text          0x80000400       0x20 build/src/main.o
+                0x80000410                main
+                0x80000400                func
+ .text          0x80000420       0x10 build/src/sub.o
+                0x80000420                sub
+";
+        let map_path = dir.join("test.map");
+        std::fs::write(&map_path, map_contents).unwrap();
+        let _ = std::fs::remove_file(&cache_path);
+
+        // The first call writes the cache, the second one reads it
+        let map = MapFile::new_from_map_file_cached(&map_path, &cache_path);
+        assert_eq!(map.parse_warnings.len(), 1);
+        let cached = MapFile::load_cache(&cache_path, &map_path).unwrap();
+        assert_eq!(cached.segments_list, map.segments_list);
+        assert_eq!(cached.parse_warnings, map.parse_warnings);
+        assert!(std::ptr::eq(
+            cached.segments_list[0].sections_list[0]
+                .section_type
+                .as_str(),
+            cached.segments_list[0].sections_list[1]
+                .section_type
+                .as_str()
+        ));
+
+        // Editing the map invalidates the cache
+        std::fs::write(&map_path, map_contents.replace("sub", "other")).unwrap();
+        assert!(MapFile::load_cache(&cache_path, &map_path).is_none());
+        let map = MapFile::new_from_map_file_cached(&map_path, &cache_path);
+        assert!(map.find_symbol_by_name("other").is_some());
+        assert!(MapFile::load_cache(&cache_path, &map_path).is_some());
+
+        // Caches written by other versions of the crate are rejected
+        let data = std::fs::read(&cache_path).unwrap();
+        let version = env!("CARGO_PKG_VERSION").as_bytes();
+        let version_pos = data
+            .windows(version.len())
+            .position(|x| x == version)
+            .unwrap();
+        let mut other_version = data.clone();
+        other_version[version_pos] ^= 1;
+        std::fs::write(&cache_path, &other_version).unwrap();
+        assert!(MapFile::load_cache(&cache_path, &map_path).is_none());
+
+        // Truncated caches are rejected instead of misread
+        for len in [0, 8, 20, data.len() / 2, data.len() - 1] {
+            std::fs::write(&cache_path, &data[..len]).unwrap();
+            assert!(MapFile::load_cache(&cache_path, &map_path).is_none());
+        }
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn json_document_roundtrip() {
+        let map_contents = "\
+Linker script and memory map
+
+.main           0x80000400       0x30 load address 0x00001000
+ .text          0x80000400       0x20 build/src/main.o
+                0x80000410                main
+                0x80000400                func
+ .data          0x80000420       0x10 build/src/main.o
+                0x80000420                some_data
+";
+        let map = MapFile::new_from_gnu_map_str(map_contents);
+        assert_eq!(map.parse_warnings.len(), 1);
+
+        let document = map.to_json_document();
+        assert!(document.contains("\"format_version\": 2"));
+        assert!(document.contains("\"kind\": \"unsorted_symbol\""));
+
+        let loaded = MapFile::from_json_document(&document);
+        assert_eq!(loaded.segments_list, map.segments_list);
+        assert_eq!(loaded.parse_warnings, map.parse_warnings);
+
+        // Version 1 is the layout of `jsonify`, with or without `--machine`
+        let legacy = r#"{
+    "segments": [
+        {
+            "name": ".main",
+            "vram": "0x80000400",
+            "size": "0x30",
+            "vrom": "0x001000",
+            "files": [
+                {
+                    "filepath": "build/src/main.o",
+                    "sectionType": ".text",
+                    "vram": 2147484672,
+                    "size": 32,
+                    "vrom": 4096,
+                    "symbols": [
+                        {
+                            "name": "main",
+                            "vram": "0x80000400",
+                            "size": "0x20",
+                            "vrom": null
+                        }
+                    ]
+                }
+            ]
+        }
+    ]
+}"#;
+        let migrated = MapFile::from_json_document(legacy);
+        let segment = &migrated.segments_list[0];
+        assert_eq!(segment.vrom, Some(0x1000));
+        assert_eq!(segment.sections_list[0].section_type, ".text");
+        assert_eq!(segment.sections_list[0].vram, 0x80000400);
+        let sym = &segment.sections_list[0].symbols[0];
+        assert_eq!(sym.name, "main");
+        assert_eq!(sym.size, 0x20);
+        assert_eq!(sym.vrom, None);
+
+        let newer = MapFile::try_from_json_document(r#"{"format_version": 3, "segments": []}"#);
+        assert!(matches!(
+            newer.unwrap_err().kind,
+            MapParseErrorKind::UnsupportedJsonDocumentVersion(3)
+        ));
+
+        let broken = MapFile::try_from_json_document("{\n  \"segments\": [,\n}").unwrap_err();
+        assert!(matches!(
+            broken.kind,
+            MapParseErrorKind::InvalidJsonDocument(_)
+        ));
+        assert_eq!(broken.line_number, Some(2));
+
+        let missing = MapFile::try_from_json_document(r#"{"format_version": 2}"#);
+        assert!(matches!(
+            missing.unwrap_err().kind,
+            MapParseErrorKind::InvalidJsonDocument(_)
+        ));
+    }
+
+    #[test]
+    fn gnu_map_writer_roundtrip() {
+        let map_contents = "\
+Linker script and memory map
+
+.main           0x80000400       0x40 load address 0x00001000
+ .text          0x80000400       0x20 build/src/main.o
+                0x80000400                main
+                0x80000410                func
+ *fill*         0x80000420        0x10 
+ .rodata.cst4.literals
+                0x80000430       0x10 build/src/main.o
+                0x80000430                some_rodata
+
+.a_very_long_segment_name
+                0x80000440       0x10 load address 0x00001040
+ .bss           0x80000440       0x10 build/src/main.o
+                0x80000440                some_bss
+";
+        let map = MapFile::new_from_gnu_map_str(map_contents);
+
+        let written = map.to_gnu_map_string();
+        assert_eq!(MapFile::detect_format(&written).unwrap().0, MapFormat::Gnu);
+        let reparsed = MapFile::new_from_gnu_map_str(&written);
+        assert_eq!(reparsed.segments_list, map.segments_list);
+        assert_eq!(reparsed.segments_list[1].name, ".a_very_long_segment_name");
+        assert!(reparsed.segments_list[0].sections_list[1].is_fill);
+        assert_eq!(
+            reparsed.segments_list[0].sections_list[2].section_type,
+            ".rodata.cst4.literals"
+        );
+
+        // Maps of other formats keep their layout
+        let lld_contents = "     VMA      LMA     Size Align Out     In      Symbol\n\
+80000400     1000       20    10 .text
+80000400     1000       20    10         build/src/main.o:(.text)
+80000400     1000        0     1                 main
+80000410     1010       10     1                 func
+";
+        let mut map = MapFile::new_from_lld_map_str(lld_contents);
+        let reparsed = MapFile::new_from_gnu_map_str(&map.try_to_gnu_map_string().unwrap());
+        let section = &reparsed.segments_list[0].sections_list[0];
+        assert_eq!(reparsed.segments_list[0].vrom, Some(0x1000));
+        assert_eq!(section.filepath, PathBuf::from("build/src/main.o"));
+        assert_eq!(section.symbols.len(), 2);
+        assert_eq!(section.symbols[1].vrom, Some(0x1010));
+        assert_eq!(section.symbols[1].size, 0x10);
+
+        // Filepaths are written as they are, but they can't be parsed back
+        map.segments_list[0].sections_list[0].filepath = "build/src/main file.o".into();
+        assert!(map
+            .to_gnu_map_string()
+            .contains(" 0x20 build/src/main file.o\n"));
+        let err = map.try_to_gnu_map_string().unwrap_err();
+        assert_eq!(err.kind, GnuMapWriteErrorKind::WhitespaceOnFilepath);
+        assert_eq!(err.section_filepath, PathBuf::from("build/src/main file.o"));
+
+        // GNU ld lists empty sections too, but they are dropped when parsing
+        let mut map = MapFile::new_from_gnu_map_str(map_contents);
+        let mut empty_section = map.segments_list[0].sections_list[0].clone();
+        empty_section.size = 0;
+        empty_section.symbols.clear();
+        map.segments_list[0].sections_list.insert(0, empty_section);
+        assert!(map
+            .to_gnu_map_string()
+            .contains(" .text          0x0000000080000400        0x0 build/src/main.o\n"));
+        let err = map.try_to_gnu_map_string().unwrap_err();
+        assert_eq!(err.kind, GnuMapWriteErrorKind::EmptySection);
+        assert_eq!(err.segment_name, ".main");
+
+        // Symbols with whitespace on their names are dropped when parsing
+        let ld64_contents = "\
+# Path: hello
+# Arch: x86_64
+# Object files:
+[  0] linker synthesized
+[  1] main.o
+# Sections:
+# Address\tSize    \tSegment\tSection
+0x100000F70\t0x00000020\t__TEXT\t__text
+0x100000F90\t0x0000000C\t__TEXT\t__cstring
+# Symbols:
+# Address\tSize    \tFile  Name
+0x100000F70\t0x00000020\t[  1] _main
+0x100000F90\t0x0000000C\t[  1] literal string: Hello world
+";
+        let mut map = MapFile::new_from_ld64_map_str(ld64_contents);
+        let written = map.to_gnu_map_string();
+        let reparsed = MapFile::new_from_gnu_map_str(&written);
+        assert_eq!(reparsed.segments_list[0].sections_list.len(), 2);
+        assert!(reparsed.segments_list[0].sections_list[1]
+            .symbols
+            .is_empty());
+        let err = map.try_to_gnu_map_string().unwrap_err();
+        assert_eq!(err.kind, GnuMapWriteErrorKind::WhitespaceOnSymbolName);
+        assert_eq!(err.segment_name, "__TEXT");
+        assert_eq!(err.symbol_name, "literal string: Hello world");
+        assert_eq!(
+            err.to_string(),
+            "__TEXT: main.o (literal string: Hello world): symbol name contains whitespace"
+        );
+
+        // As are jump table labels
+        map.segments_list[0].sections_list[1].symbols[0].name = ".L80001234".into();
+        let err = map.try_to_gnu_map_string().unwrap_err();
+        assert_eq!(err.kind, GnuMapWriteErrorKind::DiscardedSymbolName);
+        map.segments_list[0].sections_list[1].symbols.clear();
+        assert!(map.try_to_gnu_map_string().is_ok());
+
+        map.segments_list[0].sections_list[1].section_type = "__cstring literals".into();
+        let err = map.try_to_gnu_map_string().unwrap_err();
+        assert_eq!(err.kind, GnuMapWriteErrorKind::WhitespaceOnSectionType);
+        assert_eq!(err.section_filepath, PathBuf::from("main.o"));
+
+        map.segments_list[0].name = "__TEXT segment".into();
+        let err = map.try_to_gnu_map_string().unwrap_err();
+        assert_eq!(err.kind, GnuMapWriteErrorKind::WhitespaceOnSegmentName);
+        assert_eq!(err.section_filepath, PathBuf::new());
+    }
+
+    #[test]
+    fn gnu_linker_script() {
+        let map_contents = "\
+Linker script and memory map
+
+.main           0x80000400       0x70 load address 0x00001000
+ .text          0x80000400       0x20 build/src/main.o
+                0x80000400                main
+ *fill*         0x80000420        0x10 
+ .text          0x80000430       0x10 build/lib/mylib.a
+                0x80000430                other
+ .rodata        0x80000450       0x10 build/src/main.o
+                0x80000450                some_rodata
+ .text          0x80000460       0x10 /usr/lib/libm.a(s_atan.o)
+                0x80000460                atan
+
+.main.bss       0x80000470       0x10
+ .bss           0x80000470       0x10 build/src/main.o
+                0x80000470                some_bss
+";
+        let mut map = MapFile::new_from_gnu_map_str(map_contents);
+        map.segments_list[0].sections_list[2].filepath = "build/lib/my lib.a".into();
+        map.segments_list[1].vrom = None;
+
+        let expected = "\
+SECTIONS
+{
+    _mainSegmentRomStart = LOADADDR(.main);
+    .main 0x80000400 : AT(0x00001000)
+    {
+        build/src/main.o(.text);
+        . += 0x10;
+        \"build/lib/my lib.a\"(.text);
+        . = 0x50;
+        build/src/main.o(.rodata);
+        *libm.a:s_atan.o(.text);
+    }
+    _mainSegmentRomEnd = LOADADDR(.main) + SIZEOF(.main);
+
+    .main.bss 0x80000470 (NOLOAD) :
+    {
+        build/src/main.o(.bss);
+    }
+
+    /DISCARD/ :
+    {
+        *(*);
+    }
+}
+";
+        assert_eq!(map.to_gnu_linker_script(), expected);
+    }
 }
@@ -0,0 +1,160 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT */
+
+use std::fmt::Write;
+
+use crate::{mapfile::MapFile, segment::Segment};
+
+impl MapFile {
+    /// Generates a GNU ld linker script which reproduces the layout of this
+    /// map, for bootstrapping a project from an existing binary and its map.
+    ///
+    /// Each segment becomes an output section placed at its vram, with an
+    /// `AT()` load address if it has a rom address. Its sections become input
+    /// section rules, in the same order, selecting the section type of each
+    /// filepath, like `build/src/main.o(.text);`. Archive members, like
+    /// `/usr/lib/libm.a(s_atan.o)`, are selected with the `archive:member`
+    /// syntax instead, like `*libm.a:s_atan.o(.text);`.
+    ///
+    /// Fill sections and gaps between sections are reproduced by moving the
+    /// location counter. Segments where every section is a noload one, like
+    /// `.bss`, are marked as `NOLOAD`.
+    ///
+    /// `_{name}SegmentRomStart` and `_{name}SegmentRomEnd` symbols are defined
+    /// around each segment with a rom address, where `name` is the name of
+    /// the segment without the leading dot. Every input section not listed
+    /// on the map is discarded.
+    pub fn to_gnu_linker_script(&self) -> String {
+        let mut ret = String::new();
+
+        ret.push_str("SECTIONS\n{\n");
+
+        for (index, segment) in self.segments_list.iter().enumerate() {
+            let name = output_section_name(segment, index);
+            let symbol_name = segment_symbol_name(&name);
+
+            if segment.vrom.is_some() {
+                writeln!(
+                    ret,
+                    "    _{}SegmentRomStart = LOADADDR({});",
+                    symbol_name, name
+                )
+                .unwrap();
+            }
+
+            write!(ret, "    {} 0x{:08X}", name, segment.vram).unwrap();
+            if is_noload_segment(segment) {
+                ret.push_str(" (NOLOAD)");
+            }
+            ret.push_str(" :");
+            if let Some(vrom) = segment.vrom {
+                write!(ret, " AT(0x{:08X})", vrom).unwrap();
+            }
+            ret.push_str("\n    {\n");
+
+            // Where the linker will place the next section
+            let mut expected_vram = segment.vram;
+            for section in &segment.sections_list {
+                if section.is_fill {
+                    if section.size > 0 {
+                        writeln!(ret, "        . += 0x{:X};", section.size).unwrap();
+                        expected_vram = expected_vram.saturating_add(section.size);
+                    }
+                    continue;
+                }
+                if section.filepath.as_os_str().is_empty() {
+                    // Nothing to pull it from, the gap will be kept by the
+                    // next section
+                    continue;
+                }
+
+                if section.vram > expected_vram {
+                    writeln!(ret, "        . = 0x{:X};", section.vram - segment.vram).unwrap();
+                }
+
+                writeln!(
+                    ret,
+                    "        {}({});",
+                    input_file_pattern(&section.filepath.to_string_lossy()),
+                    section.section_type
+                )
+                .unwrap();
+                expected_vram = expected_vram.max(section.vram.saturating_add(section.size));
+            }
+
+            ret.push_str("    }\n");
+
+            if segment.vrom.is_some() {
+                writeln!(
+                    ret,
+                    "    _{}SegmentRomEnd = LOADADDR({}) + SIZEOF({});",
+                    symbol_name, name, name
+                )
+                .unwrap();
+            }
+            ret.push('\n');
+        }
+
+        ret.push_str("    /DISCARD/ :\n    {\n        *(*);\n    }\n}\n");
+
+        ret
+    }
+}
+
+fn output_section_name(segment: &Segment, index: usize) -> String {
+    if segment.name.is_empty() || segment.name.contains(char::is_whitespace) {
+        format!(".segment_{}", index)
+    } else {
+        segment.name.clone()
+    }
+}
+
+/// Makes a valid C identifier out of the name of an output section, like
+/// `main` for `.main`.
+fn segment_symbol_name(output_section_name: &str) -> String {
+    output_section_name
+        .trim_start_matches('.')
+        .chars()
+        .map(|x| if x.is_ascii_alphanumeric() { x } else { '_' })
+        .collect()
+}
+
+fn is_noload_segment(segment: &Segment) -> bool {
+    let mut sections = segment.sections_list.iter().filter(|x| !x.is_fill);
+
+    sections.clone().next().is_some() && sections.all(|x| x.is_noload_section())
+}
+
+/// Maps list archive members as `archive(member)`, which linker scripts
+/// select as `archive:member`. The directory of the archive is replaced
+/// with a wildcard, since it usually depends on the toolchain installation.
+fn input_file_pattern(filepath: &str) -> String {
+    if let Some((archive, member)) = filepath.strip_suffix(')').and_then(|x| x.split_once('(')) {
+        let archive_name = archive.rsplit(['/', '\\']).next().unwrap_or(archive);
+
+        if !archive_name.is_empty()
+            && !member.is_empty()
+            && !needs_quotes(archive_name)
+            && !needs_quotes(member)
+        {
+            return format!("*{}:{}", archive_name, member);
+        }
+    }
+
+    quote_if_needed(filepath)
+}
+
+/// Filenames with characters that have a meaning on linker scripts must be
+/// written between quotes.
+fn quote_if_needed(filepath: &str) -> String {
+    if needs_quotes(filepath) {
+        format!("\"{}\"", filepath.replace('"', ""))
+    } else {
+        filepath.to_string()
+    }
+}
+
+fn needs_quotes(name: &str) -> bool {
+    name.chars()
+        .any(|x| x.is_whitespace() || "(),;:*?[]\"".contains(x))
+}
@@ -0,0 +1,543 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT */
+
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    fs::{self, File},
+    io::{self, Read},
+    path::Path,
+};
+
+use crate::{
+    interned::{InternedPath, InternedStr},
+    map_parse_error::MapParseError,
+    map_parse_warning::{MapParseWarning, MapParseWarningKind},
+    mapfile::MapFile,
+    section::Section,
+    segment::Segment,
+    symbol::Symbol,
+};
+
+const CACHE_MAGIC: &[u8; 8] = b"MAPCACHE";
+
+/// Bumped every time the layout of the cache changes, so caches written by
+/// other versions of this crate get discarded instead of misread.
+const CACHE_FORMAT_VERSION: u32 = 3;
+
+/// Also stored on the header, since a new release may parse the same map
+/// differently even if the layout of the cache didn't change.
+const CRATE_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+const FLAG_VROM: u8 = 1 << 0;
+const FLAG_ALIGN: u8 = 1 << 1;
+/// `selector` for segments, `is_fill` for sections and
+/// `nonmatching_sym_exists` for symbols.
+const FLAG_EXTRA: u8 = 1 << 2;
+/// `size_is_unknown` for symbols.
+const FLAG_SIZE_UNKNOWN: u8 = 1 << 3;
+
+impl MapFile {
+    /// Writes this map to `cache_path` in a compact binary format, so it can
+    /// be reloaded with [`MapFile::load_cache`] without parsing the map at
+    /// `map_path` again.
+    ///
+    /// The cache stores the length and a hash of the contents of the map at
+    /// `map_path`, which must be the map this `MapFile` was parsed from.
+    pub fn save_cache(&self, cache_path: &Path, map_path: &Path) -> io::Result<()> {
+        let fingerprint = fingerprint_file(map_path)?;
+
+        fs::write(cache_path, self.encode_cache(fingerprint))
+    }
+
+    /// Loads a map previously written by [`MapFile::save_cache`].
+    ///
+    /// Returns `None` if the cache can't be read, was written by an
+    /// incompatible version of this crate, or if the contents of the map at
+    /// `map_path` changed since the cache was written.
+    pub fn load_cache(cache_path: &Path, map_path: &Path) -> Option<Self> {
+        let fingerprint = fingerprint_file(map_path).ok()?;
+
+        Self::decode_cache(&fs::read(cache_path).ok()?, fingerprint)
+    }
+
+    /// Same as [`MapFile::new_from_map_file`], but reuses the cache at
+    /// `cache_path` if it is up to date with the map, or writes it after
+    /// parsing the map otherwise.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the map can't be read or parsed, or if the cache can't be
+    /// written. Use [`MapFile::try_new_from_map_file_cached`] to handle those
+    /// cases gracefully.
+    #[must_use]
+    pub fn new_from_map_file_cached(map_path: &Path, cache_path: &Path) -> Self {
+        Self::try_new_from_map_file_cached(map_path, cache_path).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Same as [`MapFile::new_from_map_file_cached`], but returns an error
+    /// instead of panicking if the map can't be read or parsed, or if the
+    /// cache can't be written.
+    pub fn try_new_from_map_file_cached(
+        map_path: &Path,
+        cache_path: &Path,
+    ) -> Result<Self, MapParseError> {
+        let fingerprint = fingerprint_file(map_path)?;
+
+        if let Some(m) = fs::read(cache_path)
+            .ok()
+            .and_then(|x| Self::decode_cache(&x, fingerprint))
+        {
+            return Ok(m);
+        }
+
+        let m = Self::try_new_from_map_file(map_path)?;
+        fs::write(cache_path, m.encode_cache(fingerprint))?;
+        Ok(m)
+    }
+
+    fn encode_cache(&self, map_fingerprint: MapFingerprint) -> Vec<u8> {
+        let mut strings = StringTable::default();
+        let mut body = Vec::new();
+
+        write_uint(&mut body, self.segments_list.len() as u64);
+        for segment in &self.segments_list {
+            write_uint(&mut body, strings.index(&segment.name));
+            write_uint(&mut body, segment.vram);
+            write_uint(&mut body, segment.size);
+            write_optionals(
+                &mut body,
+                segment.vrom,
+                segment.align,
+                flag_if(segment.selector.is_some(), FLAG_EXTRA),
+            );
+            if let Some(selector) = segment.selector {
+                write_uint(&mut body, selector);
+            }
+
+            write_uint(&mut body, segment.sections_list.len() as u64);
+            for section in &segment.sections_list {
+                write_uint(&mut body, strings.index(section.filepath.to_string_lossy()));
+                write_uint(&mut body, section.vram);
+                write_uint(&mut body, section.size);
+                write_uint(&mut body, strings.index(section.section_type.as_str()));
+                write_optionals(
+                    &mut body,
+                    section.vrom,
+                    section.align,
+                    flag_if(section.is_fill, FLAG_EXTRA),
+                );
+
+                write_uint(&mut body, section.symbols.len() as u64);
+                for sym in &section.symbols {
+                    write_uint(&mut body, strings.index(sym.name.as_str()));
+                    write_uint(&mut body, sym.vram);
+                    write_uint(&mut body, sym.size);
+                    write_optionals(
+                        &mut body,
+                        sym.vrom,
+                        sym.align,
+                        flag_if(sym.nonmatching_sym_exists, FLAG_EXTRA)
+                            | flag_if(sym.size_is_unknown, FLAG_SIZE_UNKNOWN),
+                    );
+                }
+            }
+        }
+
+        write_uint(&mut body, self.parse_warnings.len() as u64);
+        for warning in &self.parse_warnings {
+            write_uint(&mut body, strings.index(&warning.segment_name));
+            write_uint(
+                &mut body,
+                strings.index(warning.section_filepath.to_string_lossy()),
+            );
+            write_uint(&mut body, strings.index(&warning.symbol_name));
+            body.push(match warning.kind {
+                MapParseWarningKind::SymbolBeforeSection => 0,
+                MapParseWarningKind::UnsortedSymbol => 1,
+                MapParseWarningKind::SectionSizeExceeded => 2,
+                MapParseWarningKind::UnknownSectionIndex => 3,
+                MapParseWarningKind::UnknownSegmentAddress => 4,
+            });
+        }
+
+        let mut data = Vec::with_capacity(body.len() + strings.bytes_len + 64);
+        data.extend_from_slice(CACHE_MAGIC);
+        data.extend_from_slice(&CACHE_FORMAT_VERSION.to_le_bytes());
+        write_uint(&mut data, CRATE_VERSION.len() as u64);
+        data.extend_from_slice(CRATE_VERSION.as_bytes());
+        data.extend_from_slice(&map_fingerprint.len.to_le_bytes());
+        data.extend_from_slice(&map_fingerprint.hash.to_le_bytes());
+
+        // The lengths of every string go first, then all of them together,
+        // so the whole table can be checked to be valid UTF-8 at once
+        write_uint(&mut data, strings.list.len() as u64);
+        for s in &strings.list {
+            write_uint(&mut data, s.len() as u64);
+        }
+        write_uint(&mut data, strings.bytes_len as u64);
+        for s in &strings.list {
+            data.extend_from_slice(s.as_bytes());
+        }
+        data.extend_from_slice(&body);
+
+        data
+    }
+
+    fn decode_cache(data: &[u8], map_fingerprint: MapFingerprint) -> Option<Self> {
+        let mut reader = CacheReader { data, pos: 0 };
+
+        if reader.bytes(CACHE_MAGIC.len())? != CACHE_MAGIC {
+            return None;
+        }
+        if u32::from_le_bytes(reader.array()?) != CACHE_FORMAT_VERSION {
+            return None;
+        }
+        let version_len = reader.len()?;
+        if reader.bytes(version_len)? != CRATE_VERSION.as_bytes() {
+            return None;
+        }
+        let fingerprint = MapFingerprint {
+            len: u64::from_le_bytes(reader.array()?),
+            hash: u64::from_le_bytes(reader.array()?),
+        };
+        if fingerprint != map_fingerprint {
+            return None;
+        }
+
+        let strings_count = reader.count()?;
+        let mut strings = CacheStrings {
+            raw: Vec::with_capacity(strings_count),
+            strs: vec![None; strings_count],
+            paths: vec![None; strings_count],
+        };
+        let mut ends = Vec::with_capacity(strings_count);
+        let mut end = 0usize;
+        for _ in 0..strings_count {
+            end = end.checked_add(reader.len()?)?;
+            ends.push(end);
+        }
+        let blob_len = reader.len()?;
+        if blob_len != end {
+            return None;
+        }
+        let blob = std::str::from_utf8(reader.bytes(blob_len)?).ok()?;
+        let mut start = 0;
+        for end in ends {
+            strings.raw.push(blob.get(start..end)?);
+            start = end;
+        }
+
+        let mut m = Self::new_impl();
+
+        let segments_count = reader.count()?;
+        m.segments_list.reserve(segments_count);
+        for _ in 0..segments_count {
+            let name = strings.raw(reader.len()?)?.to_string();
+            let vram = reader.uint()?;
+            let size = reader.uint()?;
+            let (vrom, align, extra) = reader.optionals()?;
+
+            let mut segment = Segment::new(name, vram, size, vrom, align);
+            if extra & FLAG_EXTRA != 0 {
+                segment.selector = Some(reader.uint()?);
+            }
+
+            let sections_count = reader.count()?;
+            segment.sections_list.reserve(sections_count);
+            for _ in 0..sections_count {
+                let filepath = strings.path(reader.len()?)?;
+                let vram = reader.uint()?;
+                let size = reader.uint()?;
+                let section_type = strings.str(reader.len()?)?;
+                let (vrom, align, extra) = reader.optionals()?;
+                let is_fill = extra & FLAG_EXTRA != 0;
+
+                let mut section =
+                    Section::new_impl(filepath, vram, size, section_type, vrom, align, is_fill);
+
+                let symbols_count = reader.count()?;
+                section.symbols.reserve(symbols_count);
+                for _ in 0..symbols_count {
+                    let name = strings.str(reader.len()?)?;
+                    let vram = reader.uint()?;
+                    let size = reader.uint()?;
+                    let (vrom, align, extra) = reader.optionals()?;
+
+                    let mut sym = Symbol::new(name, vram, size, vrom, align);
+                    sym.nonmatching_sym_exists = extra & FLAG_EXTRA != 0;
+                    sym.size_is_unknown = extra & FLAG_SIZE_UNKNOWN != 0;
+                    section.symbols.push(sym);
+                }
+
+                segment.sections_list.push(section);
+            }
+
+            m.segments_list.push(segment);
+        }
+
+        let warnings_count = reader.count()?;
+        m.parse_warnings.reserve(warnings_count);
+        for _ in 0..warnings_count {
+            let segment_name = strings.raw(reader.len()?)?.to_string();
+            let section_filepath = strings.raw(reader.len()?)?.into();
+            let symbol_name = strings.raw(reader.len()?)?.to_string();
+            let kind = match reader.bytes(1)?[0] {
+                0 => MapParseWarningKind::SymbolBeforeSection,
+                1 => MapParseWarningKind::UnsortedSymbol,
+                2 => MapParseWarningKind::SectionSizeExceeded,
+                3 => MapParseWarningKind::UnknownSectionIndex,
+                4 => MapParseWarningKind::UnknownSegmentAddress,
+                _ => return None,
+            };
+
+            m.parse_warnings.push(MapParseWarning::new(
+                segment_name,
+                section_filepath,
+                symbol_name,
+                kind,
+            ));
+        }
+
+        // Trailing garbage means the file is not what we think it is
+        if reader.pos != data.len() {
+            return None;
+        }
+
+        Some(m)
+    }
+}
+
+/// Assigns an index to each distinct string written to the cache, so each
+/// one is only stored once.
+#[derive(Default)]
+struct StringTable<'a> {
+    indices: HashMap<Cow<'a, str>, u64>,
+    list: Vec<Cow<'a, str>>,
+    bytes_len: usize,
+}
+
+impl<'a> StringTable<'a> {
+    fn index(&mut self, s: impl Into<Cow<'a, str>>) -> u64 {
+        let s = s.into();
+        if let Some(index) = self.indices.get(&s) {
+            return *index;
+        }
+
+        let index = self.list.len() as u64;
+        self.bytes_len += s.len();
+        self.list.push(s.clone());
+        self.indices.insert(s, index);
+        index
+    }
+}
+
+/// Writes `value` as an unsigned LEB128 number.
+fn write_uint(data: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        data.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    data.push(value as u8);
+}
+
+/// Returns `flag` if `value` is set, or no flags otherwise.
+fn flag_if(value: bool, flag: u8) -> u8 {
+    if value {
+        flag
+    } else {
+        0
+    }
+}
+
+/// Writes the flags byte followed by the values that are present. `extra`
+/// holds the flags that don't have a value attached.
+fn write_optionals(data: &mut Vec<u8>, vrom: Option<u64>, align: Option<u64>, extra: u8) {
+    let mut flags = extra;
+    if vrom.is_some() {
+        flags |= FLAG_VROM;
+    }
+    if align.is_some() {
+        flags |= FLAG_ALIGN;
+    }
+    data.push(flags);
+
+    if let Some(vrom) = vrom {
+        write_uint(data, vrom);
+    }
+    if let Some(align) = align {
+        write_uint(data, align);
+    }
+}
+
+/// Reads the values written with [`write_uint`] and friends. Every method
+/// returns `None` if the data ends early or is malformed.
+struct CacheReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> CacheReader<'a> {
+    fn bytes(&mut self, len: usize) -> Option<&'a [u8]> {
+        let end = self.pos.checked_add(len)?;
+        let ret = self.data.get(self.pos..end)?;
+        self.pos = end;
+        Some(ret)
+    }
+
+    fn array<const N: usize>(&mut self) -> Option<[u8; N]> {
+        self.bytes(N)?.try_into().ok()
+    }
+
+    fn uint(&mut self) -> Option<u64> {
+        let mut value = 0u64;
+        let mut shift = 0;
+        loop {
+            let byte = *self.data.get(self.pos)?;
+            self.pos += 1;
+
+            value |= u64::from(byte & 0x7F) << shift;
+            if byte & 0x80 == 0 {
+                return Some(value);
+            }
+
+            shift += 7;
+            if shift >= 64 {
+                return None;
+            }
+        }
+    }
+
+    fn len(&mut self) -> Option<usize> {
+        self.uint()?.try_into().ok()
+    }
+
+    /// Reads the amount of items of a list. Since every item takes at least
+    /// a byte, bogus counts are rejected before allocating space for them.
+    fn count(&mut self) -> Option<usize> {
+        let count = self.len()?;
+        (count <= self.data.len() - self.pos).then_some(count)
+    }
+
+    /// Returns the values written with [`write_optionals`], alongside its
+    /// `extra` flags.
+    fn optionals(&mut self) -> Option<(Option<u64>, Option<u64>, u8)> {
+        let flags = self.bytes(1)?[0];
+        if flags & !(FLAG_VROM | FLAG_ALIGN | FLAG_EXTRA | FLAG_SIZE_UNKNOWN) != 0 {
+            return None;
+        }
+
+        let vrom = if flags & FLAG_VROM != 0 {
+            Some(self.uint()?)
+        } else {
+            None
+        };
+        let align = if flags & FLAG_ALIGN != 0 {
+            Some(self.uint()?)
+        } else {
+            None
+        };
+
+        Some((vrom, align, flags & (FLAG_EXTRA | FLAG_SIZE_UNKNOWN)))
+    }
+}
+
+/// The string table of a cache being read.
+///
+/// Strings are only copied out of the cache the first time they are used,
+/// and every later use shares that copy.
+struct CacheStrings<'a> {
+    raw: Vec<&'a str>,
+    strs: Vec<Option<InternedStr>>,
+    paths: Vec<Option<InternedPath>>,
+}
+
+impl<'a> CacheStrings<'a> {
+    fn raw(&self, index: usize) -> Option<&'a str> {
+        self.raw.get(index).copied()
+    }
+
+    fn str(&mut self, index: usize) -> Option<InternedStr> {
+        let raw = self.raw(index)?;
+
+        Some(
+            self.strs[index]
+                .get_or_insert_with(|| InternedStr::from(raw))
+                .clone(),
+        )
+    }
+
+    fn path(&mut self, index: usize) -> Option<InternedPath> {
+        let raw = self.raw(index)?;
+
+        Some(
+            self.paths[index]
+                .get_or_insert_with(|| InternedPath::from(raw))
+                .clone(),
+        )
+    }
+}
+
+/// Length and hash of the contents of a map, used to tell if a cache is
+/// still up to date with it.
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct MapFingerprint {
+    len: u64,
+    hash: u64,
+}
+
+/// Measures and hashes the contents of the file at `path`.
+///
+/// Storing the length alongside the hash means an edit must keep the size of
+/// the map intact and collide on the 64 bits hash to go unnoticed.
+///
+/// The file is processed a word at a time with a multiply-rotate hash in the
+/// style of FxHash, spread over four independent lanes so the multiplications
+/// can overlap. Every step is a bijection of the state of its lane, so
+/// changing a single word of the file always changes the hash. Unlike the std
+/// hashers, the result is stable across platforms and Rust versions.
+fn fingerprint_file(path: &Path) -> io::Result<MapFingerprint> {
+    let mut file = File::open(path)?;
+    let mut buffer = vec![0u8; 256 * 1024];
+    let mut lanes = [0u64; 4];
+    let mut total_len: u64 = 0;
+
+    loop {
+        // Fill the whole buffer so words don't depend on how reads are split
+        let mut read = 0;
+        while read < buffer.len() {
+            match file.read(&mut buffer[read..]) {
+                Ok(0) => break,
+                Ok(x) => read += x,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+        total_len += read as u64;
+
+        let mut blocks = buffer[..read].chunks_exact(32);
+        for block in &mut blocks {
+            for (lane, word) in lanes.iter_mut().zip(block.chunks_exact(8)) {
+                *lane = hash_step(*lane, u64::from_le_bytes(word.try_into().unwrap()));
+            }
+        }
+        for byte in blocks.remainder() {
+            lanes[0] = hash_step(lanes[0], u64::from(*byte));
+        }
+
+        if read < buffer.len() {
+            break;
+        }
+    }
+
+    Ok(MapFingerprint {
+        len: total_len,
+        hash: lanes
+            .iter()
+            .fold(total_len, |hash, lane| hash_step(hash, *lane)),
+    })
+}
+
+fn hash_step(hash: u64, word: u64) -> u64 {
+    (hash.rotate_left(5) ^ word).wrapping_mul(0x51_7C_C1_B7_27_22_0A_95)
+}
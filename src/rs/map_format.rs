@@ -0,0 +1,104 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT */
+
+use std::fmt;
+
+#[cfg(feature = "python_bindings")]
+use pyo3::prelude::*;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// The mapfile formats understood by this crate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+#[cfg_attr(
+    feature = "python_bindings",
+    pyclass(module = "mapfile_parser", eq, eq_int, frozen, hash)
+)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum MapFormat {
+    /// GNU ld
+    Gnu,
+    /// clang ld.lld
+    Lld,
+    /// Metrowerks ld (mwld)
+    Mw,
+    /// Microsoft LINK (`link.exe`) and `lld-link /map`
+    Msvc,
+    /// Apple ld64 (`-map`)
+    Ld64,
+    /// Psy-Q psylink
+    Psyq,
+    /// ARM Compiler armlink (`--map`, `--symbols`)
+    Armlink,
+    /// RGBDS rgblink (`-m`)
+    Rgbds,
+    /// cc65 ld65 (`-m`)
+    Ld65,
+    /// Open Watcom wlink
+    Watcom,
+    /// GNU nm symbol listings (`nm -S --defined-only`)
+    Nm,
+    /// GNU objdump symbol tables (`objdump -t`)
+    Objdump,
+}
+
+impl MapFormat {
+    /// Every supported format, in the order they are tried by the format
+    /// detection.
+    pub const ALL: &'static [MapFormat] = &[
+        MapFormat::Lld,
+        MapFormat::Mw,
+        MapFormat::Msvc,
+        MapFormat::Ld64,
+        MapFormat::Psyq,
+        MapFormat::Armlink,
+        MapFormat::Rgbds,
+        MapFormat::Ld65,
+        MapFormat::Watcom,
+        MapFormat::Nm,
+        MapFormat::Objdump,
+        MapFormat::Gnu,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            MapFormat::Gnu => "GNU ld",
+            MapFormat::Lld => "clang ld.lld",
+            MapFormat::Mw => "Metrowerks ld",
+            MapFormat::Msvc => "Microsoft LINK",
+            MapFormat::Ld64 => "Apple ld64",
+            MapFormat::Psyq => "Psy-Q psylink",
+            MapFormat::Armlink => "ARM armlink",
+            MapFormat::Rgbds => "RGBDS",
+            MapFormat::Ld65 => "cc65 ld65",
+            MapFormat::Watcom => "Open Watcom wlink",
+            MapFormat::Nm => "GNU nm",
+            MapFormat::Objdump => "GNU objdump",
+        }
+    }
+}
+
+impl fmt::Display for MapFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[cfg(feature = "python_bindings")]
+#[allow(non_snake_case)]
+pub(crate) mod python_bindings {
+    use pyo3::prelude::*;
+
+    #[pymethods]
+    impl super::MapFormat {
+        fn getName(&self) -> &'static str {
+            self.name()
+        }
+
+        fn __str__(&self) -> &'static str {
+            self.name()
+        }
+    }
+}
@@ -0,0 +1,444 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT */
+
+//! Hand-written line classifiers for the GNU and LLD map parsers.
+//!
+//! Each function recognizes a single kind of line by looking at its
+//! whitespace separated fields, and accepts exactly the same lines the
+//! regular expressions these parsers used to rely on did. The regex each one
+//! replaces is documented on it.
+//!
+//! Numbers are returned as unparsed text, so the parsers can report malformed
+//! ones with the offending line.
+
+/// A section entry of a GNU map, like
+/// ` .text          0x80000400      0x100 build/src/main.o`.
+///
+/// The section name is empty if it was printed on the previous line.
+pub(crate) struct GnuSectionData<'a> {
+    pub section: &'a str,
+    pub vram: &'a str,
+    pub size: &'a str,
+    pub name: &'a str,
+}
+
+/// A symbol entry of a GNU map, like `                0x80000400                main`.
+pub(crate) struct GnuSymbol<'a> {
+    pub vram: &'a str,
+    pub name: &'a str,
+}
+
+/// A `*fill*` entry of a GNU map.
+pub(crate) struct GnuFill<'a> {
+    pub size: &'a str,
+}
+
+/// A segment entry of a GNU map, like
+/// `.main          0x80000400     0x1000 load address 0x00001000`.
+///
+/// `vrom` is `None` for segments without a load address. The name is empty
+/// if it was printed on the previous line.
+pub(crate) struct GnuSegment<'a> {
+    pub name: &'a str,
+    pub vram: &'a str,
+    pub size: &'a str,
+    pub vrom: Option<&'a str>,
+}
+
+/// The columns every entry of an LLD map starts with.
+pub(crate) struct LldRow<'a> {
+    pub vram: &'a str,
+    pub vrom: &'a str,
+    pub size: &'a str,
+    pub align: &'a str,
+    /// Everything after the align column.
+    pub rest: &'a str,
+}
+
+/// An input section entry of an LLD map, like `build/src/main.o:(.text)`.
+pub(crate) struct LldSectionData<'a> {
+    pub name: &'a str,
+    pub section: &'a str,
+}
+
+/// Regex: `^\s+(?P<section>[^*][^\s]+)\s*$`
+pub(crate) fn gnu_section_alone(line: &str) -> Option<&str> {
+    let trimmed = line.trim_end();
+    let (leading, fields) = split_leading(trimmed);
+    if leading.is_empty() {
+        return None;
+    }
+    let [section] = exact_fields(fields)?;
+
+    section_name_field(line, leading, section)
+}
+
+/// Regex: `^\s+(?P<section>([^*][^\s]+)?)\s+(?P<vram>0x[^\s]+)\s+(?P<size>0x[^\s]+)\s+(?P<name>[^\s]+)$`
+pub(crate) fn gnu_section_data(line: &str) -> Option<GnuSectionData<'_>> {
+    if ends_with_whitespace(line) {
+        return None;
+    }
+    let (leading, fields) = split_leading(line);
+
+    let mut iter = fields.split_whitespace();
+    let first = iter.next()?;
+    let second = iter.next()?;
+    let third = iter.next()?;
+    match (iter.next(), iter.next()) {
+        (None, None) => {
+            // No section name, so the first `\s+` and the second one have
+            // to split the leading whitespace between them.
+            if leading_char_count(leading) < 2 || !is_gnu_number(first) || !is_gnu_number(second) {
+                return None;
+            }
+            Some(GnuSectionData {
+                section: "",
+                vram: first,
+                size: second,
+                name: third,
+            })
+        }
+        (Some(fourth), None) => {
+            if leading.is_empty() || !is_gnu_number(second) || !is_gnu_number(third) {
+                return None;
+            }
+            Some(GnuSectionData {
+                section: section_name_field(line, leading, first)?,
+                vram: second,
+                size: third,
+                name: fourth,
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Regex: `^\s+(?P<vram>0x[^\s]+)\s+(?P<name>[^\s]+)$`
+pub(crate) fn gnu_symbol(line: &str) -> Option<GnuSymbol<'_>> {
+    if ends_with_whitespace(line) {
+        return None;
+    }
+    let (leading, fields) = split_leading(line);
+    if leading.is_empty() {
+        return None;
+    }
+
+    let [vram, name] = exact_fields(fields)?;
+    if !is_gnu_number(vram) {
+        return None;
+    }
+
+    Some(GnuSymbol { vram, name })
+}
+
+/// Jump table labels, like `.L80001234`.
+///
+/// Regex: `(?P<name>\.?L[0-9A-F]{8})$`
+pub(crate) fn gnu_is_label(line: &str) -> bool {
+    let bytes = line.as_bytes();
+    if bytes.len() < 9 {
+        return false;
+    }
+    let (prefix, digits) = bytes.split_at(bytes.len() - 8);
+
+    prefix.last() == Some(&b'L') && digits.iter().all(is_upper_hex_digit)
+}
+
+/// Regex: `^\s+(?P<fill>\*[^\s\*]+\*)\s+(?P<vram>0x[^\s]+)\s+(?P<size>0x[^\s]+)\s+(?P<fillValue>[0-9a-zA-Z]*)$`
+pub(crate) fn gnu_fill(line: &str) -> Option<GnuFill<'_>> {
+    let (leading, fields) = split_leading(line);
+    if leading.is_empty() {
+        return None;
+    }
+
+    let mut iter = fields.split_whitespace();
+    let fill = iter.next()?;
+    let vram = iter.next()?;
+    let size = iter.next()?;
+    match (iter.next(), iter.next()) {
+        // The fill value may be missing, but the whitespace before it is
+        // still required.
+        (None, None) => {
+            if !ends_with_whitespace(line) {
+                return None;
+            }
+        }
+        (Some(fill_value), None) => {
+            if ends_with_whitespace(line) || !fill_value.bytes().all(|x| x.is_ascii_alphanumeric())
+            {
+                return None;
+            }
+        }
+        _ => return None,
+    }
+
+    let fill_inner = fill.strip_prefix('*')?.strip_suffix('*')?;
+    if fill_inner.is_empty() || fill_inner.contains('*') {
+        return None;
+    }
+    if !is_gnu_number(vram) || !is_gnu_number(size) {
+        return None;
+    }
+
+    Some(GnuFill { size })
+}
+
+/// Matches either of these regexes, in order:
+/// - `(?P<name>([^\s]+)?)\s+(?P<vram>0x[^\s]+)\s+(?P<size>0x[^\s]+)\s+(?P<loadaddress>(load address)?)\s+(?P<vrom>0x[^\s]+)$`
+/// - `(?P<name>([^\s]+)?)\s+(?P<vram>0x[^\s]+)\s+(?P<size>0x[^\s]+)$`, which
+///   gives segments without a `vrom`.
+pub(crate) fn gnu_segment(line: &str) -> Option<GnuSegment<'_>> {
+    if ends_with_whitespace(line) {
+        return None;
+    }
+
+    // The fields are matched from the end of the line, since these regexes
+    // aren't anchored to its start.
+    let mut iter = line.split_whitespace().rev();
+    let last = iter.next()?;
+    let second_last = iter.next()?;
+    let third_last = iter.next();
+
+    if let Some(third_last) = third_last.filter(|_| is_gnu_number(last)) {
+        if second_last == "address" && third_last == "load" {
+            // `0x... 0x... load address 0x...`
+            if gap(line, third_last, second_last) == " " {
+                if let (Some(size), Some(vram)) = (iter.next(), iter.next()) {
+                    if is_gnu_number(vram) && is_gnu_number(size) {
+                        if let Some(name) = segment_name_field(line, vram, iter.next()) {
+                            return Some(GnuSegment {
+                                name,
+                                vram,
+                                size,
+                                vrom: Some(last),
+                            });
+                        }
+                    }
+                }
+            }
+        } else if is_gnu_number(second_last)
+            && is_gnu_number(third_last)
+            && leading_char_count(gap(line, second_last, last)) >= 2
+        {
+            // `0x... 0x...  0x...`, where the empty load address has to be
+            // surrounded by whitespace.
+            if let Some(name) = segment_name_field(line, third_last, iter.next()) {
+                return Some(GnuSegment {
+                    name,
+                    vram: third_last,
+                    size: second_last,
+                    vrom: Some(last),
+                });
+            }
+        }
+    }
+
+    if !is_gnu_number(second_last) || !is_gnu_number(last) {
+        return None;
+    }
+
+    Some(GnuSegment {
+        name: segment_name_field(line, second_last, third_last)?,
+        vram: second_last,
+        size: last,
+        vrom: None,
+    })
+}
+
+/// Regex: `^\s*(?P<vram>[0-9a-fA-F]+)\s+(?P<vrom>[0-9a-fA-F]+)\s+(?P<size>[0-9a-fA-F]+)\s+(?P<align>[0-9a-fA-F]+) `
+pub(crate) fn lld_row(line: &str) -> Option<LldRow<'_>> {
+    let mut iter = line.split_whitespace();
+    let vram = iter.next()?;
+    let vrom = iter.next()?;
+    let size = iter.next()?;
+    let align = iter.next()?;
+
+    if ![vram, vrom, size, align]
+        .iter()
+        .all(|x| x.bytes().all(|b| b.is_ascii_hexdigit()))
+    {
+        return None;
+    }
+
+    let align_end = field_end(line, align);
+    let rest = line[align_end..].strip_prefix(' ')?;
+
+    Some(LldRow {
+        vram,
+        vrom,
+        size,
+        align,
+        rest,
+    })
+}
+
+/// Output sections, like `.text`.
+///
+/// Regex: `^(?P<name>[^\s]+)$`
+pub(crate) fn lld_segment(rest: &str) -> Option<&str> {
+    if rest.is_empty() || rest.contains(char::is_whitespace) {
+        return None;
+    }
+
+    Some(rest)
+}
+
+/// Padding entries, like `. += 0x10`.
+///
+/// Regex: `^\s+(?P<expr>\.\s*\+=\s*.+)$`
+pub(crate) fn lld_is_fill(rest: &str) -> bool {
+    let (leading, fields) = split_leading(rest);
+    if leading.is_empty() {
+        return false;
+    }
+
+    let Some(expr) = fields.strip_prefix('.') else {
+        return false;
+    };
+    let Some(value) = expr.trim_start().strip_prefix("+=") else {
+        return false;
+    };
+
+    // `\s*` gives back a character to `.+` if needed
+    !value.is_empty()
+}
+
+/// Regex: `^\s+(?P<name>[^\s]+):\((?P<section>[^\s()]+)\)$`
+pub(crate) fn lld_section_data(rest: &str) -> Option<LldSectionData<'_>> {
+    let name = lld_single_field(rest)?;
+
+    let inner = name.strip_suffix(')')?;
+    let open_paren = inner.rfind('(')?;
+    let section = &inner[open_paren + 1..];
+    let name = inner[..open_paren].strip_suffix(':')?;
+
+    if name.is_empty() || section.is_empty() || section.contains(')') {
+        return None;
+    }
+
+    Some(LldSectionData { name, section })
+}
+
+/// Jump table labels, like `.L80001234`.
+///
+/// Regex: `^\s+(?P<name>\.?L[0-9A-F]{8})$`
+pub(crate) fn lld_is_label(rest: &str) -> bool {
+    let Some(name) = lld_single_field(rest) else {
+        return false;
+    };
+    let name = name.strip_prefix('.').unwrap_or(name);
+
+    name.len() == 9 && name.starts_with('L') && name.as_bytes()[1..].iter().all(is_upper_hex_digit)
+}
+
+/// Regex: `^\s+(?P<name>[^\s]+)$`
+pub(crate) fn lld_symbol(rest: &str) -> Option<&str> {
+    lld_single_field(rest)
+}
+
+/// A single field preceded by whitespace and not followed by anything.
+fn lld_single_field(rest: &str) -> Option<&str> {
+    if ends_with_whitespace(rest) {
+        return None;
+    }
+    let (leading, fields) = split_leading(rest);
+    if leading.is_empty() {
+        return None;
+    }
+
+    let [field] = exact_fields(fields)?;
+    Some(field)
+}
+
+/// Splits `line` into its leading whitespace and everything after it.
+fn split_leading(line: &str) -> (&str, &str) {
+    let fields = line.trim_start();
+
+    (&line[..line.len() - fields.len()], fields)
+}
+
+/// Returns the whitespace separated fields of `fields` if there are exactly
+/// `N` of them.
+fn exact_fields<const N: usize>(fields: &str) -> Option<[&str; N]> {
+    let mut iter = fields.split_whitespace();
+    let mut ret = [""; N];
+
+    for x in &mut ret {
+        *x = iter.next()?;
+    }
+    if iter.next().is_some() {
+        return None;
+    }
+
+    Some(ret)
+}
+
+/// The `[^*][^\s]+` section name of the GNU regexes, which gets a whitespace
+/// character prepended if the field itself doesn't fit.
+fn section_name_field<'a>(line: &'a str, leading: &'a str, field: &'a str) -> Option<&'a str> {
+    let mut chars = field.chars();
+    let first = chars.next()?;
+
+    if first != '*' && chars.next().is_some() {
+        return Some(field);
+    }
+
+    // `^\s+` has to keep at least one whitespace character for itself
+    if leading_char_count(leading) < 2 {
+        return None;
+    }
+    let last_whitespace = leading.chars().next_back()?;
+    let start = field_start(line, field) - last_whitespace.len_utf8();
+
+    Some(&line[start..field_end(line, field)])
+}
+
+/// The optional `[^\s]+` name of the GNU segment regexes, which is the field
+/// right before `vram`, or empty if `vram` is the first field. Either way
+/// there must be whitespace before `vram`.
+fn segment_name_field<'a>(
+    line: &'a str,
+    vram: &'a str,
+    previous: Option<&'a str>,
+) -> Option<&'a str> {
+    match previous {
+        Some(x) => Some(x),
+        None => {
+            if field_start(line, vram) == 0 {
+                return None;
+            }
+            Some("")
+        }
+    }
+}
+
+/// The text between the fields `a` and `b` of `line`.
+fn gap<'a>(line: &'a str, a: &str, b: &str) -> &'a str {
+    &line[field_end(line, a)..field_start(line, b)]
+}
+
+/// The byte offset of `field` within `line`, which must be a subslice of it.
+fn field_start(line: &str, field: &str) -> usize {
+    field.as_ptr() as usize - line.as_ptr() as usize
+}
+
+fn field_end(line: &str, field: &str) -> usize {
+    field_start(line, field) + field.len()
+}
+
+fn leading_char_count(whitespace: &str) -> usize {
+    whitespace.chars().take(2).count()
+}
+
+fn ends_with_whitespace(line: &str) -> bool {
+    line.chars().next_back().is_some_and(char::is_whitespace)
+}
+
+/// `0x[^\s]+`
+fn is_gnu_number(field: &str) -> bool {
+    field.len() > 2 && field.starts_with("0x")
+}
+
+fn is_upper_hex_digit(x: &u8) -> bool {
+    x.is_ascii_digit() || (b'A'..=b'F').contains(x)
+}
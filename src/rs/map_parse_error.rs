@@ -0,0 +1,160 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT */
+
+use std::{error, fmt, io};
+
+#[cfg(feature = "python_bindings")]
+use pyo3::{
+    exceptions::{PyIOError, PyValueError},
+    prelude::*,
+};
+
+/// The reason why a mapfile could not be parsed.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum MapParseErrorKind {
+    /// The mapfile could not be read.
+    Io(io::Error),
+
+    /// The contents don't look like any of the supported mapfile formats.
+    ///
+    /// Only returned by the functions that guess the format of the map. Use
+    /// [`MapFile::try_new_from_map_str_with_format`] to parse a map with an
+    /// explicit format instead.
+    ///
+    /// [`MapFile::try_new_from_map_str_with_format`]: crate::MapFile::try_new_from_map_str_with_format
+    UnknownFormat,
+
+    /// A field that was expected to be an hexadecimal number could not be
+    /// parsed as one, or it does not fit on 64 bits.
+    ///
+    /// Contains the offending field.
+    InvalidHexNumber(String),
+
+    /// A field that was expected to be a decimal number could not be parsed
+    /// as one, or it does not fit on 64 bits.
+    ///
+    /// Contains the offending field.
+    InvalidDecimalNumber(String),
+
+    /// A row of a delimited file doesn't have the column that was expected
+    /// to contain a field.
+    ///
+    /// Contains the zero-based index of the missing column.
+    MissingColumn(usize),
+
+    /// A symbol was found before any section was declared on the mapfile.
+    SymbolOutsideSection,
+
+    /// Computing an address from the fields of a line overflows 64 bits.
+    AddressOverflow,
+
+    /// The given ELF file is malformed or not supported.
+    ///
+    /// Contains a description of the problem.
+    InvalidElf(String),
+
+    /// The given JSON document is malformed or doesn't follow the schema of
+    /// any version of the format.
+    ///
+    /// Contains a description of the problem.
+    InvalidJsonDocument(String),
+
+    /// The given JSON document was written by a newer version of this crate.
+    ///
+    /// Contains the `format_version` of the document.
+    UnsupportedJsonDocumentVersion(u64),
+}
+
+impl fmt::Display for MapParseErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MapParseErrorKind::Io(e) => write!(f, "unable to read mapfile: {}", e),
+            MapParseErrorKind::UnknownFormat => {
+                write!(f, "the format of the mapfile could not be detected")
+            }
+            MapParseErrorKind::InvalidHexNumber(x) => {
+                write!(f, "invalid hexadecimal number `{}`", x)
+            }
+            MapParseErrorKind::InvalidDecimalNumber(x) => {
+                write!(f, "invalid decimal number `{}`", x)
+            }
+            MapParseErrorKind::MissingColumn(x) => write!(f, "missing column {}", x),
+            MapParseErrorKind::SymbolOutsideSection => {
+                write!(f, "symbol found outside of any section")
+            }
+            MapParseErrorKind::AddressOverflow => write!(f, "address overflows 64 bits"),
+            MapParseErrorKind::InvalidElf(x) => write!(f, "invalid ELF file: {}", x),
+            MapParseErrorKind::InvalidJsonDocument(x) => {
+                write!(f, "invalid JSON document: {}", x)
+            }
+            MapParseErrorKind::UnsupportedJsonDocumentVersion(x) => {
+                write!(f, "unsupported JSON document format version {}", x)
+            }
+        }
+    }
+}
+
+/// An error produced while parsing a mapfile.
+#[derive(Debug)]
+pub struct MapParseError {
+    /// The 1-based line number of the mapfile where the error was found.
+    ///
+    /// `None` if the error is not associated to any specific line, like when
+    /// the mapfile could not be read.
+    pub line_number: Option<usize>,
+
+    /// The contents of the offending line.
+    pub line: String,
+
+    pub kind: MapParseErrorKind,
+}
+
+impl MapParseError {
+    pub fn new(line_number: Option<usize>, line: &str, kind: MapParseErrorKind) -> Self {
+        Self {
+            line_number,
+            line: line.to_string(),
+            kind,
+        }
+    }
+
+    pub(crate) fn new_at_line(line_number: usize, line: &str, kind: MapParseErrorKind) -> Self {
+        Self::new(Some(line_number), line, kind)
+    }
+}
+
+impl fmt::Display for MapParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(line_number) = self.line_number {
+            write!(f, "line {}: {}: `{}`", line_number, self.kind, self.line)
+        } else {
+            write!(f, "{}", self.kind)
+        }
+    }
+}
+
+impl error::Error for MapParseError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match &self.kind {
+            MapParseErrorKind::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for MapParseError {
+    fn from(value: io::Error) -> Self {
+        Self::new(None, "", MapParseErrorKind::Io(value))
+    }
+}
+
+#[cfg(feature = "python_bindings")]
+impl From<MapParseError> for PyErr {
+    fn from(value: MapParseError) -> Self {
+        match value.kind {
+            MapParseErrorKind::Io(e) => PyIOError::new_err(e.to_string()),
+            _ => PyValueError::new_err(value.to_string()),
+        }
+    }
+}
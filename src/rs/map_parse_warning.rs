@@ -0,0 +1,164 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT */
+
+use std::{fmt, path::PathBuf};
+
+#[cfg(feature = "python_bindings")]
+use pyo3::prelude::*;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// The kind of inconsistency found while post-processing a mapfile.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+#[cfg_attr(
+    feature = "python_bindings",
+    pyclass(module = "mapfile_parser", eq, eq_int, frozen, hash)
+)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum MapParseWarningKind {
+    /// The symbol has an address lower than the starting address of its
+    /// section.
+    SymbolBeforeSection,
+
+    /// The symbol that follows this one has a lower address, so the size of
+    /// this symbol can't be inferred.
+    UnsortedSymbol,
+
+    /// The sizes of the symbols of the section add up to more than the size
+    /// of the section, so the size of the last symbol can't be inferred.
+    SectionSizeExceeded,
+
+    /// The symbol belongs to a section that is not listed on the mapfile, so
+    /// it was skipped.
+    UnknownSectionIndex,
+
+    /// None of the symbols of the segment tell its address, so it was
+    /// skipped. The `symbol_name` of these warnings is empty.
+    UnknownSegmentAddress,
+}
+
+impl fmt::Display for MapParseWarningKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MapParseWarningKind::SymbolBeforeSection => {
+                write!(f, "symbol is placed before the start of its section")
+            }
+            MapParseWarningKind::UnsortedSymbol => {
+                write!(f, "the next symbol has a lower address than this one")
+            }
+            MapParseWarningKind::SectionSizeExceeded => {
+                write!(f, "symbols exceed the size of their section")
+            }
+            MapParseWarningKind::UnknownSectionIndex => {
+                write!(f, "symbol belongs to a section not listed on the map")
+            }
+            MapParseWarningKind::UnknownSegmentAddress => {
+                write!(f, "the address of the segment can't be inferred")
+            }
+        }
+    }
+}
+
+/// A non-fatal problem found while parsing a mapfile.
+///
+/// Symbols affected by a warning that prevents inferring their size get a
+/// size of zero and have [`Symbol::size_is_unknown`] set.
+///
+/// [`Symbol::size_is_unknown`]: crate::Symbol::size_is_unknown
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "python_bindings",
+    pyclass(module = "mapfile_parser", frozen)
+)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MapParseWarning {
+    pub segment_name: String,
+
+    pub section_filepath: PathBuf,
+
+    pub symbol_name: String,
+
+    pub kind: MapParseWarningKind,
+}
+
+impl MapParseWarning {
+    pub fn new(
+        segment_name: String,
+        section_filepath: PathBuf,
+        symbol_name: String,
+        kind: MapParseWarningKind,
+    ) -> Self {
+        Self {
+            segment_name,
+            section_filepath,
+            symbol_name,
+            kind,
+        }
+    }
+}
+
+impl fmt::Display for MapParseWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} ({}): {}",
+            self.segment_name,
+            self.section_filepath.display(),
+            self.symbol_name,
+            self.kind
+        )
+    }
+}
+
+#[cfg(feature = "python_bindings")]
+#[allow(non_snake_case)]
+pub(crate) mod python_bindings {
+    use pyo3::{intern, prelude::*, IntoPyObjectExt};
+
+    #[pymethods]
+    impl super::MapParseWarningKind {
+        fn __str__(&self) -> String {
+            self.to_string()
+        }
+    }
+
+    #[pymethods]
+    impl super::MapParseWarning {
+        /* Getters */
+
+        #[getter]
+        fn get_segmentName(&self) -> &str {
+            &self.segment_name
+        }
+
+        // Manually convert PathBuf into a pathlib.Path object since pyo3 refuses to do so
+        #[getter]
+        fn get_sectionFilepath(&self) -> PyResult<PyObject> {
+            Python::with_gil(|py| {
+                let pathlib = py.import("pathlib")?;
+                let pathlib_path = pathlib.getattr(intern!(py, "Path"))?;
+                let args = (self.section_filepath.as_path(),);
+
+                pathlib_path.call1(args)?.into_py_any(py)
+            })
+        }
+
+        #[getter]
+        fn get_symbolName(&self) -> &str {
+            &self.symbol_name
+        }
+
+        #[getter]
+        fn get_kind(&self) -> super::MapParseWarningKind {
+            self.kind
+        }
+
+        /* Methods */
+
+        fn __str__(&self) -> String {
+            self.to_string()
+        }
+    }
+}
@@ -10,8 +10,8 @@ use pyo3::prelude::*;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    found_symbol_info, maps_comparison_info, progress_stats, section, segment, symbol,
-    symbol_comparison_info, symbol_decomp_state,
+    found_symbol_info, map_parse_warning, maps_comparison_info, progress_stats, section, segment,
+    symbol, symbol_comparison_info, symbol_decomp_state,
 };
 
 #[derive(Debug, Clone)]
@@ -22,6 +22,10 @@ use crate::{
 pub struct MapFile {
     pub segments_list: Vec<segment::Segment>,
 
+    /// Inconsistencies found while parsing the mapfile.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub parse_warnings: Vec<map_parse_warning::MapParseWarning>,
+
     #[cfg(feature = "python_bindings")]
     #[pyo3(get, set)]
     pub(crate) debugging: bool,
@@ -67,7 +71,7 @@ impl MapFile {
     pub fn find_symbol_by_name(
         &self,
         sym_name: &str,
-    ) -> Option<found_symbol_info::FoundSymbolInfo> {
+    ) -> Option<found_symbol_info::FoundSymbolInfo<'_>> {
         for segment in &self.segments_list {
             if let Some(info) = segment.find_symbol_by_name(sym_name) {
                 return Some(info);
@@ -84,7 +88,7 @@ impl MapFile {
     pub fn find_symbol_by_vram_or_vrom(
         &self,
         address: u64,
-    ) -> Option<found_symbol_info::FoundSymbolInfo> {
+    ) -> Option<found_symbol_info::FoundSymbolInfo<'_>> {
         for segment in &self.segments_list {
             #[allow(deprecated)]
             if let Some(info) = segment.find_symbol_by_vram_or_vrom(address) {
@@ -99,7 +103,7 @@ impl MapFile {
         &self,
         address: u64,
     ) -> (
-        Option<found_symbol_info::FoundSymbolInfo>,
+        Option<found_symbol_info::FoundSymbolInfo<'_>>,
         Vec<&section::Section>,
     ) {
         let mut possible_sections = Vec::new();
@@ -119,7 +123,7 @@ impl MapFile {
         &self,
         address: u64,
     ) -> (
-        Option<found_symbol_info::FoundSymbolInfo>,
+        Option<found_symbol_info::FoundSymbolInfo<'_>>,
         Vec<&section::Section>,
     ) {
         let mut possible_sections = Vec::new();
@@ -401,8 +405,10 @@ pub(crate) mod python_bindings {
     };
 
     use crate::{
-        found_symbol_info, maps_comparison_info, progress_stats, report::ReportCategories, section,
-        segment, symbol,
+        elf_mismatch::ElfMismatch, found_symbol_info, map_format::MapFormat,
+        map_parse_warning::MapParseWarning, maps_comparison_info, parser_csv::CsvColumnMapping,
+        progress_stats, report::ReportCategories, section, segment, symbol,
+        symbol_addrs::SymbolAddrsEntry, symbol_addrs_check::python_bindings::PySymbolAddrsCheck,
     };
 
     #[pymethods]
@@ -413,43 +419,181 @@ pub(crate) mod python_bindings {
         }
 
         #[staticmethod]
-        fn newFromMapFile(map_path: PathBuf) -> Self {
-            Self::new_from_map_file(&map_path)
+        fn newFromMapFile(map_path: PathBuf) -> PyResult<Self> {
+            Ok(Self::open_map_file(&map_path, true)?)
         }
 
         #[staticmethod]
-        fn newFromMapStr(map_contents: &str) -> Self {
-            Self::new_from_map_str(map_contents)
+        fn newFromMapStr(map_contents: &str) -> PyResult<Self> {
+            let mut m = Self::new_impl();
+            m.try_parse_map_contents_impl(map_contents, true)?;
+            Ok(m)
         }
 
         #[staticmethod]
-        fn newFromGnuMapStr(map_contents: &str) -> Self {
-            Self::new_from_gnu_map_str(map_contents)
+        fn newFromMapStrWithFormat(map_contents: &str, format: MapFormat) -> PyResult<Self> {
+            Ok(Self::try_new_from_map_str_with_format(
+                map_contents,
+                format,
+            )?)
         }
 
         #[staticmethod]
-        fn newFromLldMapStr(map_contents: &str) -> Self {
-            Self::new_from_lld_map_str(map_contents)
+        fn detectFormat(map_contents: &str) -> Option<(MapFormat, f32)> {
+            Self::detect_format(map_contents)
         }
 
         #[staticmethod]
-        fn newFromMwMapStr(map_contents: &str) -> Self {
-            Self::new_from_mw_map_str(map_contents)
+        fn newFromGnuMapStr(map_contents: &str) -> PyResult<Self> {
+            Ok(Self::try_new_from_gnu_map_str(map_contents)?)
         }
 
-        fn readMapFile(&mut self, map_path: PathBuf) {
-            #[allow(deprecated)]
-            self.read_map_file(&map_path)
+        #[staticmethod]
+        fn newFromLldMapStr(map_contents: &str) -> PyResult<Self> {
+            Ok(Self::try_new_from_lld_map_str(map_contents)?)
         }
 
-        fn parseMapContents(&mut self, map_contents: &str) {
-            #[allow(deprecated)]
-            self.parse_map_contents(map_contents)
+        #[staticmethod]
+        fn newFromMwMapStr(map_contents: &str) -> PyResult<Self> {
+            Ok(Self::try_new_from_mw_map_str(map_contents)?)
         }
 
-        fn parseMapContentsGNU(&mut self, map_contents: &str) {
-            #[allow(deprecated)]
-            self.parse_map_contents_gnu(map_contents)
+        #[staticmethod]
+        fn newFromMsvcMapStr(map_contents: &str) -> PyResult<Self> {
+            Ok(Self::try_new_from_msvc_map_str(map_contents)?)
+        }
+
+        #[staticmethod]
+        fn newFromLd64MapStr(map_contents: &str) -> PyResult<Self> {
+            Ok(Self::try_new_from_ld64_map_str(map_contents)?)
+        }
+
+        #[staticmethod]
+        fn newFromPsyqMapStr(map_contents: &str) -> PyResult<Self> {
+            Ok(Self::try_new_from_psyq_map_str(map_contents)?)
+        }
+
+        #[staticmethod]
+        fn newFromArmlinkMapStr(map_contents: &str) -> PyResult<Self> {
+            Ok(Self::try_new_from_armlink_map_str(map_contents)?)
+        }
+
+        #[staticmethod]
+        fn newFromRgbdsMapStr(map_contents: &str) -> PyResult<Self> {
+            Ok(Self::try_new_from_rgbds_map_str(map_contents)?)
+        }
+
+        #[staticmethod]
+        fn newFromRgbdsSymStr(sym_contents: &str) -> PyResult<Self> {
+            Ok(Self::try_new_from_rgbds_sym_str(sym_contents)?)
+        }
+
+        fn addRgbdsSymStr(&mut self, sym_contents: &str) -> PyResult<()> {
+            Ok(self.try_add_rgbds_sym_str(sym_contents)?)
+        }
+
+        #[staticmethod]
+        fn newFromLd65MapStr(map_contents: &str) -> PyResult<Self> {
+            Ok(Self::try_new_from_ld65_map_str(map_contents)?)
+        }
+
+        #[staticmethod]
+        fn newFromWatcomMapStr(map_contents: &str) -> PyResult<Self> {
+            Ok(Self::try_new_from_watcom_map_str(map_contents)?)
+        }
+
+        #[staticmethod]
+        fn newFromWatcomMapStrWithSegmentBases(
+            map_contents: &str,
+            segment_bases: HashMap<u64, u64>,
+        ) -> PyResult<Self> {
+            Ok(Self::try_new_from_watcom_map_str_with_segment_bases(
+                map_contents,
+                &segment_bases,
+            )?)
+        }
+
+        #[staticmethod]
+        fn newFromElf(elf_bytes: &[u8]) -> PyResult<Self> {
+            Ok(Self::try_new_from_elf(elf_bytes)?)
+        }
+
+        #[staticmethod]
+        fn newFromNmStr(nm_contents: &str) -> PyResult<Self> {
+            Ok(Self::try_new_from_nm_str(nm_contents)?)
+        }
+
+        #[staticmethod]
+        fn newFromObjdumpStr(objdump_contents: &str) -> PyResult<Self> {
+            Ok(Self::try_new_from_objdump_str(objdump_contents)?)
+        }
+
+        #[staticmethod]
+        #[allow(clippy::too_many_arguments)]
+        #[pyo3(signature = (csv_contents, name, vram, size=None, vrom=None, section=None, filepath=None, delimiter=',', hasHeader=true))]
+        fn newFromCsvStr(
+            csv_contents: &str,
+            name: usize,
+            vram: usize,
+            size: Option<usize>,
+            vrom: Option<usize>,
+            section: Option<usize>,
+            filepath: Option<usize>,
+            delimiter: char,
+            hasHeader: bool,
+        ) -> PyResult<Self> {
+            let mapping = CsvColumnMapping {
+                delimiter,
+                has_header: hasHeader,
+                size,
+                vrom,
+                section,
+                filepath,
+                ..CsvColumnMapping::new(name, vram)
+            };
+            Ok(Self::try_new_from_csv_str(csv_contents, &mapping)?)
+        }
+
+        #[staticmethod]
+        fn newFromSplatSymbolAddrsStr(symbol_addrs_contents: &str) -> PyResult<Self> {
+            Ok(Self::try_new_from_splat_symbol_addrs_str(
+                symbol_addrs_contents,
+            )?)
+        }
+
+        #[staticmethod]
+        fn newFromMapFileCached(map_path: PathBuf, cache_path: PathBuf) -> PyResult<Self> {
+            Ok(Self::try_new_from_map_file_cached(&map_path, &cache_path)?)
+        }
+
+        #[staticmethod]
+        fn loadCache(cache_path: PathBuf, map_path: PathBuf) -> Option<Self> {
+            Self::load_cache(&cache_path, &map_path)
+        }
+
+        fn saveCache(&self, cache_path: PathBuf, map_path: PathBuf) -> PyResult<()> {
+            Ok(self.save_cache(&cache_path, &map_path)?)
+        }
+
+        #[staticmethod]
+        fn newFromJsonDocument(document: &str) -> PyResult<Self> {
+            Ok(Self::try_from_json_document(document)?)
+        }
+
+        fn toJsonDocument(&self) -> String {
+            self.to_json_document()
+        }
+
+        fn readMapFile(&mut self, map_path: PathBuf) -> PyResult<()> {
+            Ok(self.try_read_map_file(&map_path)?)
+        }
+
+        fn parseMapContents(&mut self, map_contents: &str) -> PyResult<()> {
+            Ok(self.try_parse_map_contents_impl(map_contents, true)?)
+        }
+
+        fn parseMapContentsGNU(&mut self, map_contents: &str) -> PyResult<()> {
+            Ok(self.try_parse_map_contents_gnu(map_contents)?)
         }
 
         /**
@@ -458,9 +602,8 @@ pub(crate) mod python_bindings {
         The `mapContents` argument must contain the contents of a clang ld.lld mapfile.
         */
         #[pyo3(name = "parseMapContentsLLD")]
-        fn parseMapContentsLLD(&mut self, map_contents: &str) {
-            #[allow(deprecated)]
-            self.parse_map_contents_lld(map_contents)
+        fn parseMapContentsLLD(&mut self, map_contents: &str) -> PyResult<()> {
+            Ok(self.try_parse_map_contents_lld(map_contents)?)
         }
 
         fn filterBySectionType(&self, section_type: &str) -> Self {
@@ -645,6 +788,39 @@ pub(crate) mod python_bindings {
             self.print_symbols_csv()
         }
 
+        fn toSplatSymbolAddrs(&self) -> String {
+            self.to_splat_symbol_addrs()
+        }
+
+        fn toGnuMapString(&self) -> String {
+            self.to_gnu_map_string()
+        }
+
+        fn toGnuLinkerScript(&self) -> String {
+            self.to_gnu_linker_script()
+        }
+
+        fn checkSplatSymbolAddrs(
+            &self,
+            symbol_addrs_contents: &str,
+        ) -> PyResult<Vec<PySymbolAddrsCheck>> {
+            let entries = SymbolAddrsEntry::parse_list(symbol_addrs_contents)?;
+
+            Ok(self
+                .check_symbol_addrs(&entries)
+                .into_iter()
+                .map(PySymbolAddrsCheck::from)
+                .collect())
+        }
+
+        fn getParseWarnings(&self) -> Vec<MapParseWarning> {
+            self.parse_warnings.clone()
+        }
+
+        fn enrichFromElf(&mut self, elf_bytes: &[u8]) -> PyResult<Vec<ElfMismatch>> {
+            Ok(self.try_enrich_from_elf(elf_bytes)?)
+        }
+
         fn copySegmentList(&self) -> Vec<segment::Segment> {
             self.segments_list.clone()
         }
@@ -4,21 +4,80 @@
 use std::{
     collections::{HashMap, HashSet},
     ffi::OsStr,
+    fs::File,
+    io::{BufRead, BufReader},
     path::{Path, PathBuf},
 };
 
 use regex::*;
 
-use crate::{mapfile::MapFile, section, segment, symbol, utils, Section};
+use crate::{
+    interned::{InternedStr, Interner},
+    map_format::MapFormat,
+    map_lexer,
+    map_parse_error::{MapParseError, MapParseErrorKind},
+    map_parse_warning::{MapParseWarning, MapParseWarningKind},
+    mapfile::MapFile,
+    section, segment, symbol, utils, Section,
+};
 
 lazy_static! {
-    static ref BANNED_SYMBOL_NAMES: HashSet<&'static str> = {
+    pub(crate) static ref BANNED_SYMBOL_NAMES: HashSet<&'static str> = {
         let mut symbol_names = HashSet::new();
         symbol_names.insert("gcc2_compiled.");
         symbol_names
     };
+
+    // Format detection
+    static ref REGEX_GNU_SECTION_DATA_ENTRY: Regex =
+        Regex::new(r"(?m)^\s+[^*\s]+\s+0x[0-9a-fA-F]+\s+0x[0-9a-fA-F]+\s+[^\s]+$").unwrap();
+    static ref REGEX_LLD_HEADER: Regex =
+        Regex::new(r"\s+VMA\s+LMA\s+Size\s+Align\s+Out\s+In\s+Symbol").unwrap();
+    static ref REGEX_PSYQ_SECTION_HEADER: Regex =
+        Regex::new(r"Start\s+Stop\s+Length\s+Obj\s+Group\s+Section name").unwrap();
+    static ref REGEX_RGBDS_BANK: Regex =
+        Regex::new(r"(?m)^(ROM0|ROMX|VRAM|SRAM|WRAM0|WRAMX|OAM|HRAM) bank #\d+:").unwrap();
+    static ref REGEX_WATCOM_LINKER: Regex = Regex::new(r"(?i)watcom linker").unwrap();
+    static ref REGEX_WATCOM_MODULE_SEGMENTS: Regex =
+        Regex::new(r"\|\s+Module Segments\s+\|").unwrap();
+    static ref REGEX_NM_SYMBOL_ENTRY: Regex =
+        Regex::new(r"^(?:[^\s:]+:)*[0-9a-fA-F]{8,16}(?: [0-9a-fA-F]{8,16})? [A-Za-z] [^\s]")
+            .unwrap();
+
+    // Metrowerks ld
+    static ref REGEX_MW_MEMORY_MAP_ENTRY: Regex = Regex::new(r"^\s*(?P<name>[^ ]+)\s+(?P<address>[0-9a-fA-F]+)\s+(?P<size>[0-9a-fA-F]+)\s+(?P<offset>[0-9a-fA-F]+)$").unwrap();
+    // mwld 2.7+
+    static ref REGEX_MW_ROW: Regex = Regex::new(r"^\s*(?P<starting>[0-9a-fA-F]+)\s+(?P<size>[0-9a-fA-F]+)\s+(?P<vram>[0-9a-fA-F]+)\s+(?P<rom>[0-9a-fA-F]+)\s+(?P<align>[0-9a-fA-F]+)\s+(?P<subline>.+)").unwrap();
+    // mwld 1.3.2-
+    static ref REGEX_MW_ROMLESS_ROW: Regex = Regex::new(r"^\s*(?P<starting>[0-9a-fA-F]+)\s+(?P<size>[0-9a-fA-F]+)\s+(?P<vram>[0-9a-fA-F]+)\s+(?P<align>[0-9a-fA-F]+)\s+(?P<subline>.+)").unwrap();
+    static ref REGEX_MW_SEGMENT: Regex = Regex::new(r"^(?P<name>.+) section layout$").unwrap();
+    static ref REGEX_MW_LABEL: Regex =
+        Regex::new(r"^(?P<label>lbl_[0-9A-F]{8})\s+(?P<filename>.+?)\s*$").unwrap();
+    static ref REGEX_MW_SYMBOL: Regex =
+        Regex::new(r"^\s*(?P<name>[^ ]+)\s+(?P<filename>.+?)\s*$").unwrap();
+    static ref REGEX_MW_FILL: Regex = Regex::new(r"^\s*\*fill\*\s*$").unwrap();
+    static ref REGEX_MW_LINKER_SYMBOL: Regex =
+        Regex::new(r"^#>(?P<vram>[0-9a-fA-F]+)\s+(?P<name>[^\s]+)").unwrap();
+    static ref REGEX_MWLDARM_ROW: Regex = Regex::new(r"^\s+(?P<vram>[0-9a-fA-F]{8})\s+(?P<size>[0-9a-fA-F]{8})\s+(?P<section>[^\s]+)\s+(?P<name>[^\s]+)\s+\((?P<filename>[^)]*)\)\s*$").unwrap();
+    static ref REGEX_MWLDARM_SEGMENT: Regex = Regex::new(r"^#\s+\.(?P<name>[^\s]+)\s*$").unwrap();
+    static ref REGEX_MWLDARM_LABEL: Regex = Regex::new(r"^\$[atd](\.\d+)?$").unwrap();
 }
 
+/// Amount of bytes read from the start of a map to guess its format when
+/// parsing it from a reader.
+const FORMAT_DETECTION_HEAD_SIZE: usize = 1024 * 1024;
+
+/// Titles of the blocks GNU ld prints before the `Linker script and memory
+/// map` header. Finding one of them means that header will follow.
+const GNU_PREAMBLE_TITLES: &[&str] = &[
+    "Archive member included to satisfy reference by file",
+    "As-needed library included to satisfy reference by file",
+    "Allocating common symbols",
+    "Discarded input sections",
+    "There are no discarded input sections",
+    "Memory Configuration",
+];
+
 // TODO: Change all the deprecated functions to private and undeprecate them in a future version.
 
 impl MapFile {
@@ -31,12 +90,26 @@ impl MapFile {
     /// - GNU ld
     /// - clang ld.lld
     /// - Metrowerks ld
+    /// - Microsoft LINK
+    /// - GNU objdump
+    /// - GNU nm
+    /// - Open Watcom wlink
+    /// - cc65 ld65
+    /// - RGBDS
+    /// - ARM armlink
+    /// - Psy-Q psylink
+    /// - Apple ld64
+    ///
+    /// Maps that don't look like any of those formats are parsed as GNU ld
+    /// maps.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the file can't be read or if the map can't be parsed. Use
+    /// [`MapFile::try_new_from_map_file`] to handle those cases gracefully.
     #[must_use]
     pub fn new_from_map_file(map_path: &Path) -> Self {
-        let mut m = Self::new_impl();
-        #[allow(deprecated)]
-        m.read_map_file(map_path);
-        m
+        Self::open_map_file(map_path, true).unwrap_or_else(|e| panic!("{}", e))
     }
 
     /// Creates a new `MapFile` by parsing the contents of the map.
@@ -47,11 +120,28 @@ impl MapFile {
     /// - GNU ld
     /// - clang ld.lld
     /// - Metrowerks ld
+    /// - Microsoft LINK
+    /// - GNU objdump
+    /// - GNU nm
+    /// - Open Watcom wlink
+    /// - cc65 ld65
+    /// - RGBDS
+    /// - ARM armlink
+    /// - Psy-Q psylink
+    /// - Apple ld64
+    ///
+    /// Maps that don't look like any of those formats are parsed as GNU ld
+    /// maps.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the map can't be parsed. Use [`MapFile::try_new_from_map_str`]
+    /// to handle that case gracefully.
     #[must_use]
     pub fn new_from_map_str(map_contents: &str) -> Self {
         let mut m = Self::new_impl();
-        #[allow(deprecated)]
-        m.parse_map_contents(map_contents);
+        m.try_parse_map_contents_impl(map_contents, true)
+            .unwrap_or_else(|e| panic!("{}", e));
         m
     }
 
@@ -60,10 +150,7 @@ impl MapFile {
     /// The `map_contents` argument must contain the contents of a GNU ld mapfile.
     #[must_use]
     pub fn new_from_gnu_map_str(map_contents: &str) -> Self {
-        let mut m = Self::new_impl();
-        #[allow(deprecated)]
-        m.parse_map_contents_gnu(map_contents);
-        m
+        Self::try_new_from_gnu_map_str(map_contents).unwrap_or_else(|e| panic!("{}", e))
     }
 
     /// Parses the contents of a clang ld.lld map.
@@ -71,10 +158,7 @@ impl MapFile {
     /// The `map_contents` argument must contain the contents of a clang ld.lld mapfile.
     #[must_use]
     pub fn new_from_lld_map_str(map_contents: &str) -> Self {
-        let mut m = Self::new_impl();
-        #[allow(deprecated)]
-        m.parse_map_contents_lld(map_contents);
-        m
+        Self::try_new_from_lld_map_str(map_contents).unwrap_or_else(|e| panic!("{}", e))
     }
 
     /// Parses the contents of a Metrowerks ld (mwld) map.
@@ -82,14 +166,150 @@ impl MapFile {
     /// The `map_contents` argument must contain the contents of a Metrowerks ld mapfile.
     #[must_use]
     pub fn new_from_mw_map_str(map_contents: &str) -> Self {
+        Self::try_new_from_mw_map_str(map_contents).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Creates a new `MapFile` with the contents from the file pointed by the
+    /// `map_path` argument.
+    ///
+    /// Same as [`MapFile::new_from_map_file`], but returns an error instead of
+    /// panicking if the file can't be read or the map is malformed, and
+    /// returns a [`MapParseErrorKind::UnknownFormat`] error for maps of an
+    /// unknown format instead of parsing them as GNU ld maps.
+    pub fn try_new_from_map_file(map_path: &Path) -> Result<Self, MapParseError> {
+        Self::open_map_file(map_path, false)
+    }
+
+    pub(crate) fn open_map_file(
+        map_path: &Path,
+        unknown_as_gnu: bool,
+    ) -> Result<Self, MapParseError> {
+        let file = File::open(map_path)?;
+
+        Self::parse_reader(BufReader::new(file), unknown_as_gnu)
+    }
+
+    /// Creates a new `MapFile` by parsing the map read from `reader`.
+    ///
+    /// The format of the map is guessed from its first MiB. GNU ld and clang
+    /// ld.lld maps are parsed line by line while being read, so they are
+    /// never loaded whole into memory. Maps of the other formats are read
+    /// completely before parsing them.
+    ///
+    /// The blocks GNU ld prints before the memory map are skipped. A GNU ld
+    /// map that was cut before its `Linker script and memory map` header gives
+    /// an empty `MapFile`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the map can't be read or parsed. Use
+    /// [`MapFile::try_from_reader`] to handle those cases gracefully.
+    #[must_use]
+    pub fn from_reader(reader: impl BufRead) -> Self {
+        Self::try_from_reader(reader).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Same as [`MapFile::from_reader`], but returns an error instead of
+    /// panicking if the map can't be read or is malformed.
+    pub fn try_from_reader(reader: impl BufRead) -> Result<Self, MapParseError> {
+        Self::parse_reader(reader, false)
+    }
+
+    /// Maps of an unknown format are parsed as GNU ld maps if
+    /// `unknown_as_gnu` is set, like the constructors that predate format
+    /// detection do, or rejected otherwise.
+    fn parse_reader(mut reader: impl BufRead, unknown_as_gnu: bool) -> Result<Self, MapParseError> {
+        let mut head = String::new();
+        while head.len() < FORMAT_DETECTION_HEAD_SIZE {
+            if reader.read_line(&mut head)? == 0 {
+                break;
+            }
+        }
+
+        let format = Self::detect_format_or_fallback(&head, unknown_as_gnu)?;
+
         let mut m = Self::new_impl();
-        m.parse_map_contents_mw(map_contents);
-        m
+        match format {
+            MapFormat::Gnu => {
+                let mut parser = GnuLineParser::new();
+                feed_lines(&head, reader, |line, line_number| {
+                    parser.parse_line(line, line_number)
+                })?;
+                m.segments_list = parser.finish(&mut m.parse_warnings)?;
+            }
+            MapFormat::Lld => {
+                let mut parser = LldLineParser::new();
+                feed_lines(&head, reader, |line, line_number| {
+                    parser.parse_line(line, line_number)
+                })?;
+                m.segments_list = parser.finish(&mut m.parse_warnings);
+            }
+            _ => {
+                reader.read_to_string(&mut head)?;
+                m.try_parse_map_contents_impl(&head, unknown_as_gnu)?;
+            }
+        }
+
+        Ok(m)
+    }
+
+    /// Creates a new `MapFile` by parsing the contents of the map.
+    ///
+    /// Same as [`MapFile::new_from_map_str`], but returns an error instead of
+    /// panicking if the map is malformed.
+    pub fn try_new_from_map_str(map_contents: &str) -> Result<Self, MapParseError> {
+        let mut m = Self::new_impl();
+        m.try_parse_map_contents(map_contents)?;
+        Ok(m)
+    }
+
+    /// Same as [`MapFile::new_from_gnu_map_str`], but returns an error instead
+    /// of panicking if the map is malformed.
+    pub fn try_new_from_gnu_map_str(map_contents: &str) -> Result<Self, MapParseError> {
+        let mut m = Self::new_impl();
+        m.try_parse_map_contents_gnu(map_contents)?;
+        Ok(m)
+    }
+
+    /// Same as [`MapFile::new_from_lld_map_str`], but returns an error instead
+    /// of panicking if the map is malformed.
+    pub fn try_new_from_lld_map_str(map_contents: &str) -> Result<Self, MapParseError> {
+        let mut m = Self::new_impl();
+        m.try_parse_map_contents_lld(map_contents)?;
+        Ok(m)
+    }
+
+    /// Same as [`MapFile::new_from_mw_map_str`], but returns an error instead
+    /// of panicking if the map is malformed.
+    pub fn try_new_from_mw_map_str(map_contents: &str) -> Result<Self, MapParseError> {
+        let mut m = Self::new_impl();
+        m.try_parse_map_contents_mw(map_contents)?;
+        Ok(m)
+    }
+
+    /// Parses the contents of the map using the given `format`, skipping the
+    /// format detection.
+    #[must_use]
+    pub fn new_from_map_str_with_format(map_contents: &str, format: MapFormat) -> Self {
+        Self::try_new_from_map_str_with_format(map_contents, format)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Same as [`MapFile::new_from_map_str_with_format`], but returns an error
+    /// instead of panicking if the map is malformed.
+    pub fn try_new_from_map_str_with_format(
+        map_contents: &str,
+        format: MapFormat,
+    ) -> Result<Self, MapParseError> {
+        let mut m = Self::new_impl();
+        m.try_parse_map_contents_with_format(map_contents, format)?;
+        Ok(m)
     }
 
     pub(crate) fn new_impl() -> Self {
         Self {
             segments_list: Vec::new(),
+            parse_warnings: Vec::new(),
 
             #[cfg(feature = "python_bindings")]
             debugging: false,
@@ -100,6 +320,7 @@ impl MapFile {
         since = "2.8.0",
         note = "Use either `new_from_map_file` or `new_from_map_str` instead."
     )]
+    #[allow(clippy::new_without_default)]
     pub fn new() -> Self {
         Self::new_impl()
     }
@@ -113,13 +334,28 @@ impl MapFile {
     - GNU ld
     - clang ld.lld
     - Metrowerks ld
+    - Microsoft LINK
+    - GNU objdump
+    - GNU nm
+    - Open Watcom wlink
+    - cc65 ld65
+    - RGBDS
+    - ARM armlink
+    - Psy-Q psylink
+    - Apple ld64
      */
     #[deprecated(since = "2.8.0", note = "Prefer `MapFile::new_from_map_file` instead")]
     pub fn read_map_file(&mut self, map_path: &Path) {
-        let map_contents = utils::read_file_contents(map_path);
+        self.try_read_map_file(map_path)
+            .unwrap_or_else(|e| panic!("{}", e));
+    }
 
-        #[allow(deprecated)]
-        self.parse_map_contents(&map_contents);
+    pub(crate) fn try_read_map_file(&mut self, map_path: &Path) -> Result<(), MapParseError> {
+        let parsed = Self::open_map_file(map_path, true)?;
+
+        self.segments_list = parsed.segments_list;
+        self.parse_warnings = parsed.parse_warnings;
+        Ok(())
     }
 
     /**
@@ -133,23 +369,172 @@ impl MapFile {
     - GNU ld
     - clang ld.lld
     - Metrowerks ld
+    - Microsoft LINK
+    - GNU objdump
+    - GNU nm
+    - Open Watcom wlink
+    - cc65 ld65
+    - RGBDS
+    - ARM armlink
+    - Psy-Q psylink
+    - Apple ld64
     */
     #[deprecated(since = "2.8.0", note = "Prefer `MapFile::new_from_map_str` instead")]
     pub fn parse_map_contents(&mut self, map_contents: &str) {
-        let regex_lld_header =
-            Regex::new(r"\s+VMA\s+LMA\s+Size\s+Align\s+Out\s+In\s+Symbol").unwrap();
-
-        if regex_lld_header.is_match(map_contents) {
-            #[allow(deprecated)]
-            self.parse_map_contents_lld(map_contents);
-        } else if map_contents.starts_with("Link map of ")
-            || map_contents.contains(" section layout")
-        {
-            self.parse_map_contents_mw(map_contents);
-        } else {
-            // GNU is the fallback
-            #[allow(deprecated)]
-            self.parse_map_contents_gnu(map_contents);
+        self.try_parse_map_contents_impl(map_contents, true)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub(crate) fn try_parse_map_contents(
+        &mut self,
+        map_contents: &str,
+    ) -> Result<(), MapParseError> {
+        self.try_parse_map_contents_impl(map_contents, false)
+    }
+
+    pub(crate) fn try_parse_map_contents_impl(
+        &mut self,
+        map_contents: &str,
+        unknown_as_gnu: bool,
+    ) -> Result<(), MapParseError> {
+        let format = Self::detect_format_or_fallback(map_contents, unknown_as_gnu)?;
+
+        self.try_parse_map_contents_with_format(map_contents, format)
+    }
+
+    fn try_parse_map_contents_with_format(
+        &mut self,
+        map_contents: &str,
+        format: MapFormat,
+    ) -> Result<(), MapParseError> {
+        match format {
+            MapFormat::Gnu => self.try_parse_map_contents_gnu(map_contents),
+            MapFormat::Lld => self.try_parse_map_contents_lld(map_contents),
+            MapFormat::Mw => self.try_parse_map_contents_mw(map_contents),
+            MapFormat::Msvc => self.try_parse_map_contents_msvc(map_contents),
+            MapFormat::Ld64 => self.try_parse_map_contents_ld64(map_contents),
+            MapFormat::Psyq => self.try_parse_map_contents_psyq(map_contents),
+            MapFormat::Armlink => self.try_parse_map_contents_armlink(map_contents),
+            MapFormat::Rgbds => self.try_parse_map_contents_rgbds(map_contents),
+            MapFormat::Ld65 => self.try_parse_map_contents_ld65(map_contents),
+            MapFormat::Watcom => self.try_parse_map_contents_watcom(map_contents, &HashMap::new()),
+            MapFormat::Nm => self.try_parse_map_contents_nm(map_contents),
+            MapFormat::Objdump => self.try_parse_map_contents_objdump(map_contents),
+        }
+    }
+
+    /// Tries to guess the format of the given mapfile contents.
+    ///
+    /// Returns the most likely format alongside how confident the guess is,
+    /// as a number between `0.0` and `1.0`.
+    ///
+    /// Returns `None` if the contents do not look like any of the supported
+    /// formats.
+    pub fn detect_format(map_contents: &str) -> Option<(MapFormat, f32)> {
+        let mut best: Option<(MapFormat, f32)> = None;
+
+        for format in MapFormat::ALL {
+            if let Some(confidence) = Self::format_confidence(map_contents, *format) {
+                // Keep the first format on ties
+                if best.map_or(true, |(_, best_confidence)| confidence > best_confidence) {
+                    best = Some((*format, confidence));
+                }
+            }
+        }
+
+        best
+    }
+
+    /// GNU ld was the fallback for unknown maps before format detection
+    /// existed, so the constructors from back then keep it.
+    fn detect_format_or_fallback(
+        map_contents: &str,
+        unknown_as_gnu: bool,
+    ) -> Result<MapFormat, MapParseError> {
+        match Self::detect_format(map_contents) {
+            Some((format, _)) => Ok(format),
+            None if unknown_as_gnu => Ok(MapFormat::Gnu),
+            None => Err(MapParseError::new(
+                None,
+                "",
+                MapParseErrorKind::UnknownFormat,
+            )),
+        }
+    }
+
+    fn format_confidence(map_contents: &str, format: MapFormat) -> Option<f32> {
+        match format {
+            MapFormat::Gnu => {
+                if map_contents.starts_with("Linker script and memory map")
+                    || map_contents.contains("\nLinker script and memory map")
+                {
+                    Some(0.9)
+                } else if map_contents.contains("Memory Configuration")
+                    || map_contents.contains("Discarded input sections")
+                    || map_contents.contains("Archive member included to satisfy reference")
+                {
+                    Some(0.7)
+                } else {
+                    REGEX_GNU_SECTION_DATA_ENTRY
+                        .is_match(map_contents)
+                        .then_some(0.5)
+                }
+            }
+            MapFormat::Lld => REGEX_LLD_HEADER.is_match(map_contents).then_some(1.0),
+            MapFormat::Mw => {
+                if map_contents.starts_with("Link map of ")
+                    || map_contents.starts_with("# Link map of ")
+                {
+                    Some(1.0)
+                } else if map_contents.contains(" section layout") || map_contents.contains("\n#>")
+                {
+                    Some(0.9)
+                } else {
+                    None
+                }
+            }
+            MapFormat::Msvc => {
+                if map_contents.contains("Publics by Value") && map_contents.contains("Rva+Base") {
+                    Some(1.0)
+                } else if map_contents.contains("Preferred load address is ") {
+                    Some(0.8)
+                } else {
+                    None
+                }
+            }
+            MapFormat::Ld64 => (map_contents.contains("# Object files:")
+                && map_contents.contains("# Symbols:"))
+            .then_some(1.0),
+            MapFormat::Psyq => (map_contents.contains("Names in address order")
+                || map_contents.contains("Names alphabetical")
+                || REGEX_PSYQ_SECTION_HEADER.is_match(map_contents))
+            .then_some(1.0),
+            MapFormat::Armlink => (map_contents.contains("Memory Map of the image")
+                || map_contents.contains("Image Symbol Table"))
+            .then_some(1.0),
+            MapFormat::Rgbds => REGEX_RGBDS_BANK.is_match(map_contents).then_some(1.0),
+            MapFormat::Ld65 => (map_contents.contains("Modules list:")
+                && map_contents.contains("Segment list:"))
+            .then_some(1.0),
+            MapFormat::Watcom => {
+                if REGEX_WATCOM_LINKER.is_match(map_contents) {
+                    Some(1.0)
+                } else {
+                    REGEX_WATCOM_MODULE_SEGMENTS
+                        .is_match(map_contents)
+                        .then_some(0.8)
+                }
+            }
+            MapFormat::Nm => {
+                let mut lines = map_contents
+                    .lines()
+                    .filter(|x| !x.trim().is_empty() && !x.ends_with(':'))
+                    .peekable();
+
+                (lines.peek().is_some() && lines.all(|x| REGEX_NM_SYMBOL_ENTRY.is_match(x)))
+                    .then_some(0.5)
+            }
+            MapFormat::Objdump => map_contents.contains("SYMBOL TABLE:").then_some(1.0),
         }
     }
 }
@@ -165,179 +550,52 @@ impl MapFile {
         note = "Prefer `MapFile::new_from_gnu_map_str` instead"
     )]
     pub fn parse_map_contents_gnu(&mut self, map_contents: &str) {
-        // TODO: maybe move somewhere else?
-        let regex_section_alone_entry = Regex::new(r"^\s+(?P<section>[^*][^\s]+)\s*$").unwrap();
-        let regex_section_data_entry = Regex::new(r"^\s+(?P<section>([^*][^\s]+)?)\s+(?P<vram>0x[^\s]+)\s+(?P<size>0x[^\s]+)\s+(?P<name>[^\s]+)$").unwrap();
-        let regex_function_entry =
-            Regex::new(r"^\s+(?P<vram>0x[^\s]+)\s+(?P<name>[^\s]+)$").unwrap();
-        // regex_function_entry = re.compile(r"^\s+(?P<vram>0x[^\s]+)\s+(?P<name>[^\s]+)((\s*=\s*(?P<expression>.+))?)$")
-        let regex_label = Regex::new(r"(?P<name>\.?L[0-9A-F]{8})$").unwrap();
-        let regex_fill =
-            Regex::new(r"^\s+(?P<fill>\*[^\s\*]+\*)\s+(?P<vram>0x[^\s]+)\s+(?P<size>0x[^\s]+)\s+(?P<fillValue>[0-9a-zA-Z]*)$")
-                .unwrap();
-        let regex_segment_entry = Regex::new(r"(?P<name>([^\s]+)?)\s+(?P<vram>0x[^\s]+)\s+(?P<size>0x[^\s]+)\s+(?P<loadaddress>(load address)?)\s+(?P<vrom>0x[^\s]+)$").unwrap();
-        let regex_romless_segment_entry =
-            Regex::new(r"(?P<name>([^\s]+)?)\s+(?P<vram>0x[^\s]+)\s+(?P<size>0x[^\s]+)$").unwrap();
+        self.try_parse_map_contents_gnu(map_contents)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
 
+    pub(crate) fn try_parse_map_contents_gnu(
+        &mut self,
+        map_contents: &str,
+    ) -> Result<(), MapParseError> {
         let map_data = MapFile::preprocess_map_data_gnu(map_contents);
-
-        let mut temp_segment_list = vec![segment::Segment::new_placeholder()];
-
-        let mut in_section = false;
-
-        let mut prev_line = "";
-        for line in map_data.lines() {
-            if in_section {
-                if !line.starts_with("        ") {
-                    in_section = false;
-                } else if !regex_label.is_match(line) {
-                    // Filter out jump table's labels
-
-                    // Find symbols
-                    if let Some(entry_match) = regex_function_entry.captures(line) {
-                        // println!("{entry_match:?}");
-                        let sym_name = &entry_match["name"];
-
-                        if !BANNED_SYMBOL_NAMES.contains(&sym_name) {
-                            let sym_vram = utils::parse_hex(&entry_match["vram"]);
-
-                            let current_segment = temp_segment_list.last_mut().unwrap();
-                            let current_section = current_segment.sections_list.last_mut().unwrap();
-
-                            current_section
-                                .symbols
-                                .push(symbol::Symbol::new_default(sym_name.into(), sym_vram));
-                        }
-                    }
+        let line_offset = count_lines_before(map_contents, map_data);
+
+        // Lines without indentation that look like a segment entry always
+        // start a new segment, no matter what came before them.
+        let temp_segment_list = parse_segment_chunks(
+            map_data,
+            line_offset + 1,
+            |line| !line.starts_with(char::is_whitespace) && map_lexer::gnu_segment(line).is_some(),
+            |chunk, first_line_number| {
+                let mut parser = GnuLineParser::new();
+                // The header was already skipped
+                parser.found_memory_map = true;
+
+                for (line_index, line) in chunk.lines().enumerate() {
+                    parser.parse_line(line, first_line_number + line_index)?;
                 }
-            }
 
-            if !in_section {
-                if let Some(section_entry_match) = regex_section_data_entry.captures(line) {
-                    let filepath = PathBuf::from(&section_entry_match["name"]);
-                    let vram = utils::parse_hex(&section_entry_match["vram"]);
-                    let size = utils::parse_hex(&section_entry_match["size"]);
-                    let section_type = &section_entry_match["section"];
+                Ok(parser.temp_segment_list)
+            },
+        )?;
 
-                    if size > 0 {
-                        // TODO: de-duplicate the following code:
+        self.segments_list =
+            Self::post_process_segments_gnu(temp_segment_list, &mut self.parse_warnings);
 
-                        if !section_type.is_empty() {
-                            in_section = true;
-                            let current_segment = temp_segment_list.last_mut().unwrap();
-
-                            current_segment
-                                .sections_list
-                                .push(section::Section::new_default(
-                                    filepath,
-                                    vram,
-                                    size,
-                                    section_type.into(),
-                                ));
-                        } else if let Some(section_alone_match) =
-                            regex_section_alone_entry.captures(prev_line)
-                        {
-                            // Some sections may be too large, making the entry be splitted between two lines, making the section name be in one line and the rest of the info in the next one
-
-                            let section_type = &section_alone_match["section"];
-
-                            in_section = true;
-                            let current_segment = temp_segment_list.last_mut().unwrap();
-
-                            current_segment
-                                .sections_list
-                                .push(section::Section::new_default(
-                                    filepath,
-                                    vram,
-                                    size,
-                                    section_type.into(),
-                                ));
-                        }
-                    }
-                } else if let Some(segment_entry_match) = regex_segment_entry.captures(line) {
-                    let mut name = &segment_entry_match["name"];
-                    let vram = utils::parse_hex(&segment_entry_match["vram"]);
-                    let size = utils::parse_hex(&segment_entry_match["size"]);
-                    let vrom = Some(utils::parse_hex(&segment_entry_match["vrom"]));
-
-                    if name.is_empty() {
-                        // If the segment name is too long then this line gets break in two lines
-                        name = prev_line;
-                    }
-
-                    temp_segment_list.push(segment::Segment::new_default(
-                        name.into(),
-                        vram,
-                        size,
-                        vrom,
-                    ));
-                } else if let Some(segment_entry_match) = regex_romless_segment_entry.captures(line)
-                {
-                    // Some segments do not have a rom address
-                    let mut name = &segment_entry_match["name"];
-                    let vram = utils::parse_hex(&segment_entry_match["vram"]);
-                    let size = utils::parse_hex(&segment_entry_match["size"]);
-                    let vrom = None;
-
-                    if name.is_empty() {
-                        // If the segment name is too long then this line gets break in two lines
-                        name = prev_line;
-                    }
-
-                    temp_segment_list.push(segment::Segment::new_default(
-                        name.into(),
-                        vram,
-                        size,
-                        vrom,
-                    ));
-                } else if let Some(fill_match) = regex_fill.captures(line) {
-                    // Make a dummy file to handle *fill*
-                    let mut filepath = PathBuf::new();
-                    let mut vram = 0;
-                    let size = utils::parse_hex(&fill_match["size"]);
-                    let mut section_type = "".to_owned();
-
-                    let current_segment = temp_segment_list.last_mut().unwrap();
-
-                    if !current_segment.sections_list.is_empty() {
-                        let prev_section = current_segment.sections_list.last().unwrap();
-                        let mut name = prev_section
-                            .filepath
-                            .file_name()
-                            .unwrap_or_else(|| OsStr::new(""))
-                            .to_owned();
-
-                        name.push("__fill__");
-                        filepath = prev_section.filepath.with_file_name(name);
-                        vram = prev_section.vram + prev_section.size;
-                        section_type.clone_from(&prev_section.section_type);
-                    }
-
-                    current_segment
-                        .sections_list
-                        .push(section::Section::new_fill(
-                            filepath,
-                            vram,
-                            size,
-                            section_type,
-                        ));
-                }
-            }
-
-            prev_line = line;
-        }
-
-        self.segments_list = Self::post_process_segments_gnu(temp_segment_list);
+        Ok(())
     }
 
     fn post_process_segments_gnu(
         temp_segment_list: Vec<segment::Segment>,
+        warnings: &mut Vec<MapParseWarning>,
     ) -> Vec<segment::Segment> {
-        let mut segments_list = Vec::with_capacity(temp_segment_list.len());
-
-        // We need to keep a calculated rom in case the segment doesn't specify it explicitly
+        // We need to keep a calculated rom in case the segment doesn't specify it explicitly.
+        // It only depends on the sizes of the sections, so it can be figured out before
+        // processing each segment.
         let mut current_calculated_section_rom = 0;
 
+        let mut segments_with_rom = Vec::with_capacity(temp_segment_list.len());
         for (i, segment) in temp_segment_list.into_iter().enumerate() {
             if i == 0 && segment.is_placeholder() {
                 // skip the dummy segment if it has no size, sections or symbols
@@ -348,94 +606,147 @@ impl MapFile {
                 continue;
             }
 
-            let mut new_segment = segment.clone_no_sectionlist();
-
-            let mut vrom_offset = if let Some(vrom) = segment.vrom {
-                current_calculated_section_rom = vrom;
-                vrom
-            } else {
-                new_segment.vrom = Some(current_calculated_section_rom);
-                current_calculated_section_rom
-            };
-            for mut section in segment.sections_list.into_iter() {
-                if section.is_placeholder() {
-                    // drop placeholders
-                    continue;
+            let calculated_rom = segment.vrom.unwrap_or(current_calculated_section_rom);
+            current_calculated_section_rom = calculated_rom;
+            for section in &segment.sections_list {
+                if !section.is_placeholder() && !section.is_noload_section() {
+                    current_calculated_section_rom += section.size;
                 }
+            }
 
-                // The size of the section
-                let mut acummulated_size = 0;
-                let symbols_count = section.symbols.len();
-                let is_noload_section = section.is_noload_section();
+            segments_with_rom.push((segment, calculated_rom));
+        }
 
-                if let Some(vrom) = section.vrom {
-                    vrom_offset = vrom;
-                }
+        let processed = map_segments(segments_with_rom, |(segment, calculated_rom)| {
+            let mut segment_warnings = Vec::new();
+            let new_segment =
+                Self::post_process_segment_gnu(segment, calculated_rom, &mut segment_warnings);
+            (new_segment, segment_warnings)
+        });
 
-                if !is_noload_section {
-                    section.vrom = Some(vrom_offset);
-                }
+        join_processed_segments(processed, warnings)
+    }
 
-                if symbols_count > 0 {
-                    let mut sym_vrom = vrom_offset;
+    fn post_process_segment_gnu(
+        segment: segment::Segment,
+        calculated_rom: u64,
+        warnings: &mut Vec<MapParseWarning>,
+    ) -> segment::Segment {
+        let mut new_segment = segment.clone_no_sectionlist();
+
+        let mut vrom_offset = if let Some(vrom) = segment.vrom {
+            vrom
+        } else {
+            new_segment.vrom = Some(calculated_rom);
+            calculated_rom
+        };
+        for mut section in segment.sections_list.into_iter() {
+            if section.is_placeholder() {
+                // drop placeholders
+                continue;
+            }
+
+            // The size of the section
+            let mut acummulated_size = 0;
+            let symbols_count = section.symbols.len();
+            let is_noload_section = section.is_noload_section();
+
+            if let Some(vrom) = section.vrom {
+                vrom_offset = vrom;
+            }
+
+            if !is_noload_section {
+                section.vrom = Some(vrom_offset);
+            }
 
-                    // The first symbol of the section on the mapfile may not be the actual first
-                    // symbol if it is marked `static`, be a jumptable, etc, producing a mismatch
-                    // on the vrom address of each symbol of this section.
-                    // A way to adjust this difference is by increasing the start of the vrom
-                    // by the difference in vram address between the first symbol and the vram
-                    // of the section.
-                    if let Some(first_sym) = section.symbols.first() {
-                        sym_vrom = sym_vrom + first_sym.vram - section.vram;
+            if symbols_count > 0 {
+                let mut sym_vrom = vrom_offset;
+
+                // The first symbol of the section on the mapfile may not be the actual first
+                // symbol if it is marked `static`, be a jumptable, etc, producing a mismatch
+                // on the vrom address of each symbol of this section.
+                // A way to adjust this difference is by increasing the start of the vrom
+                // by the difference in vram address between the first symbol and the vram
+                // of the section.
+                if let Some(first_sym) = section.symbols.first() {
+                    if let Some(diff) = first_sym.vram.checked_sub(section.vram) {
+                        sym_vrom += diff;
 
                         // Aditionally, if the first symbol is missing then calculation of the size
                         // for the last symbol would be wrong, since we subtract the accumulated
                         // size of each symbol from the section's total size to calculate it.
                         // We need to adjust the total size by this difference too.
-                        acummulated_size += first_sym.vram - section.vram;
+                        acummulated_size += diff;
+                    } else {
+                        push_warning(
+                            warnings,
+                            &new_segment.name,
+                            &section.filepath,
+                            &first_sym.name,
+                            MapParseWarningKind::SymbolBeforeSection,
+                        );
                     }
+                }
 
-                    // Calculate size of each symbol
-                    for index in 0..symbols_count - 1 {
-                        let next_sym_vram = section.symbols[index + 1].vram;
-                        let sym = &mut section.symbols[index];
-                        let sym_size = next_sym_vram - sym.vram;
-                        acummulated_size += sym_size;
-
-                        sym.size = sym_size;
-
-                        if !is_noload_section {
-                            // Only set vrom of non bss variables
-                            sym.vrom = Some(sym_vrom);
-                            sym_vrom += sym_size;
-                        }
-                    }
+                // Calculate size of each symbol
+                for index in 0..symbols_count - 1 {
+                    let next_sym_vram = section.symbols[index + 1].vram;
+                    let sym = &mut section.symbols[index];
+                    let sym_size = next_sym_vram.checked_sub(sym.vram).unwrap_or_else(|| {
+                        push_warning(
+                            warnings,
+                            &new_segment.name,
+                            &section.filepath,
+                            &sym.name,
+                            MapParseWarningKind::UnsortedSymbol,
+                        );
+                        sym.size_is_unknown = true;
+                        0
+                    });
+                    acummulated_size += sym_size;
 
-                    // Calculate size of last symbol of the section
-                    let sym = &mut section.symbols[symbols_count - 1];
-                    let sym_size = section.size - acummulated_size;
                     sym.size = sym_size;
+
                     if !is_noload_section {
+                        // Only set vrom of non bss variables
                         sym.vrom = Some(sym_vrom);
-                        //sym_vrom += sym_size;
+                        sym_vrom += sym_size;
                     }
-
-                    Self::fixup_non_matching_symbols_for_section(&mut section);
                 }
 
+                // Calculate size of last symbol of the section
+                let sym = &mut section.symbols[symbols_count - 1];
+                let sym_size = section
+                    .size
+                    .checked_sub(acummulated_size)
+                    .unwrap_or_else(|| {
+                        push_warning(
+                            warnings,
+                            &new_segment.name,
+                            &section.filepath,
+                            &sym.name,
+                            MapParseWarningKind::SectionSizeExceeded,
+                        );
+                        sym.size_is_unknown = true;
+                        0
+                    });
+                sym.size = sym_size;
                 if !is_noload_section {
-                    vrom_offset += section.size;
-                    current_calculated_section_rom += section.size;
+                    sym.vrom = Some(sym_vrom);
+                    //sym_vrom += sym_size;
                 }
 
-                new_segment.sections_list.push(section);
+                Self::fixup_non_matching_symbols_for_section(&mut section);
             }
 
-            segments_list.push(new_segment);
+            if !is_noload_section {
+                vrom_offset += section.size;
+            }
+
+            new_segment.sections_list.push(section);
         }
 
-        segments_list.shrink_to_fit();
-        segments_list
+        new_segment
     }
 
     /**
@@ -448,199 +759,179 @@ impl MapFile {
         note = "Prefer `MapFile::new_from_lld_map_str` instead"
     )]
     pub fn parse_map_contents_lld(&mut self, map_contents: &str) {
-        let map_data = map_contents;
-
-        // Every line starts with this information, so instead of duplicating it we put them on one single regex
-        let regex_row_entry = Regex::new(r"^\s*(?P<vram>[0-9a-fA-F]+)\s+(?P<vrom>[0-9a-fA-F]+)\s+(?P<size>[0-9a-fA-F]+)\s+(?P<align>[0-9a-fA-F]+) ").unwrap();
-
-        let regex_segment_entry = Regex::new(r"^(?P<name>[^\s]+)$").unwrap();
-        let regex_fill = Regex::new(r"^\s+(?P<expr>\.\s*\+=\s*.+)$").unwrap();
-        let regex_section_data_entry =
-            Regex::new(r"^\s+(?P<name>[^\s]+):\((?P<section>[^\s()]+)\)$$").unwrap();
-        let regex_label = Regex::new(r"^\s+(?P<name>\.?L[0-9A-F]{8})$").unwrap();
-        let regex_symbol_entry = Regex::new(r"^\s+(?P<name>[^\s]+)$").unwrap();
-
-        let mut temp_segment_list = vec![segment::Segment::new_placeholder()];
-
-        for line in map_data.lines() {
-            if let Some(row_entry_match) = regex_row_entry.captures(line) {
-                let vram = utils::parse_hex(&row_entry_match["vram"]);
-                let vrom = Some(utils::parse_hex(&row_entry_match["vrom"]));
-                let size = utils::parse_hex(&row_entry_match["size"]);
-                let align = utils::parse_hex(&row_entry_match["align"]);
-
-                let subline = &line[row_entry_match.get(0).unwrap().len()..];
-
-                if let Some(segment_entry_match) = regex_segment_entry.captures(subline) {
-                    let name = &segment_entry_match["name"];
-
-                    let mut new_segment =
-                        segment::Segment::new_default(name.into(), vram, size, vrom);
-                    new_segment.align = Some(align);
-
-                    temp_segment_list.push(new_segment);
-                } else if regex_fill.is_match(subline) {
-                    // Make a dummy section to handle pads (. += XX)
-
-                    let mut filepath = PathBuf::new();
-                    let mut section_type = "".to_owned();
-
-                    let current_segment = temp_segment_list.last_mut().unwrap();
-
-                    if !current_segment.sections_list.is_empty() {
-                        let prev_section = current_segment.sections_list.last().unwrap();
-                        let mut name = prev_section.filepath.file_name().unwrap().to_owned();
-
-                        name.push("__fill__");
-                        filepath = prev_section.filepath.with_file_name(name);
-                        section_type.clone_from(&prev_section.section_type);
-                    }
-
-                    let mut new_section =
-                        section::Section::new_fill(filepath, vram, size, section_type);
-                    if !utils::is_noload_section(&new_section.section_type) {
-                        new_section.vrom = vrom;
-                    }
-                    current_segment.sections_list.push(new_section);
-                } else if let Some(section_entry_match) = regex_section_data_entry.captures(subline)
-                {
-                    let filepath = PathBuf::from(&section_entry_match["name"]);
-                    let section_type = &section_entry_match["section"];
-
-                    if size > 0 {
-                        let current_segment = temp_segment_list.last_mut().unwrap();
-
-                        let mut new_section = section::Section::new_default(
-                            filepath,
-                            vram,
-                            size,
-                            section_type.into(),
-                        );
-                        if !utils::is_noload_section(&new_section.section_type) {
-                            new_section.vrom = vrom;
-                        }
-                        new_section.align = Some(align);
-
-                        current_segment.sections_list.push(new_section);
-                    }
-                } else if regex_label.is_match(subline) {
-                    // pass
-                } else if let Some(symbol_entry_match) = regex_symbol_entry.captures(subline) {
-                    let name = &symbol_entry_match["name"];
+        self.try_parse_map_contents_lld(map_contents)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
 
-                    if !BANNED_SYMBOL_NAMES.contains(&name) {
-                        let current_segment = temp_segment_list.last_mut().unwrap();
-                        let current_section = current_segment.sections_list.last_mut().unwrap();
+    pub(crate) fn try_parse_map_contents_lld(
+        &mut self,
+        map_contents: &str,
+    ) -> Result<(), MapParseError> {
+        // Output sections are the only entries without indentation after the columns
+        let temp_segment_list = parse_segment_chunks(
+            map_contents,
+            1,
+            |line| {
+                map_lexer::lld_row(line).is_some_and(|x| map_lexer::lld_segment(x.rest).is_some())
+            },
+            |chunk, first_line_number| {
+                let mut parser = LldLineParser::new();
+
+                for (line_index, line) in chunk.lines().enumerate() {
+                    parser.parse_line(line, first_line_number + line_index)?;
+                }
 
-                        let mut new_symbol = symbol::Symbol::new_default(name.into(), vram);
-                        if size > 0 {
-                            new_symbol.size = size;
-                        }
-                        if !current_section.is_noload_section() {
-                            new_symbol.vrom = vrom
-                        }
-                        new_symbol.align = Some(align);
+                Ok(parser.temp_segment_list)
+            },
+        )?;
 
-                        current_section.symbols.push(new_symbol);
-                    }
-                }
-            }
-        }
+        self.segments_list =
+            Self::post_process_segments_lld(temp_segment_list, &mut self.parse_warnings);
 
-        self.segments_list = Self::post_process_segments_lld(temp_segment_list);
+        Ok(())
     }
 
     fn post_process_segments_lld(
         temp_segment_list: Vec<segment::Segment>,
+        warnings: &mut Vec<MapParseWarning>,
     ) -> Vec<segment::Segment> {
-        let mut segments_list = Vec::with_capacity(temp_segment_list.len());
+        let mut temp_segment_list = temp_segment_list;
+        if temp_segment_list
+            .first()
+            .is_some_and(|x| x.is_placeholder())
+        {
+            // skip the dummy segment if it has no size, sections or symbols
+            temp_segment_list.remove(0);
+        }
 
-        for (i, segment) in temp_segment_list.into_iter().enumerate() {
-            if i == 0 && segment.is_placeholder() {
-                // skip the dummy segment if it has no size, sections or symbols
-                continue;
-            }
+        let processed = map_segments(temp_segment_list, |segment| {
+            let mut segment_warnings = Vec::new();
+            let new_segment = Self::post_process_segment_lld(segment, &mut segment_warnings);
+            (new_segment, segment_warnings)
+        });
 
-            let mut new_segment = segment.clone_no_sectionlist();
+        join_processed_segments(processed, warnings)
+    }
 
-            for mut section in segment.sections_list.into_iter() {
-                if section.is_placeholder() {
-                    // drop placeholders
-                    continue;
-                }
+    fn post_process_segment_lld(
+        segment: segment::Segment,
+        warnings: &mut Vec<MapParseWarning>,
+    ) -> segment::Segment {
+        let mut new_segment = segment.clone_no_sectionlist();
 
-                let mut acummulated_size = 0;
-                let symbols_count = section.symbols.len();
+        for mut section in segment.sections_list.into_iter() {
+            if section.is_placeholder() {
+                // drop placeholders
+                continue;
+            }
 
-                if symbols_count > 0 {
-                    // Calculate the size of symbols that the map section did not report.
-                    // usually asm symbols and not C ones
+            let mut acummulated_size = 0;
+            let symbols_count = section.symbols.len();
 
-                    for index in 0..symbols_count - 1 {
-                        let next_sym_vram = section.symbols[index + 1].vram;
-                        let sym = &mut section.symbols[index];
+            if symbols_count > 0 {
+                // Calculate the size of symbols that the map section did not report.
+                // usually asm symbols and not C ones
 
-                        let sym_size = next_sym_vram - sym.vram;
-                        acummulated_size += sym_size;
+                for index in 0..symbols_count - 1 {
+                    let next_sym_vram = section.symbols[index + 1].vram;
+                    let sym = &mut section.symbols[index];
 
-                        if sym.size == 0 {
-                            sym.size = sym_size;
-                        }
-                    }
+                    let sym_size = next_sym_vram.checked_sub(sym.vram);
+                    acummulated_size += sym_size.unwrap_or(0);
 
-                    // Calculate size of last symbol of the section
-                    let sym = &mut section.symbols[symbols_count - 1];
                     if sym.size == 0 {
-                        let sym_size = section.size - acummulated_size;
-                        sym.size = sym_size;
+                        sym.size = sym_size.unwrap_or_else(|| {
+                            push_warning(
+                                warnings,
+                                &new_segment.name,
+                                &section.filepath,
+                                &sym.name,
+                                MapParseWarningKind::UnsortedSymbol,
+                            );
+                            sym.size_is_unknown = true;
+                            0
+                        });
                     }
+                }
 
-                    Self::fixup_non_matching_symbols_for_section(&mut section);
+                // Calculate size of last symbol of the section
+                let sym = &mut section.symbols[symbols_count - 1];
+                if sym.size == 0 {
+                    let sym_size =
+                        section
+                            .size
+                            .checked_sub(acummulated_size)
+                            .unwrap_or_else(|| {
+                                push_warning(
+                                    warnings,
+                                    &new_segment.name,
+                                    &section.filepath,
+                                    &sym.name,
+                                    MapParseWarningKind::SectionSizeExceeded,
+                                );
+                                sym.size_is_unknown = true;
+                                0
+                            });
+                    sym.size = sym_size;
                 }
 
-                new_segment.sections_list.push(section);
+                Self::fixup_non_matching_symbols_for_section(&mut section);
             }
 
-            segments_list.push(new_segment);
+            new_segment.sections_list.push(section);
         }
 
-        segments_list.shrink_to_fit();
-        segments_list
+        new_segment
     }
 
-    fn parse_map_contents_mw(&mut self, map_contents: &str) {
+    fn try_parse_map_contents_mw(&mut self, map_contents: &str) -> Result<(), MapParseError> {
         let map_data = preprocess_map_data_mw(map_contents);
+        let line_offset = count_lines_before(map_contents, map_data);
 
-        let memory_map = parse_memory_map_mw(map_data);
+        let memory_map = parse_memory_map_mw(map_contents, map_data)?;
         let regex_entries = MwRegexEntries::new(map_data);
 
+        if regex_entries.is_mwldarm {
+            self.segments_list =
+                parse_segments_mwldarm(map_data, line_offset, &regex_entries, &memory_map)?;
+            return Ok(());
+        }
+
         let mut temp_segment_list = vec![segment::Segment::new_placeholder()];
+        let mut interner = Interner::default();
 
         // Use a bunch of characters that shouldn't be valid in any os as a marker that we haven't found a file yet.
         let invalid_file_name = "invalid file <>:\"/\\|?*";
         let mut current_filename = invalid_file_name.to_string();
 
-        for line in map_data.lines() {
+        for (line_index, line) in map_data.lines().enumerate() {
+            let line_number = line_offset + line_index + 1;
+
             // Check for regex_entries.common_row first since it is more likely to match
             if let (Some(row_entry_match), false) = (
                 regex_entries.common_row.captures(line),
                 temp_segment_list.is_empty(),
             ) {
-                let starting = utils::parse_hex(&row_entry_match["starting"]);
-                let size = utils::parse_hex(&row_entry_match["size"]);
-                let vram = utils::parse_hex(&row_entry_match["vram"]);
-                let align = utils::parse_hex(&row_entry_match["align"]);
-
-                let rom = row_entry_match.name("rom").map_or_else(
-                    || {
-                        temp_segment_list
-                            .last()
-                            .unwrap()
-                            .vrom
-                            .map(|segment_rom| segment_rom + starting)
-                    },
-                    |x| Some(utils::parse_hex(x.as_str())),
-                );
+                let starting = parse_hex(&row_entry_match["starting"], line_number, line)?;
+                let size = parse_hex(&row_entry_match["size"], line_number, line)?;
+                let vram = parse_hex(&row_entry_match["vram"], line_number, line)?;
+                let align = parse_hex(&row_entry_match["align"], line_number, line)?;
+
+                let rom = if let Some(x) = row_entry_match.name("rom") {
+                    Some(parse_hex(x.as_str(), line_number, line)?)
+                } else if let Some(segment_rom) =
+                    temp_segment_list.last().and_then(|segment| segment.vrom)
+                {
+                    Some(segment_rom.checked_add(starting).ok_or_else(|| {
+                        MapParseError::new_at_line(
+                            line_number,
+                            line,
+                            MapParseErrorKind::AddressOverflow,
+                        )
+                    })?)
+                } else {
+                    None
+                };
 
                 let subline = &row_entry_match["subline"];
 
@@ -655,10 +946,13 @@ impl MapFile {
 
                         if !BANNED_SYMBOL_NAMES.contains(&symbol) {
                             let current_segment = temp_segment_list.last_mut().unwrap();
-                            let current_section = current_segment.sections_list.last_mut().unwrap();
+                            let current_section = current_segment
+                                .sections_list
+                                .last_mut()
+                                .ok_or_else(|| symbol_outside_section(line_number, line))?;
 
                             let mut new_symbol =
-                                symbol::Symbol::new_default(symbol.to_string(), vram);
+                                symbol::Symbol::new_default(interner.intern_str(symbol), vram);
                             if size > 0 {
                                 new_symbol.size = size;
                             }
@@ -674,17 +968,13 @@ impl MapFile {
                     } else {
                         // New file!
                         if size > 0 {
-                            let section_type = &symbol_entry_match["name"];
-                            let filepath = PathBuf::from(filename);
+                            let section_type = interner.intern_str(&symbol_entry_match["name"]);
+                            let filepath = interner.intern_path(Path::new(filename));
 
                             let current_segment = temp_segment_list.last_mut().unwrap();
 
-                            let mut new_section = section::Section::new_default(
-                                filepath,
-                                vram,
-                                size,
-                                section_type.into(),
-                            );
+                            let mut new_section =
+                                section::Section::new_default(filepath, vram, size, section_type);
                             if !utils::is_noload_section(&new_section.section_type) {
                                 new_section.vrom = rom
                             }
@@ -705,18 +995,26 @@ impl MapFile {
                     let current_segment = temp_segment_list.last_mut().unwrap();
 
                     let mut filepath = PathBuf::new();
-                    let mut section_type = "".to_owned();
+                    let mut section_type = InternedStr::default();
 
                     if let Some(prev_section) = current_segment.sections_list.last() {
-                        let mut name = prev_section.filepath.file_name().unwrap().to_owned();
+                        let mut name = prev_section
+                            .filepath
+                            .file_name()
+                            .unwrap_or_else(|| OsStr::new(""))
+                            .to_owned();
 
                         name.push("__fill__");
                         filepath = prev_section.filepath.with_file_name(name);
                         section_type.clone_from(&prev_section.section_type);
                     }
 
-                    let mut new_section =
-                        section::Section::new_fill(filepath, vram, size, section_type);
+                    let mut new_section = section::Section::new_fill(
+                        interner.intern_path(&filepath),
+                        vram,
+                        size,
+                        section_type,
+                    );
                     new_section.align = Some(align);
                     if !utils::is_noload_section(&new_section.section_type) {
                         new_section.vrom = rom;
@@ -751,17 +1049,21 @@ impl MapFile {
         }
 
         self.segments_list = Self::post_process_segments_mw(temp_segment_list);
+
+        Ok(())
     }
 
     fn post_process_segments_mw(temp_segment_list: Vec<segment::Segment>) -> Vec<segment::Segment> {
-        let mut segments_list = Vec::with_capacity(temp_segment_list.len());
-
-        for (i, segment) in temp_segment_list.into_iter().enumerate() {
-            if i == 0 && (segment.sections_list.is_empty() || segment.is_placeholder()) {
-                // skip the dummy segment if it has no size, sections or symbols
-                continue;
-            }
+        let mut temp_segment_list = temp_segment_list;
+        if temp_segment_list
+            .first()
+            .is_some_and(|x| x.sections_list.is_empty() || x.is_placeholder())
+        {
+            // skip the dummy segment if it has no size, sections or symbols
+            temp_segment_list.remove(0);
+        }
 
+        map_segments(temp_segment_list, |segment| {
             let mut new_segment = segment.clone_no_sectionlist();
 
             for mut section in segment.sections_list.into_iter() {
@@ -778,11 +1080,8 @@ impl MapFile {
                 new_segment.sections_list.push(section);
             }
 
-            segments_list.push(new_segment);
-        }
-
-        segments_list.shrink_to_fit();
-        segments_list
+            new_segment
+        })
     }
 }
 
@@ -805,7 +1104,10 @@ struct MwMemoryMapEntry {
     file_offset: u64,
 }
 
-fn parse_memory_map_mw(map_data: &str) -> HashMap<String, MwMemoryMapEntry> {
+fn parse_memory_map_mw(
+    map_contents: &str,
+    map_data: &str,
+) -> Result<HashMap<String, MwMemoryMapEntry>, MapParseError> {
     let map_data = {
         if let Some(start_index) = map_data.find("Memory map:") {
             if let Some(end_index) = map_data[start_index..].find("Linker generated symbols:") {
@@ -819,14 +1121,18 @@ fn parse_memory_map_mw(map_data: &str) -> HashMap<String, MwMemoryMapEntry> {
     };
 
     let mut memory_map = HashMap::new();
-    let entry = Regex::new(r"^\s*(?P<name>[^ ]+)\s+(?P<address>[0-9a-fA-F]+)\s+(?P<size>[0-9a-fA-F]+)\s+(?P<offset>[0-9a-fA-F]+)$").unwrap();
+    let entry = &*REGEX_MW_MEMORY_MAP_ENTRY;
+
+    let line_offset = count_lines_before(map_contents, map_data);
+
+    for (line_index, line) in map_data.lines().enumerate() {
+        let line_number = line_offset + line_index + 1;
 
-    for line in map_data.lines() {
         if let Some(entry_match) = entry.captures(line) {
             let name = &entry_match["name"];
-            let starting_address = utils::parse_hex(&entry_match["address"]);
-            let size = utils::parse_hex(&entry_match["size"]);
-            let file_offset = utils::parse_hex(&entry_match["offset"]);
+            let starting_address = parse_hex(&entry_match["address"], line_number, line)?;
+            let size = parse_hex(&entry_match["size"], line_number, line)?;
+            let file_offset = parse_hex(&entry_match["offset"], line_number, line)?;
 
             memory_map.insert(
                 name.to_string(),
@@ -839,41 +1145,511 @@ fn parse_memory_map_mw(map_data: &str) -> HashMap<String, MwMemoryMapEntry> {
         }
     }
 
-    memory_map
+    Ok(memory_map)
 }
 
 struct MwRegexEntries {
-    common_row: Regex,
-    segment: Regex,
-    label: Regex,
-    symbol: Regex,
-    fill: Regex,
+    /// mwldarm (Nintendo DS) maps use a different layout, where each row
+    /// contains the address, size, section, symbol and file.
+    is_mwldarm: bool,
+    common_row: &'static Regex,
+    segment: &'static Regex,
+    label: &'static Regex,
+    symbol: &'static Regex,
+    fill: &'static Regex,
+    linker_symbol: &'static Regex,
 }
 
 impl MwRegexEntries {
     fn new(map_data: &str) -> Self {
+        if map_data.contains("\n#>") || map_data.contains("\n# .") {
+            return Self {
+                is_mwldarm: true,
+                common_row: &REGEX_MWLDARM_ROW,
+                segment: &REGEX_MWLDARM_SEGMENT,
+                label: &REGEX_MWLDARM_LABEL,
+                symbol: &REGEX_MW_SYMBOL,
+                fill: &REGEX_MW_FILL,
+                linker_symbol: &REGEX_MW_LINKER_SYMBOL,
+            };
+        }
+
         // Almost every line starts with this information, so instead of duplicating it we put them on one single regex
-        let common_row = if map_data.contains("address  Size   address  offset") {
-            // mwld 2.7+
-            Regex::new(r"^\s*(?P<starting>[0-9a-fA-F]+)\s+(?P<size>[0-9a-fA-F]+)\s+(?P<vram>[0-9a-fA-F]+)\s+(?P<rom>[0-9a-fA-F]+)\s+(?P<align>[0-9a-fA-F]+)\s+(?P<subline>.+)").unwrap()
+        let common_row: &Regex = if map_data.contains("address  Size   address  offset") {
+            &REGEX_MW_ROW
         } else {
-            // mwld 1.3.2-
-            Regex::new(r"^\s*(?P<starting>[0-9a-fA-F]+)\s+(?P<size>[0-9a-fA-F]+)\s+(?P<vram>[0-9a-fA-F]+)\s+(?P<align>[0-9a-fA-F]+)\s+(?P<subline>.+)").unwrap()
+            &REGEX_MW_ROMLESS_ROW
         };
 
-        let segment = Regex::new(r"^(?P<name>.+) section layout$").unwrap();
-        let label = Regex::new(r"^(?P<label>lbl_[0-9A-F]{8})\s+(?P<filename>.+?)\s*$").unwrap();
-        let symbol = Regex::new(r"^\s*(?P<name>[^ ]+)\s+(?P<filename>.+?)\s*$").unwrap();
-        let fill = Regex::new(r"^\s*\*fill\*\s*$").unwrap();
-
         Self {
+            is_mwldarm: false,
             common_row,
-            segment,
-            label,
-            symbol,
-            fill,
+            segment: &REGEX_MW_SEGMENT,
+            label: &REGEX_MW_LABEL,
+            symbol: &REGEX_MW_SYMBOL,
+            fill: &REGEX_MW_FILL,
+            linker_symbol: &REGEX_MW_LINKER_SYMBOL,
+        }
+    }
+}
+
+fn parse_segments_mwldarm(
+    map_data: &str,
+    line_offset: usize,
+    regex_entries: &MwRegexEntries,
+    memory_map: &HashMap<String, MwMemoryMapEntry>,
+) -> Result<Vec<segment::Segment>, MapParseError> {
+    let mut segments_list: Vec<segment::Segment> = Vec::new();
+    let mut interner = Interner::default();
+
+    for (line_index, line) in map_data.lines().enumerate() {
+        let line_number = line_offset + line_index + 1;
+
+        if let Some(segment_entry_match) = regex_entries.segment.captures(line) {
+            // Every overlay gets its own block, so they become segments too
+            let name = &segment_entry_match["name"];
+
+            segments_list.push(segment::Segment::new_default(name.to_string(), 0, 0, None));
+        } else if regex_entries.linker_symbol.is_match(line) {
+            // Symbols defined by the linker command file, like
+            // `SDK_OVERLAY_*` ones. They don't belong to any file.
+        } else if let Some(row_entry_match) = regex_entries.common_row.captures(line) {
+            let Some(current_segment) = segments_list.last_mut() else {
+                continue;
+            };
+
+            let vram = parse_hex(&row_entry_match["vram"], line_number, line)?;
+            let size = parse_hex(&row_entry_match["size"], line_number, line)?;
+            let section_type = &row_entry_match["section"];
+            let name = &row_entry_match["name"];
+            let filename = &row_entry_match["filename"];
+
+            let is_new_section = current_segment.sections_list.last().map_or(true, |x| {
+                x.section_type != section_type || x.filepath.as_os_str() != filename
+            });
+            if is_new_section {
+                current_segment
+                    .sections_list
+                    .push(section::Section::new_default(
+                        interner.intern_path(Path::new(filename)),
+                        vram,
+                        0,
+                        interner.intern_str(section_type),
+                    ));
+            }
+            let current_section = current_segment.sections_list.last_mut().unwrap();
+            let Some(row_end) = vram.checked_add(size) else {
+                return Err(MapParseError::new_at_line(
+                    line_number,
+                    line,
+                    MapParseErrorKind::AddressOverflow,
+                ));
+            };
+            current_section.size = row_end
+                .max(current_section.vram.saturating_add(current_section.size))
+                .saturating_sub(current_section.vram);
+
+            // `$a`, `$t` and `$d` mark the start of ARM code, Thumb code and
+            // data, they are not real symbols.
+            if regex_entries.label.is_match(name)
+                || name == section_type
+                || name == filename
+                || BANNED_SYMBOL_NAMES.contains(&name)
+            {
+                continue;
+            }
+
+            let mut new_symbol = symbol::Symbol::new_default(interner.intern_str(name), vram);
+            if size > 0 {
+                new_symbol.size = size;
+            }
+            current_section.symbols.push(new_symbol);
+        }
+    }
+
+    for segment in &mut segments_list {
+        if let (Some(first), Some(last)) =
+            (segment.sections_list.first(), segment.sections_list.last())
+        {
+            segment.vram = first.vram;
+            segment.size = last
+                .vram
+                .saturating_add(last.size)
+                .saturating_sub(first.vram);
+        }
+
+        let memory_map_entry = memory_map
+            .get(&segment.name)
+            .or_else(|| memory_map.get(&format!(".{}", segment.name)));
+        if let Some(segment_entry) = memory_map_entry {
+            segment.vram = segment_entry.starting_address;
+            segment.size = segment_entry.size;
+            segment.vrom = Some(segment_entry.file_offset);
+        }
+
+        for section in &mut segment.sections_list {
+            if !section.is_noload_section() {
+                section.vrom = segment
+                    .vrom
+                    .zip(section.vram.checked_sub(segment.vram))
+                    .and_then(|(segment_vrom, offset)| segment_vrom.checked_add(offset));
+            }
+            for sym in &mut section.symbols {
+                sym.vrom = section
+                    .vrom
+                    .zip(sym.vram.checked_sub(section.vram))
+                    .and_then(|(section_vrom, offset)| section_vrom.checked_add(offset));
+            }
+
+            MapFile::fixup_non_matching_symbols_for_section(section);
         }
     }
+
+    Ok(segments_list)
+}
+
+/// Parses a GNU ld map one line at a time, so it can be fed from a reader
+/// without holding the whole map in memory.
+struct GnuLineParser {
+    temp_segment_list: Vec<segment::Segment>,
+    interner: Interner,
+    in_section: bool,
+    prev_line: String,
+
+    /// Whether the `Linker script and memory map` header was already found
+    /// (or skipped by the caller).
+    found_memory_map: bool,
+    /// Whether one of the [`GNU_PREAMBLE_TITLES`] was found. Lines after it
+    /// are only parsed to look for errors, and anything built from them is
+    /// dropped right away, so huge blocks like the archive members of LTO
+    /// builds don't pile up in memory.
+    in_preamble: bool,
+    /// The first error found before the header. Everything before the header
+    /// gets discarded once it is found, so this is only reported if the map
+    /// doesn't have that header.
+    pending_error: Option<MapParseError>,
+}
+
+impl GnuLineParser {
+    fn new() -> Self {
+        Self {
+            temp_segment_list: vec![segment::Segment::new_placeholder()],
+            interner: Interner::default(),
+            in_section: false,
+            prev_line: String::new(),
+
+            found_memory_map: false,
+            in_preamble: false,
+            pending_error: None,
+        }
+    }
+
+    fn parse_line(&mut self, line: &str, line_number: usize) -> Result<(), MapParseError> {
+        if self.found_memory_map {
+            return self.parse_map_line(line, line_number);
+        }
+
+        // Skip the stuff we don't care about, same as `preprocess_map_data_gnu`
+        if line_number > 1 && line.starts_with("Linker script and memory map") {
+            self.found_memory_map = true;
+            self.temp_segment_list = vec![segment::Segment::new_placeholder()];
+            self.interner = Interner::default();
+            self.in_section = false;
+            self.prev_line.clear();
+            self.pending_error = None;
+            return Ok(());
+        }
+
+        if !self.in_preamble && GNU_PREAMBLE_TITLES.iter().any(|x| line.starts_with(x)) {
+            self.in_preamble = true;
+            self.temp_segment_list = vec![segment::Segment::new_placeholder()];
+        }
+        if self.in_preamble && self.pending_error.is_some() {
+            // Only the first error would be reported
+            return Ok(());
+        }
+
+        if let Err(e) = self.parse_map_line(line, line_number) {
+            self.pending_error.get_or_insert(e);
+        }
+        if self.in_preamble {
+            self.drop_preamble_entries();
+        }
+        Ok(())
+    }
+
+    /// Drops everything parsed from the preamble except for the last segment
+    /// and its last section, without its symbols, which is all the next line
+    /// can refer to.
+    fn drop_preamble_entries(&mut self) {
+        let last_segment = self.temp_segment_list.len() - 1;
+        self.temp_segment_list.drain(..last_segment);
+
+        let sections_list = &mut self.temp_segment_list[0].sections_list;
+        let last_section = sections_list.len().saturating_sub(1);
+        sections_list.drain(..last_section);
+        if let Some(section) = sections_list.last_mut() {
+            section.symbols.clear();
+        }
+
+        self.interner = Interner::default();
+    }
+
+    fn parse_map_line(&mut self, line: &str, line_number: usize) -> Result<(), MapParseError> {
+        if self.in_section {
+            if !line.starts_with("        ") {
+                self.in_section = false;
+            } else if !map_lexer::gnu_is_label(line) {
+                // Filter out jump table's labels
+
+                // Find symbols
+                if let Some(entry) = map_lexer::gnu_symbol(line) {
+                    let sym_name = entry.name;
+
+                    if !BANNED_SYMBOL_NAMES.contains(&sym_name) {
+                        let sym_vram = parse_hex(entry.vram, line_number, line)?;
+
+                        let current_segment = self.temp_segment_list.last_mut().unwrap();
+                        let current_section = current_segment
+                            .sections_list
+                            .last_mut()
+                            .ok_or_else(|| symbol_outside_section(line_number, line))?;
+
+                        current_section.symbols.push(symbol::Symbol::new_default(
+                            self.interner.intern_str(sym_name),
+                            sym_vram,
+                        ));
+                    }
+                }
+            }
+        }
+
+        if !self.in_section {
+            if let Some(section_entry) = map_lexer::gnu_section_data(line) {
+                let vram = parse_hex(section_entry.vram, line_number, line)?;
+                let size = parse_hex(section_entry.size, line_number, line)?;
+                let section_type = section_entry.section;
+
+                if size > 0 {
+                    let filepath = self.interner.intern_path(Path::new(section_entry.name));
+
+                    // TODO: de-duplicate the following code:
+
+                    if !section_type.is_empty() {
+                        self.in_section = true;
+                        let current_segment = self.temp_segment_list.last_mut().unwrap();
+
+                        current_segment
+                            .sections_list
+                            .push(section::Section::new_default(
+                                filepath,
+                                vram,
+                                size,
+                                self.interner.intern_str(section_type),
+                            ));
+                    } else if let Some(section_type) = map_lexer::gnu_section_alone(&self.prev_line)
+                    {
+                        // Some sections may be too large, making the entry be splitted between two lines, making the section name be in one line and the rest of the info in the next one
+
+                        self.in_section = true;
+                        let current_segment = self.temp_segment_list.last_mut().unwrap();
+
+                        current_segment
+                            .sections_list
+                            .push(section::Section::new_default(
+                                filepath,
+                                vram,
+                                size,
+                                self.interner.intern_str(section_type),
+                            ));
+                    }
+                }
+            } else if let Some(segment_entry) = map_lexer::gnu_segment(line) {
+                let mut name = segment_entry.name;
+                let vram = parse_hex(segment_entry.vram, line_number, line)?;
+                let size = parse_hex(segment_entry.size, line_number, line)?;
+                // Some segments do not have a rom address
+                let vrom = match segment_entry.vrom {
+                    Some(x) => Some(parse_hex(x, line_number, line)?),
+                    None => None,
+                };
+
+                if name.is_empty() {
+                    // If the segment name is too long then this line gets break in two lines
+                    name = &self.prev_line;
+                }
+
+                self.temp_segment_list.push(segment::Segment::new_default(
+                    name.into(),
+                    vram,
+                    size,
+                    vrom,
+                ));
+            } else if let Some(fill_entry) = map_lexer::gnu_fill(line) {
+                // Make a dummy file to handle *fill*
+                let mut filepath = PathBuf::new();
+                let mut vram = 0;
+                let size = parse_hex(fill_entry.size, line_number, line)?;
+                let mut section_type = InternedStr::default();
+
+                let current_segment = self.temp_segment_list.last_mut().unwrap();
+
+                if !current_segment.sections_list.is_empty() {
+                    let prev_section = current_segment.sections_list.last().unwrap();
+                    let mut name = prev_section
+                        .filepath
+                        .file_name()
+                        .unwrap_or_else(|| OsStr::new(""))
+                        .to_owned();
+
+                    name.push("__fill__");
+                    filepath = prev_section.filepath.with_file_name(name);
+                    vram = prev_section.vram + prev_section.size;
+                    section_type.clone_from(&prev_section.section_type);
+                }
+
+                current_segment
+                    .sections_list
+                    .push(section::Section::new_fill(
+                        self.interner.intern_path(&filepath),
+                        vram,
+                        size,
+                        section_type,
+                    ));
+            }
+        }
+
+        self.prev_line.clear();
+        self.prev_line.push_str(line);
+
+        Ok(())
+    }
+
+    fn finish(
+        self,
+        warnings: &mut Vec<MapParseWarning>,
+    ) -> Result<Vec<segment::Segment>, MapParseError> {
+        if let Some(e) = self.pending_error {
+            return Err(e);
+        }
+        if self.in_preamble && !self.found_memory_map {
+            // The map was cut before its memory map
+            return Ok(Vec::new());
+        }
+
+        Ok(MapFile::post_process_segments_gnu(
+            self.temp_segment_list,
+            warnings,
+        ))
+    }
+}
+
+/// Parses a clang ld.lld map one line at a time.
+struct LldLineParser {
+    temp_segment_list: Vec<segment::Segment>,
+    interner: Interner,
+}
+
+impl LldLineParser {
+    fn new() -> Self {
+        Self {
+            temp_segment_list: vec![segment::Segment::new_placeholder()],
+            interner: Interner::default(),
+        }
+    }
+
+    fn parse_line(&mut self, line: &str, line_number: usize) -> Result<(), MapParseError> {
+        // Every line starts with the same columns
+        if let Some(row_entry) = map_lexer::lld_row(line) {
+            let vram = parse_hex(row_entry.vram, line_number, line)?;
+            let vrom = Some(parse_hex(row_entry.vrom, line_number, line)?);
+            let size = parse_hex(row_entry.size, line_number, line)?;
+            let align = parse_hex(row_entry.align, line_number, line)?;
+
+            let subline = row_entry.rest;
+
+            if let Some(name) = map_lexer::lld_segment(subline) {
+                let mut new_segment = segment::Segment::new_default(name.into(), vram, size, vrom);
+                new_segment.align = Some(align);
+
+                self.temp_segment_list.push(new_segment);
+            } else if map_lexer::lld_is_fill(subline) {
+                // Make a dummy section to handle pads (. += XX)
+
+                let mut filepath = PathBuf::new();
+                let mut section_type = InternedStr::default();
+
+                let current_segment = self.temp_segment_list.last_mut().unwrap();
+
+                if !current_segment.sections_list.is_empty() {
+                    let prev_section = current_segment.sections_list.last().unwrap();
+                    let mut name = prev_section
+                        .filepath
+                        .file_name()
+                        .unwrap_or_else(|| OsStr::new(""))
+                        .to_owned();
+
+                    name.push("__fill__");
+                    filepath = prev_section.filepath.with_file_name(name);
+                    section_type.clone_from(&prev_section.section_type);
+                }
+
+                let mut new_section = section::Section::new_fill(
+                    self.interner.intern_path(&filepath),
+                    vram,
+                    size,
+                    section_type,
+                );
+                if !utils::is_noload_section(&new_section.section_type) {
+                    new_section.vrom = vrom;
+                }
+                current_segment.sections_list.push(new_section);
+            } else if let Some(section_entry) = map_lexer::lld_section_data(subline) {
+                if size > 0 {
+                    let filepath = self.interner.intern_path(Path::new(section_entry.name));
+                    let section_type = self.interner.intern_str(section_entry.section);
+                    let current_segment = self.temp_segment_list.last_mut().unwrap();
+
+                    let mut new_section =
+                        section::Section::new_default(filepath, vram, size, section_type);
+                    if !utils::is_noload_section(&new_section.section_type) {
+                        new_section.vrom = vrom;
+                    }
+                    new_section.align = Some(align);
+
+                    current_segment.sections_list.push(new_section);
+                }
+            } else if map_lexer::lld_is_label(subline) {
+                // pass
+            } else if let Some(name) = map_lexer::lld_symbol(subline) {
+                if !BANNED_SYMBOL_NAMES.contains(&name) {
+                    let current_segment = self.temp_segment_list.last_mut().unwrap();
+                    let current_section = current_segment
+                        .sections_list
+                        .last_mut()
+                        .ok_or_else(|| symbol_outside_section(line_number, line))?;
+
+                    let mut new_symbol =
+                        symbol::Symbol::new_default(self.interner.intern_str(name), vram);
+                    if size > 0 {
+                        new_symbol.size = size;
+                    }
+                    if !current_section.is_noload_section() {
+                        new_symbol.vrom = vrom
+                    }
+                    new_symbol.align = Some(align);
+
+                    current_section.symbols.push(new_symbol);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn finish(self, warnings: &mut Vec<MapParseWarning>) -> Vec<segment::Segment> {
+        MapFile::post_process_segments_lld(self.temp_segment_list, warnings)
+    }
 }
 
 impl MapFile {
@@ -891,7 +1667,7 @@ impl MapFile {
         map_data
     }
 
-    fn fixup_non_matching_symbols_for_section(section: &mut Section) {
+    pub(crate) fn fixup_non_matching_symbols_for_section(section: &mut Section) {
         // Fixup `.NON_MATCHING` symbols.
         // These kind of symbols have the same address as their
         // real counterpart, but their order is not guaranteed,
@@ -926,3 +1702,184 @@ impl MapFile {
         }
     }
 }
+
+pub(crate) fn parse_hex(src: &str, line_number: usize, line: &str) -> Result<u64, MapParseError> {
+    utils::try_parse_hex(src).map_err(|_| {
+        MapParseError::new_at_line(
+            line_number,
+            line,
+            MapParseErrorKind::InvalidHexNumber(src.to_string()),
+        )
+    })
+}
+
+pub(crate) fn push_warning(
+    warnings: &mut Vec<MapParseWarning>,
+    segment_name: &str,
+    section_filepath: &Path,
+    symbol_name: &str,
+    kind: MapParseWarningKind,
+) {
+    warnings.push(MapParseWarning::new(
+        segment_name.to_string(),
+        section_filepath.to_path_buf(),
+        symbol_name.to_string(),
+        kind,
+    ));
+}
+
+pub(crate) fn symbol_outside_section(line_number: usize, line: &str) -> MapParseError {
+    MapParseError::new_at_line(line_number, line, MapParseErrorKind::SymbolOutsideSection)
+}
+
+/// Calls `parse_line` with every line of `head` and then with every line left
+/// on `reader`, alongside their 1-based line number.
+fn feed_lines(
+    head: &str,
+    mut reader: impl BufRead,
+    mut parse_line: impl FnMut(&str, usize) -> Result<(), MapParseError>,
+) -> Result<(), MapParseError> {
+    let mut line_number = 0;
+
+    for line in head.lines() {
+        line_number += 1;
+        parse_line(line, line_number)?;
+    }
+
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            break;
+        }
+        line_number += 1;
+
+        // Same line endings as `str::lines`
+        let trimmed = match line.strip_suffix('\n') {
+            Some(x) => x.strip_suffix('\r').unwrap_or(x),
+            None => &line,
+        };
+        parse_line(trimmed, line_number)?;
+    }
+
+    Ok(())
+}
+
+/// Parses `map_data`, whose first line is `first_line_number`, with
+/// `parse_chunk`.
+///
+/// With the `parallel` feature the map is split into chunks on every line for
+/// which `starts_segment` returns `true`, and the chunks are parsed
+/// concurrently. Otherwise the whole map is parsed at once.
+#[cfg_attr(not(feature = "parallel"), allow(unused_variables))]
+fn parse_segment_chunks(
+    map_data: &str,
+    first_line_number: usize,
+    starts_segment: impl Fn(&str) -> bool,
+    parse_chunk: impl Fn(&str, usize) -> Result<Vec<segment::Segment>, MapParseError> + Sync + Send,
+) -> Result<Vec<segment::Segment>, MapParseError> {
+    #[cfg(feature = "parallel")]
+    {
+        let chunks = segment_chunks(map_data, first_line_number, starts_segment);
+        let chunk_segment_lists = map_segments(chunks, |(chunk, chunk_first_line_number)| {
+            parse_chunk(chunk, chunk_first_line_number)
+        });
+
+        join_segment_chunks(chunk_segment_lists)
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    {
+        parse_chunk(map_data, first_line_number)
+    }
+}
+
+/// Splits `map_data` into chunks that can be parsed independently, alongside
+/// the 1-based line number of their first line.
+///
+/// A new chunk is started on every line for which `starts_segment` returns
+/// `true`, except the first one. Those lines must start a new segment no
+/// matter the state the parser was on.
+#[cfg(feature = "parallel")]
+fn segment_chunks(
+    map_data: &str,
+    first_line_number: usize,
+    starts_segment: impl Fn(&str) -> bool,
+) -> Vec<(&str, usize)> {
+    let mut chunks = Vec::new();
+    let mut chunk_start = (0, first_line_number);
+    let mut offset = 0;
+
+    for (line_index, line) in map_data.split_inclusive('\n').enumerate() {
+        if offset != 0 && starts_segment(line.trim_end_matches(['\r', '\n'])) {
+            chunks.push((&map_data[chunk_start.0..offset], chunk_start.1));
+            chunk_start = (offset, first_line_number + line_index);
+        }
+        offset += line.len();
+    }
+    chunks.push((&map_data[chunk_start.0..], chunk_start.1));
+
+    chunks
+}
+
+/// Joins the segment lists of each chunk of a map, returning the first
+/// error if any.
+///
+/// The first segment of every chunk but the first one is the dummy segment
+/// the parser starts with, which is empty since chunks start with a segment
+/// entry, so it gets removed.
+#[cfg(feature = "parallel")]
+fn join_segment_chunks(
+    chunk_segment_lists: Vec<Result<Vec<segment::Segment>, MapParseError>>,
+) -> Result<Vec<segment::Segment>, MapParseError> {
+    let mut temp_segment_list = Vec::new();
+
+    for (i, segment_list) in chunk_segment_lists.into_iter().enumerate() {
+        let segment_list = segment_list?;
+        temp_segment_list.extend(segment_list.into_iter().skip(if i == 0 { 0 } else { 1 }));
+    }
+
+    // Each chunk was built with its own interner
+    crate::interned::intern_segment_strings(&mut temp_segment_list);
+    Ok(temp_segment_list)
+}
+
+/// Joins the segments processed by [`map_segments`], keeping their warnings
+/// in the same order as if they had been processed sequentially.
+fn join_processed_segments(
+    processed: Vec<(segment::Segment, Vec<MapParseWarning>)>,
+    warnings: &mut Vec<MapParseWarning>,
+) -> Vec<segment::Segment> {
+    let mut segments_list = Vec::with_capacity(processed.len());
+
+    for (segment, segment_warnings) in processed {
+        warnings.extend(segment_warnings);
+        segments_list.push(segment);
+    }
+
+    segments_list
+}
+
+/// Calls `f` on every element of `items`, concurrently if the `parallel`
+/// feature is enabled. The results keep the order of `items`.
+fn map_segments<T: Send, U: Send>(items: Vec<T>, f: impl Fn(T) -> U + Sync + Send) -> Vec<U> {
+    #[cfg(feature = "parallel")]
+    {
+        use rayon::prelude::*;
+
+        items.into_par_iter().map(f).collect()
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    {
+        items.into_iter().map(f).collect()
+    }
+}
+
+/// Counts how many lines of `map_contents` precede `map_data`, which must be
+/// a subslice of `map_contents`.
+pub(crate) fn count_lines_before(map_contents: &str, map_data: &str) -> usize {
+    let start = map_data.as_ptr() as usize - map_contents.as_ptr() as usize;
+
+    map_contents[..start].matches('\n').count()
+}
@@ -0,0 +1,316 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT */
+
+use std::{collections::HashMap, path::PathBuf};
+
+use regex::*;
+
+use crate::{
+    interned::InternedStr,
+    map_parse_error::MapParseError,
+    mapfile::MapFile,
+    parser::{parse_hex, BANNED_SYMBOL_NAMES},
+    section, segment, symbol,
+};
+
+impl MapFile {
+    /// Parses the contents of an ARM Compiler armlink map, as generated by
+    /// `--map` and `--symbols`.
+    ///
+    /// The `map_contents` argument must contain the contents of an armlink
+    /// mapfile.
+    ///
+    /// The Thumb bit of the addresses of Thumb functions is cleared.
+    #[must_use]
+    pub fn new_from_armlink_map_str(map_contents: &str) -> Self {
+        Self::try_new_from_armlink_map_str(map_contents).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Same as [`MapFile::new_from_armlink_map_str`], but returns an error
+    /// instead of panicking if the map is malformed.
+    pub fn try_new_from_armlink_map_str(map_contents: &str) -> Result<Self, MapParseError> {
+        let mut m = Self::new_impl();
+        m.try_parse_map_contents_armlink(map_contents)?;
+        Ok(m)
+    }
+
+    pub(crate) fn try_parse_map_contents_armlink(
+        &mut self,
+        map_contents: &str,
+    ) -> Result<(), MapParseError> {
+        let regex_symbol_entry = Regex::new(r"^\s+(?P<name>[^\s]+)\s+0x(?P<value>[0-9a-fA-F]+)\s+(?:[^\s]+\s+)??(?P<type>Thumb Code|ARM Code|Code|Data|Number|Section)\s+(?P<size>\d+)\s+(?P<object>[^\s(]+(?:\([^)]*\))?)\((?P<section>[^)]+)\)\s*$").unwrap();
+        let regex_load_region = Regex::new(r"Load Region (?P<name>[^\s]+) \(Base: 0x(?P<base>[0-9a-fA-F]+), Size: 0x(?P<size>[0-9a-fA-F]+)").unwrap();
+        let regex_execution_region = Regex::new(r"Execution Region (?P<name>[^\s]+) \((?:Exec base|Base): 0x(?P<base>[0-9a-fA-F]+)(?:, Load base: 0x(?P<load>[0-9a-fA-F]+))?").unwrap();
+        // Older armlink versions don't have the `Load Addr` column
+        let regex_row_entry = Regex::new(r"^\s+0x(?P<exec>[0-9a-fA-F]+)\s+(?:(?:0x(?P<load>[0-9a-fA-F]+)|-)\s+)?0x(?P<size>[0-9a-fA-F]+)\s+(?P<type>[^\s]+)(?P<rest>.*)$").unwrap();
+
+        let mut temp_segment_list: Vec<segment::Segment> = Vec::new();
+        let mut symbols = Vec::new();
+
+        let mut image_base = None;
+        // Exec base and load base of the current execution region
+        let mut execution_region = (0, None);
+
+        for (line_index, line) in map_contents.lines().enumerate() {
+            let line_number = line_index + 1;
+
+            if let Some(load_region_match) = regex_load_region.captures(line) {
+                let name = &load_region_match["name"];
+                let vram = parse_hex(&load_region_match["base"], line_number, line)?;
+                let size = parse_hex(&load_region_match["size"], line_number, line)?;
+
+                let image_base = *image_base.get_or_insert(vram);
+
+                temp_segment_list.push(segment::Segment::new_default(
+                    name.to_string(),
+                    vram,
+                    size,
+                    vram.checked_sub(image_base),
+                ));
+            } else if let Some(execution_region_match) = regex_execution_region.captures(line) {
+                let exec_base = parse_hex(&execution_region_match["base"], line_number, line)?;
+                let load_base = match execution_region_match.name("load") {
+                    Some(x) => parse_hex(x.as_str(), line_number, line)?,
+                    None => exec_base,
+                };
+
+                execution_region = (exec_base, Some(load_base));
+            } else if let Some(row_match) = regex_row_entry.captures(line) {
+                let Some(current_segment) = temp_segment_list.last_mut() else {
+                    continue;
+                };
+
+                let vram = parse_hex(&row_match["exec"], line_number, line)?;
+                let size = parse_hex(&row_match["size"], line_number, line)?;
+                let row_type = &row_match["type"];
+
+                let load_address = if let Some(x) = row_match.name("load") {
+                    Some(parse_hex(x.as_str(), line_number, line)?)
+                } else if line.contains(" - ") || row_type == "Zero" {
+                    // Zero initialized data is not present on the image
+                    None
+                } else {
+                    let (exec_base, load_base) = execution_region;
+                    load_base.and_then(|load_base| {
+                        vram.checked_sub(exec_base)
+                            .and_then(|x| load_base.checked_add(x))
+                    })
+                };
+                let vrom = load_address
+                    .zip(image_base)
+                    .and_then(|(x, base)| x.checked_sub(base));
+
+                if row_type == "PAD" {
+                    // Make a dummy section to handle pads
+                    let mut filepath = PathBuf::new();
+                    let mut section_type = InternedStr::default();
+
+                    if let Some(prev_section) = current_segment.sections_list.last() {
+                        let mut name = prev_section.filepath.as_os_str().to_owned();
+
+                        name.push("__fill__");
+                        filepath = PathBuf::from(name);
+                        section_type.clone_from(&prev_section.section_type);
+                    }
+
+                    let mut new_section =
+                        section::Section::new_fill(filepath, vram, size, section_type);
+                    new_section.vrom = vrom;
+                    current_segment.sections_list.push(new_section);
+                    continue;
+                }
+
+                // Attr, Idx, an optional entry marker, the section name and
+                // the object.
+                let columns: Vec<&str> = row_match["rest"].split_whitespace().skip(2).collect();
+                let columns = match columns.first() {
+                    Some(&"*") | Some(&"!") => &columns[1..],
+                    _ => &columns[..],
+                };
+                let Some((section_type, object)) = columns.split_first() else {
+                    continue;
+                };
+
+                let mut new_section = section::Section::new_default(
+                    PathBuf::from(object.join(" ")),
+                    vram,
+                    size,
+                    section_type.to_string(),
+                );
+                new_section.vrom = vrom;
+                current_segment.sections_list.push(new_section);
+            } else if let Some(symbol_match) = regex_symbol_entry.captures(line) {
+                let name = symbol_match.name("name").unwrap().as_str();
+                let value = parse_hex(&symbol_match["value"], line_number, line)?;
+                let size = symbol_match["size"].parse::<u64>().unwrap_or(0);
+                let symbol_type = symbol_match.name("type").unwrap().as_str();
+                let object = symbol_match.name("object").unwrap().as_str();
+                let section_type = symbol_match.name("section").unwrap().as_str();
+
+                if matches!(symbol_type, "Number" | "Section")
+                    || BANNED_SYMBOL_NAMES.contains(&name)
+                {
+                    continue;
+                }
+
+                // Thumb functions have the lowest bit of their address set
+                let vram = if symbol_type == "Thumb Code" {
+                    value & !1
+                } else {
+                    value
+                };
+
+                symbols.push((name, vram, size, object, section_type));
+            }
+        }
+
+        if temp_segment_list.is_empty() {
+            // Only the symbol table is available, so we make up the sections
+            // from the symbols.
+            let mut new_segment = segment::Segment::new_default("Image".to_string(), 0, 0, None);
+
+            let mut sections_by_key: HashMap<(&str, &str), usize> = HashMap::new();
+            for (_, vram, size, object, section_type) in &symbols {
+                let index = *sections_by_key
+                    .entry((object, section_type))
+                    .or_insert_with(|| {
+                        new_segment
+                            .sections_list
+                            .push(section::Section::new_default(
+                                PathBuf::from(object),
+                                *vram,
+                                0,
+                                section_type.to_string(),
+                            ));
+                        new_segment.sections_list.len() - 1
+                    });
+                let section = &mut new_segment.sections_list[index];
+
+                let end = section
+                    .vram
+                    .saturating_add(section.size)
+                    .max(vram.saturating_add(*size));
+                section.vram = section.vram.min(*vram);
+                section.size = end - section.vram;
+            }
+            new_segment
+                .sections_list
+                .sort_by_key(|x| (x.vram, x.filepath.clone()));
+
+            if let (Some(first), Some(last)) = (
+                new_segment.sections_list.first(),
+                new_segment.sections_list.last(),
+            ) {
+                new_segment.vram = first.vram;
+                new_segment.size = last.vram.saturating_add(last.size) - first.vram;
+            }
+
+            temp_segment_list.push(new_segment);
+        }
+
+        self.segments_list = Self::post_process_segments_armlink(temp_segment_list, &symbols);
+
+        Ok(())
+    }
+
+    fn post_process_segments_armlink(
+        mut temp_segment_list: Vec<segment::Segment>,
+        symbols: &[(&str, u64, u64, &str, &str)],
+    ) -> Vec<segment::Segment> {
+        let mut sections_by_key: HashMap<(&str, &str), Vec<(usize, usize)>> = HashMap::new();
+        let mut sections_by_object: HashMap<&str, Vec<(usize, usize)>> = HashMap::new();
+        let mut all_sections = Vec::new();
+        for (i, segment) in temp_segment_list.iter().enumerate() {
+            for (j, section) in segment.sections_list.iter().enumerate() {
+                if section.is_fill {
+                    continue;
+                }
+                if let Some(object) = section.filepath.to_str() {
+                    sections_by_key
+                        .entry((library_member(object), section.section_type.as_str()))
+                        .or_default()
+                        .push((i, j));
+                    sections_by_object.entry(object).or_default().push((i, j));
+                }
+                all_sections.push((i, j));
+            }
+        }
+
+        let find_containing = |candidates: &[(usize, usize)], vram: u64| {
+            candidates.iter().copied().find(|(i, j)| {
+                let section = &temp_segment_list[*i].sections_list[*j];
+                vram >= section.vram && vram - section.vram < section.size.max(1)
+            })
+        };
+
+        // Symbol index, segment index and section index
+        let mut placements = Vec::new();
+        for (sym_index, (_, vram, _, object, section_type)) in symbols.iter().enumerate() {
+            let by_key = sections_by_key
+                .get(&(library_member(object), *section_type))
+                .and_then(|x| find_containing(x, *vram));
+            // Some symbols of library members are listed as
+            // `library(member.o)`, without a section name, so we compare
+            // against the whole object of each row.
+            let by_object = || {
+                sections_by_object
+                    .get(format!("{}({})", object, section_type).as_str())
+                    .and_then(|x| find_containing(x, *vram))
+            };
+
+            let found = by_key
+                .or_else(by_object)
+                .or_else(|| find_containing(&all_sections, *vram));
+            if let Some((i, j)) = found {
+                placements.push((sym_index, i, j));
+            }
+        }
+
+        for (sym_index, i, j) in placements {
+            let (name, vram, size, _, _) = symbols[sym_index];
+            let section = &mut temp_segment_list[i].sections_list[j];
+
+            let mut new_symbol = symbol::Symbol::new_default(name.to_string(), vram);
+            new_symbol.size = size;
+            if let Some(section_vrom) = section.vrom {
+                new_symbol.vrom = Some(section_vrom + vram - section.vram);
+            }
+            section.symbols.push(new_symbol);
+        }
+
+        for segment in &mut temp_segment_list {
+            for section in &mut segment.sections_list {
+                section.symbols.sort_by_key(|x| x.vram);
+
+                // Infer the size of symbols that the map did not report,
+                // usually asm ones.
+                let section_end = section.vram.saturating_add(section.size);
+                let symbols_count = section.symbols.len();
+                for index in 0..symbols_count {
+                    let next_vram = section
+                        .symbols
+                        .get(index + 1)
+                        .map_or(section_end, |x| x.vram);
+                    let sym = &mut section.symbols[index];
+
+                    if sym.size == 0 {
+                        sym.size = next_vram.saturating_sub(sym.vram);
+                    }
+                }
+
+                Self::fixup_non_matching_symbols_for_section(section);
+            }
+        }
+
+        temp_segment_list
+    }
+}
+
+/// Objects pulled from libraries are listed as `library(member.o)` on the
+/// memory map, but only as `member.o` on the symbol table.
+fn library_member(object: &str) -> &str {
+    match object.strip_suffix(')').and_then(|x| x.split_once('(')) {
+        Some((_, member)) => member,
+        None => object,
+    }
+}
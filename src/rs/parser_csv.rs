@@ -0,0 +1,268 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT */
+
+use crate::{
+    map_parse_error::{MapParseError, MapParseErrorKind},
+    mapfile::MapFile,
+    parser::BANNED_SYMBOL_NAMES,
+    parser_nm::{segments_from_symbol_listing, ListedSymbol},
+};
+
+/// How the numbers of a column of a delimited file are written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum CsvNumberBase {
+    /// Hexadecimal if any value of the column has a `0x` prefix, an
+    /// hexadecimal letter or leading zeroes, or if every value has the same
+    /// width of at least 8 digits, like zero-padded addresses. Decimal
+    /// otherwise.
+    #[default]
+    Detect,
+    Hex,
+    Decimal,
+}
+
+/// Tells which column of a delimited file holds each field of a symbol.
+///
+/// Column indices are zero-based.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CsvColumnMapping {
+    /// The character separating the columns, usually `,` or `\t`.
+    pub delimiter: char,
+    /// Skips the first line of the file.
+    pub has_header: bool,
+
+    pub name: usize,
+    pub vram: usize,
+    /// Symbols without a size get it inferred from the next symbol.
+    pub size: Option<usize>,
+    pub vrom: Option<usize>,
+    /// Each distinct value of this column becomes a segment.
+    pub section: Option<usize>,
+    /// Each distinct value of this column becomes a section of each segment.
+    pub filepath: Option<usize>,
+
+    pub vram_base: CsvNumberBase,
+    pub size_base: CsvNumberBase,
+    pub vrom_base: CsvNumberBase,
+}
+
+impl CsvColumnMapping {
+    /// A comma separated file with a header, containing only the name and
+    /// address of each symbol.
+    ///
+    /// Addresses are read as hexadecimal, while the base of the other numeric
+    /// columns is detected per column.
+    pub fn new(name: usize, vram: usize) -> Self {
+        Self {
+            delimiter: ',',
+            has_header: true,
+            name,
+            vram,
+            size: None,
+            vrom: None,
+            section: None,
+            filepath: None,
+            vram_base: CsvNumberBase::Hex,
+            size_base: CsvNumberBase::Detect,
+            vrom_base: CsvNumberBase::Hex,
+        }
+    }
+
+    /// The mapping of the files generated by [`MapFile::to_csv_symbols`].
+    pub fn mapfile_symbols_csv() -> Self {
+        Self {
+            size: Some(3),
+            filepath: Some(0),
+            vram_base: CsvNumberBase::Hex,
+            size_base: CsvNumberBase::Decimal,
+            ..Self::new(1, 2)
+        }
+    }
+}
+
+impl MapFile {
+    /// Builds a `MapFile` from a delimited file (like CSV or TSV) listing
+    /// symbols, as exported by spreadsheets or by tools like Ghidra or IDA.
+    ///
+    /// The `csv_contents` argument must contain the contents of the file, and
+    /// `mapping` tells the column of each field.
+    ///
+    /// Each value of the section column becomes a segment, and the symbols of
+    /// each value of the filepath column become a section of it. Fields can
+    /// be quoted with `"`.
+    #[must_use]
+    pub fn new_from_csv_str(csv_contents: &str, mapping: &CsvColumnMapping) -> Self {
+        Self::try_new_from_csv_str(csv_contents, mapping).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Same as [`MapFile::new_from_csv_str`], but returns an error instead of
+    /// panicking if a row is missing a column or has a malformed number.
+    pub fn try_new_from_csv_str(
+        csv_contents: &str,
+        mapping: &CsvColumnMapping,
+    ) -> Result<Self, MapParseError> {
+        // Line number, line and fields of each row
+        let rows: Vec<(usize, &str, Vec<String>)> = csv_contents
+            .lines()
+            .enumerate()
+            .skip(if mapping.has_header { 1 } else { 0 })
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(line_index, line)| {
+                (
+                    line_index + 1,
+                    line,
+                    split_delimited_line(line, mapping.delimiter),
+                )
+            })
+            .collect();
+
+        let number_column = |index: Option<usize>, base: CsvNumberBase| {
+            let Some(index) = index else {
+                return Ok(Vec::new());
+            };
+            let is_hex = match base {
+                CsvNumberBase::Hex => true,
+                CsvNumberBase::Decimal => false,
+                CsvNumberBase::Detect => {
+                    let mut values = rows
+                        .iter()
+                        .filter_map(|(_, _, fields)| fields.get(index))
+                        .filter(|x| !x.is_empty());
+
+                    values.clone().any(|x| looks_like_hex(x))
+                        || values.next().is_some_and(|first| {
+                            first.len() >= 8 && values.all(|x| x.len() == first.len())
+                        })
+                }
+            };
+
+            rows.iter()
+                .map(|(line_number, line, fields)| {
+                    let value = field(fields, index, *line_number, line)?;
+                    parse_number(value, is_hex, *line_number, line)
+                })
+                .collect::<Result<Vec<Option<u64>>, MapParseError>>()
+        };
+
+        let vram_values = number_column(Some(mapping.vram), mapping.vram_base)?;
+        let size_values = number_column(mapping.size, mapping.size_base)?;
+        let vrom_values = number_column(mapping.vrom, mapping.vrom_base)?;
+
+        let mut symbols = Vec::new();
+
+        for (row_index, (line_number, line, fields)) in rows.iter().enumerate() {
+            let name = field(fields, mapping.name, *line_number, line)?;
+            let Some(vram) = vram_values[row_index] else {
+                continue;
+            };
+            if name.is_empty() || BANNED_SYMBOL_NAMES.contains(&name) {
+                continue;
+            }
+
+            let section = match mapping.section {
+                Some(x) => field(fields, x, *line_number, line)?,
+                None => "",
+            };
+            let object = match mapping.filepath {
+                Some(x) => field(fields, x, *line_number, line)?,
+                None => "",
+            };
+
+            symbols.push(ListedSymbol {
+                object,
+                section,
+                vram,
+                size: size_values.get(row_index).copied().flatten(),
+                vrom: vrom_values.get(row_index).copied().flatten(),
+                name,
+            });
+        }
+
+        let mut m = Self::new_impl();
+        m.segments_list = segments_from_symbol_listing(symbols);
+        Ok(m)
+    }
+}
+
+fn field<'a>(
+    fields: &'a [String],
+    index: usize,
+    line_number: usize,
+    line: &str,
+) -> Result<&'a str, MapParseError> {
+    fields.get(index).map(|x| x.as_str()).ok_or_else(|| {
+        MapParseError::new_at_line(line_number, line, MapParseErrorKind::MissingColumn(index))
+    })
+}
+
+/// Splits a line of a delimited file into its fields, removing the quotes
+/// around quoted fields.
+fn split_delimited_line(line: &str, delimiter: char) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            if c == '"' {
+                // Quotes inside quoted fields are escaped by doubling them
+                if chars.peek() == Some(&'"') {
+                    current.push(chars.next().unwrap());
+                } else {
+                    in_quotes = false;
+                }
+            } else {
+                current.push(c);
+            }
+        } else if c == '"' {
+            in_quotes = true;
+        } else if c == delimiter {
+            fields.push(current.trim().to_string());
+            current.clear();
+        } else {
+            current.push(c);
+        }
+    }
+    fields.push(current.trim().to_string());
+
+    fields
+}
+
+fn looks_like_hex(value: &str) -> bool {
+    value.starts_with("0x")
+        || value.starts_with("0X")
+        || value
+            .chars()
+            .any(|x| x.is_ascii_hexdigit() && !x.is_ascii_digit())
+        || (value.len() > 1 && value.starts_with('0'))
+}
+
+/// Parses a number of a delimited file. Empty fields give `None`.
+fn parse_number(
+    value: &str,
+    is_hex: bool,
+    line_number: usize,
+    line: &str,
+) -> Result<Option<u64>, MapParseError> {
+    if value.is_empty() {
+        return Ok(None);
+    }
+
+    let parsed = if is_hex {
+        let digits = value
+            .strip_prefix("0x")
+            .or_else(|| value.strip_prefix("0X"))
+            .unwrap_or(value);
+        u64::from_str_radix(digits, 16)
+            .map_err(|_| MapParseErrorKind::InvalidHexNumber(value.to_string()))
+    } else {
+        value
+            .parse()
+            .map_err(|_| MapParseErrorKind::InvalidDecimalNumber(value.to_string()))
+    };
+
+    parsed
+        .map(Some)
+        .map_err(|kind| MapParseError::new_at_line(line_number, line, kind))
+}
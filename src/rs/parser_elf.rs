@@ -0,0 +1,465 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT */
+
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    elf::{self, Elf, ElfSymbol},
+    elf_mismatch::{ElfMismatch, ElfMismatchKind},
+    interned::{InternedStr, Interner},
+    map_parse_error::MapParseError,
+    mapfile::MapFile,
+    parser::BANNED_SYMBOL_NAMES,
+    section, segment, symbol,
+};
+
+impl MapFile {
+    /// Builds a `MapFile` from the section headers and the symbol table of a
+    /// linked ELF file.
+    ///
+    /// Each allocated section of the ELF becomes a segment. Symbols are
+    /// grouped into sections by the object file they come from, as told by
+    /// the `STT_FILE` symbols. Since global symbols are not preceded by an
+    /// `STT_FILE` symbol, they are attributed to the object of the closest
+    /// local symbol before them.
+    ///
+    /// Both 32 and 64 bits ELF files are supported, in either endianness.
+    #[must_use]
+    pub fn new_from_elf(elf_bytes: &[u8]) -> Self {
+        Self::try_new_from_elf(elf_bytes).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Same as [`MapFile::new_from_elf`], but returns an error instead of
+    /// panicking if the ELF is malformed.
+    pub fn try_new_from_elf(elf_bytes: &[u8]) -> Result<Self, MapParseError> {
+        let elf = Elf::parse(elf_bytes)?;
+        let elf_symbols = read_elf_symbols(&elf)?;
+        let symbols = elf_symbols_with_files(&elf_symbols);
+
+        let mut m = Self::new_impl();
+        let mut interner = Interner::default();
+
+        for (section_index, elf_section) in elf.sections.iter().enumerate() {
+            if !elf_section.is_alloc() || elf_section.size == 0 {
+                continue;
+            }
+
+            let vrom = if elf_section.is_nobits() {
+                None
+            } else {
+                elf.load_address_of(elf_section)
+            };
+            let mut new_segment = segment::Segment::new_default(
+                elf_section.name.clone(),
+                elf_section.addr,
+                elf_section.size,
+                vrom,
+            );
+
+            let mut section_symbols: Vec<&(&ElfSymbol, &str)> = symbols
+                .iter()
+                .filter(|(sym, _)| sym.section_index == Some(section_index))
+                .collect();
+            section_symbols.sort_by_key(|(sym, _)| sym.value);
+
+            for (sym, file) in section_symbols {
+                if new_segment
+                    .sections_list
+                    .last()
+                    .map_or(true, |x| x.filepath.as_os_str() != *file)
+                {
+                    // The first section covers the start of the ELF section
+                    // too, in case it doesn't start with a symbol.
+                    let vram = if new_segment.sections_list.is_empty() {
+                        elf_section.addr
+                    } else {
+                        sym.value
+                    };
+
+                    let mut new_section = section::Section::new_default(
+                        interner.intern_path(Path::new(file)),
+                        vram,
+                        0,
+                        interner.intern_str(&elf_section.name),
+                    );
+                    new_section.vrom = offset_vrom(vrom, elf_section.addr, vram);
+                    new_segment.sections_list.push(new_section);
+                }
+
+                let mut new_symbol =
+                    symbol::Symbol::new_default(interner.intern_str(&sym.name), sym.value);
+                new_symbol.size = sym.size;
+                new_symbol.vrom = offset_vrom(vrom, elf_section.addr, sym.value);
+                new_segment
+                    .sections_list
+                    .last_mut()
+                    .unwrap()
+                    .symbols
+                    .push(new_symbol);
+            }
+
+            // Each section spans until the next one
+            let segment_end = new_segment.vram + new_segment.size;
+            for index in 0..new_segment.sections_list.len() {
+                let next_vram = new_segment
+                    .sections_list
+                    .get(index + 1)
+                    .map_or(segment_end, |x| x.vram);
+                let section = &mut new_segment.sections_list[index];
+                section.size = next_vram.saturating_sub(section.vram);
+
+                // Symbols without size, like hand-written asm ones, get
+                // their size inferred from the next symbol.
+                let section_end = section.vram + section.size;
+                for sym_index in 0..section.symbols.len() {
+                    if section.symbols[sym_index].size != 0 {
+                        continue;
+                    }
+                    let sym_vram = section.symbols[sym_index].vram;
+                    let next_sym_vram = section.symbols[sym_index + 1..]
+                        .iter()
+                        .map(|x| x.vram)
+                        .find(|x| *x > sym_vram)
+                        .unwrap_or(section_end);
+
+                    section.symbols[sym_index].size = next_sym_vram.saturating_sub(sym_vram);
+                }
+
+                Self::fixup_non_matching_symbols_for_section(section);
+            }
+
+            m.segments_list.push(new_segment);
+        }
+
+        Ok(m)
+    }
+
+    /// Replaces the sizes of the symbols of this map with the exact ones from
+    /// the symbol table of its linked ELF, and inserts the local symbols that
+    /// the map doesn't list.
+    ///
+    /// Most mapfiles don't tell the size of each symbol, so it gets inferred
+    /// from the next listed symbol. Because of this, static symbols missing
+    /// from the map are folded into the previous symbol, and the last symbol
+    /// of each section absorbs the alignment padding of the section.
+    ///
+    /// Symbols are matched by name and address. Local symbols missing from
+    /// the map are inserted into the section containing their address,
+    /// preferring the one built from the same source file.
+    ///
+    /// Only `STT_FUNC`, `STT_OBJECT` and `STT_NOTYPE` symbols are considered,
+    /// but their type is not kept, since [`Symbol`] has no notion of it and
+    /// none of the supported mapfile formats report it.
+    ///
+    /// Returns every disagreement found between the map and the ELF.
+    ///
+    /// [`Symbol`]: crate::Symbol
+    pub fn enrich_from_elf(&mut self, elf_bytes: &[u8]) -> Vec<ElfMismatch> {
+        self.try_enrich_from_elf(elf_bytes)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Same as [`MapFile::enrich_from_elf`], but returns an error instead of
+    /// panicking if the ELF is malformed.
+    ///
+    /// The map is left untouched if an error is returned.
+    pub fn try_enrich_from_elf(
+        &mut self,
+        elf_bytes: &[u8],
+    ) -> Result<Vec<ElfMismatch>, MapParseError> {
+        let elf = Elf::parse(elf_bytes)?;
+        let elf_symbols = read_elf_symbols(&elf)?;
+        let symbols = elf_symbols_with_files(&elf_symbols);
+
+        let mut mismatches = Vec::new();
+
+        // Segment, section and symbol index of every symbol of the map
+        let mut map_symbols: HashMap<InternedStr, Vec<(usize, usize, usize)>> = HashMap::new();
+        for (segment_index, segment) in self.segments_list.iter().enumerate() {
+            for (section_index, section) in segment.sections_list.iter().enumerate() {
+                for (symbol_index, sym) in section.symbols.iter().enumerate() {
+                    map_symbols.entry(sym.name.clone()).or_default().push((
+                        segment_index,
+                        section_index,
+                        symbol_index,
+                    ));
+                }
+            }
+        }
+
+        let mut matched = HashSet::new();
+        // Symbols of the map that got their size from the ELF
+        let mut exact_sizes = HashSet::new();
+        let mut missing = Vec::new();
+
+        for (sym, file) in &symbols {
+            let candidates = map_symbols
+                .get(sym.name.as_str())
+                .map_or(&[][..], |x| x.as_slice());
+            let same_address = candidates.iter().find(|(a, b, c)| {
+                self.segments_list[*a].sections_list[*b].symbols[*c].vram == sym.value
+            });
+
+            if let Some(&index) = same_address {
+                matched.insert(index);
+                let (segment_index, section_index, symbol_index) = index;
+                let segment = &mut self.segments_list[segment_index];
+                let section = &mut segment.sections_list[section_index];
+                let map_symbol = &mut section.symbols[symbol_index];
+
+                // Symbols without size, like hand-written asm ones, keep the
+                // inferred size.
+                if sym.size == 0 {
+                    continue;
+                }
+                if map_symbol.size != sym.size {
+                    mismatches.push(ElfMismatch::new(
+                        segment.name.clone(),
+                        section.filepath.to_path_buf(),
+                        sym.name.clone(),
+                        ElfMismatchKind::SizeMismatch {
+                            map_size: map_symbol.size,
+                            elf_size: sym.size,
+                        },
+                    ));
+                    map_symbol.size = sym.size;
+                }
+                exact_sizes.insert(index);
+            } else if let Some(&index) = candidates.iter().find(|x| !matched.contains(*x)) {
+                matched.insert(index);
+                let (segment_index, section_index, symbol_index) = index;
+                let segment = &self.segments_list[segment_index];
+                let section = &segment.sections_list[section_index];
+
+                mismatches.push(ElfMismatch::new(
+                    segment.name.clone(),
+                    section.filepath.to_path_buf(),
+                    sym.name.clone(),
+                    ElfMismatchKind::AddressMismatch {
+                        map_vram: section.symbols[symbol_index].vram,
+                        elf_vram: sym.value,
+                    },
+                ));
+            } else if sym.section_index.is_none() {
+                // Absolute symbols are rarely listed on maps
+            } else if sym.bind == elf::STB_LOCAL {
+                missing.push((*sym, *file));
+            } else {
+                mismatches.push(ElfMismatch::new(
+                    String::new(),
+                    PathBuf::new(),
+                    sym.name.clone(),
+                    ElfMismatchKind::MissingFromMap,
+                ));
+            }
+        }
+
+        let elf_names: HashSet<&str> = symbols.iter().map(|(x, _)| x.name.as_str()).collect();
+        for segment in &self.segments_list {
+            for section in &segment.sections_list {
+                for sym in &section.symbols {
+                    if !elf_names.contains(sym.name.as_str()) {
+                        mismatches.push(ElfMismatch::new(
+                            segment.name.clone(),
+                            section.filepath.to_path_buf(),
+                            sym.name.to_string(),
+                            ElfMismatchKind::MissingFromElf,
+                        ));
+                    }
+                }
+            }
+        }
+
+        // Symbols to insert on each section, alongside whether their size is
+        // exact or not.
+        let mut insertions: HashMap<(usize, usize), Vec<(symbol::Symbol, bool)>> = HashMap::new();
+        let mut interner = Interner::default();
+        for (sym, file) in missing {
+            let containing: Vec<(usize, usize)> = self
+                .segments_list
+                .iter()
+                .enumerate()
+                .flat_map(|(segment_index, segment)| {
+                    segment
+                        .sections_list
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, x)| {
+                            !x.is_fill && sym.value >= x.vram && sym.value - x.vram < x.size
+                        })
+                        .map(move |(section_index, _)| (segment_index, section_index))
+                })
+                .collect();
+
+            // Overlays may share addresses, so we use the file of the symbol
+            // to disambiguate.
+            let file_stem = Path::new(file).file_stem();
+            let target = containing
+                .iter()
+                .find(|(a, b)| {
+                    self.segments_list[*a].sections_list[*b]
+                        .filepath
+                        .file_stem()
+                        == file_stem
+                })
+                .or(containing.first());
+
+            let Some(&(segment_index, section_index)) = target else {
+                mismatches.push(ElfMismatch::new(
+                    String::new(),
+                    PathBuf::new(),
+                    sym.name.clone(),
+                    ElfMismatchKind::MissingFromMap,
+                ));
+                continue;
+            };
+
+            let segment = &self.segments_list[segment_index];
+            let section = &segment.sections_list[section_index];
+            mismatches.push(ElfMismatch::new(
+                segment.name.clone(),
+                section.filepath.to_path_buf(),
+                sym.name.clone(),
+                ElfMismatchKind::InsertedSymbol,
+            ));
+
+            let mut new_symbol =
+                symbol::Symbol::new_default(interner.intern_str(&sym.name), sym.value);
+            new_symbol.size = sym.size;
+            new_symbol.vrom = offset_vrom(section.vrom, section.vram, sym.value);
+            insertions
+                .entry((segment_index, section_index))
+                .or_default()
+                .push((new_symbol, sym.size != 0));
+        }
+
+        for ((segment_index, section_index), new_symbols) in insertions {
+            let section = &mut self.segments_list[segment_index].sections_list[section_index];
+
+            let mut section_symbols: Vec<(symbol::Symbol, bool)> = section
+                .symbols
+                .drain(..)
+                .enumerate()
+                .map(|(symbol_index, x)| {
+                    let is_exact =
+                        exact_sizes.contains(&(segment_index, section_index, symbol_index));
+                    (x, is_exact)
+                })
+                .chain(new_symbols)
+                .collect();
+            section_symbols.sort_by_key(|(x, _)| x.vram);
+
+            // Inferred sizes can't overlap the inserted symbols
+            let section_end = section.vram + section.size;
+            for index in 0..section_symbols.len() {
+                if section_symbols[index].1 {
+                    continue;
+                }
+                let sym_vram = section_symbols[index].0.vram;
+                let next_vram = section_symbols[index + 1..]
+                    .iter()
+                    .map(|(x, _)| x.vram)
+                    .find(|x| *x > sym_vram)
+                    .unwrap_or(section_end);
+
+                let sym = &mut section_symbols[index].0;
+                if sym.size == 0 || sym.vram + sym.size > next_vram {
+                    sym.size = next_vram.saturating_sub(sym_vram);
+                }
+            }
+
+            section.symbols = section_symbols.into_iter().map(|(x, _)| x).collect();
+            Self::fixup_non_matching_symbols_for_section(section);
+        }
+
+        Ok(mismatches)
+    }
+}
+
+fn read_elf_symbols(elf: &Elf) -> Result<Vec<ElfSymbol>, MapParseError> {
+    let mut elf_symbols = elf.symbols()?;
+    if elf.machine == elf::EM_ARM {
+        // Thumb functions have the lowest bit of their address set
+        for sym in &mut elf_symbols {
+            if sym.sym_type == elf::STT_FUNC {
+                sym.value &= !1;
+            }
+        }
+    }
+    Ok(elf_symbols)
+}
+
+/// Pairs every interesting symbol with the object file it comes from.
+pub(crate) fn elf_symbols_with_files(symbols: &[ElfSymbol]) -> Vec<(&ElfSymbol, &str)> {
+    // Local symbols come right after the `STT_FILE` symbol of their object.
+    let mut current_file = "";
+    let mut locals = Vec::new();
+    let mut globals = Vec::new();
+
+    for sym in symbols {
+        if sym.sym_type == elf::STT_FILE {
+            current_file = &sym.name;
+            continue;
+        }
+        if !is_interesting_elf_symbol(sym) {
+            continue;
+        }
+
+        if sym.bind == elf::STB_LOCAL {
+            locals.push((sym, current_file));
+        } else {
+            globals.push(sym);
+        }
+    }
+
+    // Global symbols are listed after every local one, so we guess their
+    // object from the closest local symbol before them.
+    let mut sorted_locals: Vec<(usize, u64, &str)> = locals
+        .iter()
+        .filter_map(|(sym, file)| sym.section_index.map(|x| (x, sym.value, *file)))
+        .collect();
+    sorted_locals.sort_by_key(|(section_index, value, _)| (*section_index, *value));
+
+    let mut result = locals;
+    for sym in globals {
+        let file = sym.section_index.map_or("", |section_index| {
+            let index = sorted_locals
+                .partition_point(|(x, value, _)| (*x, *value) <= (section_index, sym.value));
+            match index.checked_sub(1).map(|x| sorted_locals[x]) {
+                Some((x, _, file)) if x == section_index => file,
+                _ => "",
+            }
+        });
+        result.push((sym, file));
+    }
+
+    result
+}
+
+fn is_interesting_elf_symbol(sym: &ElfSymbol) -> bool {
+    if !matches!(
+        sym.sym_type,
+        elf::STT_NOTYPE | elf::STT_OBJECT | elf::STT_FUNC
+    ) {
+        return false;
+    }
+    if sym.name.is_empty() || BANNED_SYMBOL_NAMES.contains(&sym.name.as_str()) {
+        return false;
+    }
+
+    // ARM and AArch64 mapping symbols
+    !matches!(sym.name.split('.').next(), Some("$a" | "$t" | "$d" | "$x"))
+}
+
+/// Calculates the rom address of `vram`, given the rom address of `base_vram`.
+///
+/// Returns `None` if `vram` is below `base_vram` or the result overflows, which
+/// can only happen on malformed ELFs.
+fn offset_vrom(base_vrom: Option<u64>, base_vram: u64, vram: u64) -> Option<u64> {
+    base_vrom
+        .zip(vram.checked_sub(base_vram))
+        .and_then(|(base, offset)| base.checked_add(offset))
+}
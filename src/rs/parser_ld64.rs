@@ -0,0 +1,174 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT */
+
+use std::{collections::HashMap, path::Path};
+
+use regex::*;
+
+use crate::{
+    interned::Interner,
+    map_parse_error::MapParseError,
+    mapfile::MapFile,
+    parser::{parse_hex, BANNED_SYMBOL_NAMES},
+    section, segment, symbol,
+};
+
+enum Ld64Block {
+    None,
+    ObjectFiles,
+    Sections,
+    Symbols,
+}
+
+impl MapFile {
+    /// Parses the contents of an Apple ld64 map, as generated by `-map`.
+    ///
+    /// The `map_contents` argument must contain the contents of an ld64 mapfile.
+    #[must_use]
+    pub fn new_from_ld64_map_str(map_contents: &str) -> Self {
+        Self::try_new_from_ld64_map_str(map_contents).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Same as [`MapFile::new_from_ld64_map_str`], but returns an error
+    /// instead of panicking if the map is malformed.
+    pub fn try_new_from_ld64_map_str(map_contents: &str) -> Result<Self, MapParseError> {
+        let mut m = Self::new_impl();
+        m.try_parse_map_contents_ld64(map_contents)?;
+        Ok(m)
+    }
+
+    pub(crate) fn try_parse_map_contents_ld64(
+        &mut self,
+        map_contents: &str,
+    ) -> Result<(), MapParseError> {
+        let regex_object_entry = Regex::new(r"^\[\s*(?P<index>\d+)\]\s+(?P<path>.+?)\s*$").unwrap();
+        let regex_section_entry = Regex::new(r"^(?P<address>0x[0-9a-fA-F]+)\s+(?P<size>0x[0-9a-fA-F]+)\s+(?P<segment>[^\s]+)\s+(?P<section>[^\s]+)\s*$").unwrap();
+        let regex_symbol_entry = Regex::new(r"^(?P<address>0x[0-9a-fA-F]+)\s+(?P<size>0x[0-9a-fA-F]+)\s+\[\s*(?P<index>\d+)\]\s+(?P<name>.+?)\s*$").unwrap();
+
+        let mut block = Ld64Block::None;
+
+        let mut objects: HashMap<&str, &str> = HashMap::new();
+        let mut segments_list: Vec<segment::Segment> = Vec::new();
+        let mut interner = Interner::default();
+        // Address, size and name of each section, and the index of the
+        // segment containing it.
+        let mut macho_sections: Vec<(u64, u64, &str, usize)> = Vec::new();
+
+        let mut current_section_index = None;
+        let mut current_object = "";
+
+        for (line_index, line) in map_contents.lines().enumerate() {
+            let line_number = line_index + 1;
+
+            if line.starts_with('#') {
+                let header = line.trim_start_matches('#').trim();
+
+                block = match header {
+                    "Object files:" => Ld64Block::ObjectFiles,
+                    "Sections:" => Ld64Block::Sections,
+                    "Symbols:" => Ld64Block::Symbols,
+                    "Dead Stripped Symbols:" => Ld64Block::None,
+                    _ => block,
+                };
+                continue;
+            }
+
+            match block {
+                Ld64Block::None => {}
+                Ld64Block::ObjectFiles => {
+                    if let Some(object_match) = regex_object_entry.captures(line) {
+                        let index = object_match.name("index").unwrap().as_str();
+                        let path = object_match.name("path").unwrap().as_str();
+
+                        objects.insert(index, path);
+                    }
+                }
+                Ld64Block::Sections => {
+                    if let Some(section_match) = regex_section_entry.captures(line) {
+                        let vram = parse_hex(&section_match["address"], line_number, line)?;
+                        let size = parse_hex(&section_match["size"], line_number, line)?;
+                        let segment_name = &section_match["segment"];
+                        let section_name = section_match.name("section").unwrap().as_str();
+
+                        if segments_list.last().map(|x| x.name.as_str()) != Some(segment_name) {
+                            segments_list.push(segment::Segment::new_default(
+                                segment_name.to_string(),
+                                vram,
+                                0,
+                                None,
+                            ));
+                        }
+                        let current_segment = segments_list.last_mut().unwrap();
+                        current_segment.size = (vram + size).saturating_sub(current_segment.vram);
+
+                        macho_sections.push((vram, size, section_name, segments_list.len() - 1));
+                    }
+                }
+                Ld64Block::Symbols => {
+                    if let Some(symbol_match) = regex_symbol_entry.captures(line) {
+                        let vram = parse_hex(&symbol_match["address"], line_number, line)?;
+                        let size = parse_hex(&symbol_match["size"], line_number, line)?;
+                        let index = symbol_match.name("index").unwrap().as_str();
+                        let name = &symbol_match["name"];
+
+                        if BANNED_SYMBOL_NAMES.contains(&name) {
+                            continue;
+                        }
+
+                        let Some(section_index) =
+                            macho_sections
+                                .iter()
+                                .position(|(start, section_size, _, _)| {
+                                    vram >= *start && vram < start + section_size
+                                })
+                        else {
+                            // Symbols outside of any section, like absolute ones
+                            continue;
+                        };
+                        let (_, _, section_name, segment_index) = macho_sections[section_index];
+                        let object = objects.get(index).copied().unwrap_or_default();
+
+                        let current_segment = &mut segments_list[segment_index];
+
+                        // Group together every consecutive symbol that comes
+                        // from the same object and section.
+                        if current_section_index != Some(section_index)
+                            || current_object != object
+                            || current_segment.sections_list.is_empty()
+                        {
+                            current_section_index = Some(section_index);
+                            current_object = object;
+
+                            current_segment
+                                .sections_list
+                                .push(section::Section::new_default(
+                                    interner.intern_path(Path::new(object)),
+                                    vram,
+                                    0,
+                                    interner.intern_str(section_name),
+                                ));
+                        }
+
+                        let current_section = current_segment.sections_list.last_mut().unwrap();
+                        let mut new_symbol =
+                            symbol::Symbol::new_default(interner.intern_str(name), vram);
+                        new_symbol.size = size;
+
+                        current_section.size = (vram + size).saturating_sub(current_section.vram);
+                        current_section.symbols.push(new_symbol);
+                    }
+                }
+            }
+        }
+
+        for segment in &mut segments_list {
+            for section in &mut segment.sections_list {
+                Self::fixup_non_matching_symbols_for_section(section);
+            }
+        }
+
+        self.segments_list = segments_list;
+
+        Ok(())
+    }
+}
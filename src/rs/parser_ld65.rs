@@ -0,0 +1,183 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT */
+
+use std::path::Path;
+
+use regex::*;
+
+use crate::{
+    interned::Interner,
+    map_parse_error::MapParseError,
+    mapfile::MapFile,
+    parser::{parse_hex, BANNED_SYMBOL_NAMES},
+    section, segment, symbol,
+};
+
+enum Ld65Block {
+    None,
+    Modules,
+    Segments,
+    Exports,
+}
+
+impl MapFile {
+    /// Parses the contents of a cc65 ld65 map, as generated by `-m`.
+    ///
+    /// The `map_contents` argument must contain the contents of an ld65
+    /// mapfile.
+    ///
+    /// ld65 maps don't list load addresses, so no rom address is set.
+    #[must_use]
+    pub fn new_from_ld65_map_str(map_contents: &str) -> Self {
+        Self::try_new_from_ld65_map_str(map_contents).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Same as [`MapFile::new_from_ld65_map_str`], but returns an error
+    /// instead of panicking if the map is malformed.
+    pub fn try_new_from_ld65_map_str(map_contents: &str) -> Result<Self, MapParseError> {
+        let mut m = Self::new_impl();
+        m.try_parse_map_contents_ld65(map_contents)?;
+        Ok(m)
+    }
+
+    pub(crate) fn try_parse_map_contents_ld65(
+        &mut self,
+        map_contents: &str,
+    ) -> Result<(), MapParseError> {
+        let regex_module = Regex::new(r"^(?P<module>[^\s].*):\s*$").unwrap();
+        let regex_contribution = Regex::new(r"^\s+(?P<segment>[^\s]+)\s+Offs=(?P<offset>[0-9a-fA-F]+)\s+Size=(?P<size>[0-9a-fA-F]+)").unwrap();
+        let regex_segment_entry = Regex::new(r"^(?P<name>[^\s]+)\s+(?P<start>[0-9a-fA-F]{6})\s+[0-9a-fA-F]{6}\s+(?P<size>[0-9a-fA-F]{6})").unwrap();
+        // Each line of the exports list contains up to two exports
+        let regex_export_entry =
+            Regex::new(r"(?P<name>[^\s]+)\s+(?P<value>[0-9a-fA-F]{6}) [R ](?P<kind>[LE])[ZAFL]")
+                .unwrap();
+
+        let mut block = Ld65Block::None;
+
+        // Module, segment, offset and size of each contribution
+        let mut contributions: Vec<(&str, &str, u64, u64)> = Vec::new();
+        let mut segments_list: Vec<segment::Segment> = Vec::new();
+        let mut interner = Interner::default();
+        let mut symbols: Vec<(u64, &str)> = Vec::new();
+
+        let mut current_module = "";
+
+        for (line_index, line) in map_contents.lines().enumerate() {
+            let line_number = line_index + 1;
+
+            if line.starts_with("Modules list:") {
+                block = Ld65Block::Modules;
+                continue;
+            } else if line.starts_with("Segment list:") {
+                block = Ld65Block::Segments;
+                continue;
+            } else if line.starts_with("Exports list by") {
+                block = Ld65Block::Exports;
+                continue;
+            } else if line.starts_with("Imports list:") {
+                block = Ld65Block::None;
+                continue;
+            }
+
+            match block {
+                Ld65Block::None => {}
+                Ld65Block::Modules => {
+                    if let Some(contribution_match) = regex_contribution.captures(line) {
+                        let segment_name = contribution_match.name("segment").unwrap().as_str();
+                        let offset = parse_hex(&contribution_match["offset"], line_number, line)?;
+                        let size = parse_hex(&contribution_match["size"], line_number, line)?;
+
+                        contributions.push((current_module, segment_name, offset, size));
+                    } else if let Some(module_match) = regex_module.captures(line) {
+                        current_module = module_match.name("module").unwrap().as_str();
+                    }
+                }
+                Ld65Block::Segments => {
+                    if let Some(segment_match) = regex_segment_entry.captures(line) {
+                        let name = &segment_match["name"];
+                        let vram = parse_hex(&segment_match["start"], line_number, line)?;
+                        let size = parse_hex(&segment_match["size"], line_number, line)?;
+
+                        segments_list.push(segment::Segment::new_default(
+                            name.to_string(),
+                            vram,
+                            size,
+                            None,
+                        ));
+                    }
+                }
+                Ld65Block::Exports => {
+                    for export_match in regex_export_entry.captures_iter(line) {
+                        let name = export_match.name("name").unwrap().as_str();
+                        let vram = parse_hex(&export_match["value"], line_number, line)?;
+
+                        // Equates are constants, not addresses
+                        if &export_match["kind"] == "L" && !BANNED_SYMBOL_NAMES.contains(&name) {
+                            symbols.push((vram, name));
+                        }
+                    }
+                }
+            }
+        }
+
+        // Both exports lists contain the same symbols
+        symbols.sort();
+        symbols.dedup();
+
+        for (module, segment_name, offset, size) in contributions {
+            let Some(current_segment) = segments_list.iter_mut().find(|x| x.name == segment_name)
+            else {
+                continue;
+            };
+
+            current_segment
+                .sections_list
+                .push(section::Section::new_default(
+                    interner.intern_path(Path::new(module)),
+                    current_segment.vram + offset,
+                    size,
+                    interner.intern_str(segment_name),
+                ));
+        }
+
+        for (vram, name) in symbols {
+            // Different segments may be placed at the same addresses, like
+            // banked ones, so we just pick the first one that fits.
+            let Some(current_section) = segments_list
+                .iter_mut()
+                .flat_map(|x| x.sections_list.iter_mut())
+                .find(|x| vram >= x.vram && vram < x.vram + x.size)
+            else {
+                continue;
+            };
+
+            current_section
+                .symbols
+                .push(symbol::Symbol::new_default(interner.intern_str(name), vram));
+        }
+
+        for segment in &mut segments_list {
+            segment.sections_list.sort_by_key(|x| x.vram);
+
+            for section in &mut segment.sections_list {
+                // ld65 doesn't tell the size of the symbols, so we infer them
+                // from the next symbol.
+                let section_end = section.vram + section.size;
+                for index in 0..section.symbols.len() {
+                    let next_vram = section
+                        .symbols
+                        .get(index + 1)
+                        .map_or(section_end, |x| x.vram);
+
+                    section.symbols[index].size = next_vram - section.symbols[index].vram;
+                }
+
+                Self::fixup_non_matching_symbols_for_section(section);
+            }
+        }
+
+        self.segments_list = segments_list;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,245 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT */
+
+use std::{collections::BTreeMap, path::Path};
+
+use regex::*;
+
+use crate::{
+    interned::Interner,
+    map_parse_error::{MapParseError, MapParseErrorKind},
+    map_parse_warning::{MapParseWarning, MapParseWarningKind},
+    mapfile::MapFile,
+    parser::{parse_hex, push_warning, BANNED_SYMBOL_NAMES},
+    section, segment, symbol,
+};
+
+impl MapFile {
+    /// Parses the contents of a Microsoft LINK (`link.exe`) map, or a map
+    /// generated by `lld-link /map`.
+    ///
+    /// The `map_contents` argument must contain the contents of a Microsoft
+    /// LINK mapfile.
+    ///
+    /// These maps don't list the address of each section, so it is inferred
+    /// from the `Rva+Base` column of its symbols. Sections without symbols
+    /// are skipped, and a parse warning is added for them.
+    #[must_use]
+    pub fn new_from_msvc_map_str(map_contents: &str) -> Self {
+        Self::try_new_from_msvc_map_str(map_contents).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Same as [`MapFile::new_from_msvc_map_str`], but returns an error
+    /// instead of panicking if the map is malformed.
+    pub fn try_new_from_msvc_map_str(map_contents: &str) -> Result<Self, MapParseError> {
+        let mut m = Self::new_impl();
+        m.try_parse_map_contents_msvc(map_contents)?;
+        Ok(m)
+    }
+
+    pub(crate) fn try_parse_map_contents_msvc(
+        &mut self,
+        map_contents: &str,
+    ) -> Result<(), MapParseError> {
+        let regex_sections_header = Regex::new(r"^\s*Start\s+Length\s+Name\s+Class\s*$").unwrap();
+        let regex_section_entry = Regex::new(r"^\s*(?P<index>[0-9a-fA-F]{4}):(?P<offset>[0-9a-fA-F]+)\s+(?P<length>[0-9a-fA-F]+)H\s+(?P<name>[^\s]+)\s+(?P<class>[^\s]+)\s*$").unwrap();
+        let regex_symbol_entry = Regex::new(r"^\s*(?P<index>[0-9a-fA-F]{4}):(?P<offset>[0-9a-fA-F]+)\s+(?P<name>[^\s]+)\s+(?P<rva>[0-9a-fA-F]+)(?P<rest>(\s+[^\s]+)*)\s*$").unwrap();
+
+        let mut in_sections_table = false;
+        let mut in_symbols_table = false;
+
+        let mut groups: BTreeMap<u64, MsvcSectionGroup> = BTreeMap::new();
+        let mut symbols = Vec::new();
+
+        for (line_index, line) in map_contents.lines().enumerate() {
+            let line_number = line_index + 1;
+
+            if regex_sections_header.is_match(line) {
+                in_sections_table = true;
+                in_symbols_table = false;
+            } else if line.contains("Publics by Value") || line.trim() == "Static symbols" {
+                in_sections_table = false;
+                in_symbols_table = true;
+            } else if line.trim_start().starts_with("entry point at") {
+                in_sections_table = false;
+                in_symbols_table = false;
+            } else if in_sections_table {
+                if let Some(section_match) = regex_section_entry.captures(line) {
+                    let index = parse_hex(&section_match["index"], line_number, line)?;
+                    let offset = parse_hex(&section_match["offset"], line_number, line)?;
+                    let length = parse_hex(&section_match["length"], line_number, line)?;
+                    let name = &section_match["name"];
+
+                    let end = offset.checked_add(length).ok_or_else(|| {
+                        MapParseError::new_at_line(
+                            line_number,
+                            line,
+                            MapParseErrorKind::AddressOverflow,
+                        )
+                    })?;
+
+                    let group = groups.entry(index).or_insert_with(|| MsvcSectionGroup {
+                        // `.text$mn` and similar are grouped into `.text`
+                        name: name.split('$').next().unwrap_or(name).to_string(),
+                        size: 0,
+                        vram: None,
+                        contributions: Vec::new(),
+                    });
+                    group.size = group.size.max(end);
+                    group.contributions.push((offset, length, name.to_string()));
+                }
+            } else if in_symbols_table {
+                if let Some(symbol_match) = regex_symbol_entry.captures(line) {
+                    let index = parse_hex(&symbol_match["index"], line_number, line)?;
+                    let offset = parse_hex(&symbol_match["offset"], line_number, line)?;
+                    let rva_base = parse_hex(&symbol_match["rva"], line_number, line)?;
+                    let name = &symbol_match["name"];
+
+                    // The last column is the object, the ones in between are
+                    // flags (`f` for functions, `i` for inlines).
+                    let object = symbol_match["rest"]
+                        .split_whitespace()
+                        .last()
+                        .filter(|x| !(x.starts_with('<') && x.ends_with('>')))
+                        .unwrap_or("");
+
+                    if index == 0 || BANNED_SYMBOL_NAMES.contains(&name) {
+                        // Absolute symbols
+                        continue;
+                    }
+
+                    symbols.push(MsvcSymbol {
+                        index,
+                        offset,
+                        vram: rva_base,
+                        name: name.to_string(),
+                        object: object.to_string(),
+                    });
+                }
+            }
+        }
+
+        // Public and static symbols are listed on separate tables
+        symbols.sort_by_key(|x| (x.index, x.offset));
+        symbols.dedup_by(|a, b| a.index == b.index && a.offset == b.offset && a.name == b.name);
+
+        for sym in &symbols {
+            if let Some(group) = groups.get_mut(&sym.index) {
+                if group.vram.is_none() {
+                    group.vram = sym.vram.checked_sub(sym.offset);
+                }
+            } else {
+                push_warning(
+                    &mut self.parse_warnings,
+                    &format!("{:04X}", sym.index),
+                    Path::new(&sym.object),
+                    &sym.name,
+                    MapParseWarningKind::UnknownSectionIndex,
+                );
+            }
+        }
+
+        self.segments_list = build_segments_msvc(groups, &symbols, &mut self.parse_warnings);
+
+        Ok(())
+    }
+}
+
+struct MsvcSectionGroup {
+    name: String,
+    size: u64,
+    /// Inferred from the first symbol of the section
+    vram: Option<u64>,
+    /// Offset, length and name of each entry of the section table
+    contributions: Vec<(u64, u64, String)>,
+}
+
+impl MsvcSectionGroup {
+    fn contribution_at(&self, offset: u64) -> Option<&(u64, u64, String)> {
+        self.contributions
+            .iter()
+            .find(|(start, length, _)| offset >= *start && offset - start < *length)
+    }
+}
+
+struct MsvcSymbol {
+    index: u64,
+    offset: u64,
+    vram: u64,
+    name: String,
+    object: String,
+}
+
+fn build_segments_msvc(
+    groups: BTreeMap<u64, MsvcSectionGroup>,
+    symbols: &[MsvcSymbol],
+    warnings: &mut Vec<MapParseWarning>,
+) -> Vec<segment::Segment> {
+    let mut segments_list = Vec::with_capacity(groups.len());
+    let mut interner = Interner::default();
+
+    for (index, group) in &groups {
+        let Some(vram) = group.vram else {
+            push_warning(
+                warnings,
+                &group.name,
+                Path::new(""),
+                "",
+                MapParseWarningKind::UnknownSegmentAddress,
+            );
+            continue;
+        };
+
+        let mut new_segment =
+            segment::Segment::new_default(group.name.clone(), vram, group.size, None);
+
+        let group_symbols: Vec<&MsvcSymbol> =
+            symbols.iter().filter(|x| x.index == *index).collect();
+
+        let mut current_key: Option<(&str, &str)> = None;
+        for (i, sym) in group_symbols.iter().enumerate() {
+            let contribution = group.contribution_at(sym.offset);
+            let section_type = contribution.map_or(group.name.as_str(), |(_, _, name)| name);
+
+            // A symbol ends where the next one starts, unless it is the last
+            // one of its section table entry.
+            let end = contribution.map_or(group.size, |(start, length, _)| start + length);
+            let next_offset = group_symbols
+                .get(i + 1)
+                .map_or(end, |next| next.offset.min(end));
+            let size = next_offset.saturating_sub(sym.offset);
+
+            let key = (section_type, sym.object.as_str());
+            if current_key != Some(key) {
+                current_key = Some(key);
+                new_segment
+                    .sections_list
+                    .push(section::Section::new_default(
+                        interner.intern_path(Path::new(&sym.object)),
+                        sym.vram,
+                        0,
+                        interner.intern_str(section_type),
+                    ));
+            }
+
+            let current_section = new_segment.sections_list.last_mut().unwrap();
+            let mut new_symbol =
+                symbol::Symbol::new_default(interner.intern_str(&sym.name), sym.vram);
+            new_symbol.size = size;
+
+            current_section.size = sym
+                .vram
+                .saturating_add(size)
+                .saturating_sub(current_section.vram);
+            current_section.symbols.push(new_symbol);
+        }
+
+        for section in &mut new_segment.sections_list {
+            MapFile::fixup_non_matching_symbols_for_section(section);
+        }
+
+        segments_list.push(new_segment);
+    }
+
+    segments_list
+}
@@ -0,0 +1,212 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT */
+
+use std::path::Path;
+
+use regex::*;
+
+use crate::{
+    interned::Interner,
+    map_parse_error::MapParseError,
+    mapfile::MapFile,
+    parser::{parse_hex, BANNED_SYMBOL_NAMES},
+    section, segment, symbol,
+};
+
+/// A symbol read from a symbol table listing, like the ones printed by `nm`
+/// and `objdump -t`, or from a delimited file.
+pub(crate) struct ListedSymbol<'a> {
+    pub object: &'a str,
+    pub section: &'a str,
+    pub vram: u64,
+    /// `None` if the listing doesn't tell the size of the symbol.
+    pub size: Option<u64>,
+    pub vrom: Option<u64>,
+    pub name: &'a str,
+}
+
+impl MapFile {
+    /// Parses the symbol listing printed by `nm -S --defined-only`.
+    ///
+    /// The `nm_contents` argument must contain the output of `nm`.
+    ///
+    /// `nm` doesn't print the section of each symbol, so symbols are grouped
+    /// by the section implied by their type letter (`T` and `W` to `.text`,
+    /// `D` and `V` to `.data`, `R` to `.rodata`, `B` to `.bss`, `G` to
+    /// `.sdata` and `S` to `.sbss`), and then by the object printed by
+    /// `nm -A` or by the object headers of a multi-file listing. Symbols of
+    /// other types are skipped.
+    ///
+    /// The size of symbols without a size column gets inferred from the next
+    /// symbol.
+    #[must_use]
+    pub fn new_from_nm_str(nm_contents: &str) -> Self {
+        Self::try_new_from_nm_str(nm_contents).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Same as [`MapFile::new_from_nm_str`], but returns an error instead of
+    /// panicking if the listing is malformed.
+    pub fn try_new_from_nm_str(nm_contents: &str) -> Result<Self, MapParseError> {
+        let mut m = Self::new_impl();
+        m.try_parse_map_contents_nm(nm_contents)?;
+        Ok(m)
+    }
+
+    pub(crate) fn try_parse_map_contents_nm(
+        &mut self,
+        map_contents: &str,
+    ) -> Result<(), MapParseError> {
+        let regex_object_header = Regex::new(r"^(?P<object>[^\s].*):$").unwrap();
+        // `nm -A` prefixes every line with the object, and archive members
+        // are printed as `archive:member:`.
+        let regex_symbol_entry = Regex::new(r"^(?:(?P<object>.+):)?(?P<vram>[0-9a-fA-F]+)(?:\s+(?P<size>[0-9a-fA-F]+))?\s+(?P<type>[A-Za-z])\s+(?P<name>.+?)\s*$").unwrap();
+
+        let mut symbols: Vec<ListedSymbol> = Vec::new();
+
+        let mut current_object = "";
+
+        for (line_index, line) in map_contents.lines().enumerate() {
+            let line_number = line_index + 1;
+
+            if let Some(symbol_match) = regex_symbol_entry.captures(line) {
+                let type_letter = &symbol_match["type"];
+                let name = symbol_match.name("name").unwrap().as_str();
+
+                let Some(section) = nm_type_section(type_letter) else {
+                    continue;
+                };
+                if BANNED_SYMBOL_NAMES.contains(&name) {
+                    continue;
+                }
+
+                let size = match symbol_match.name("size") {
+                    Some(x) => Some(parse_hex(x.as_str(), line_number, line)?),
+                    None => None,
+                };
+
+                symbols.push(ListedSymbol {
+                    object: symbol_match
+                        .name("object")
+                        .map_or(current_object, |x| x.as_str()),
+                    section,
+                    vram: parse_hex(&symbol_match["vram"], line_number, line)?,
+                    size,
+                    vrom: None,
+                    name,
+                });
+            } else if let Some(object_match) = regex_object_header.captures(line) {
+                current_object = object_match.name("object").unwrap().as_str();
+            }
+        }
+
+        self.segments_list = segments_from_symbol_listing(symbols);
+
+        Ok(())
+    }
+}
+
+/// The section implied by the type letter of an `nm` symbol.
+fn nm_type_section(type_letter: &str) -> Option<&'static str> {
+    match type_letter {
+        "T" | "t" | "W" | "w" | "i" => Some(".text"),
+        "D" | "d" | "V" | "v" | "u" => Some(".data"),
+        "R" | "r" => Some(".rodata"),
+        "B" | "b" => Some(".bss"),
+        "G" | "g" => Some(".sdata"),
+        "S" | "s" => Some(".sbss"),
+        _ => None,
+    }
+}
+
+/// Builds the segments of a symbol table listing.
+///
+/// Each section column becomes a segment, and the symbols of each object
+/// within it become a section.
+pub(crate) fn segments_from_symbol_listing(
+    mut symbols: Vec<ListedSymbol>,
+) -> Vec<segment::Segment> {
+    // Listings are usually sorted by name
+    symbols.sort_by_key(|x| x.vram);
+
+    let mut section_names: Vec<&str> = Vec::new();
+    for sym in &symbols {
+        if !section_names.contains(&sym.section) {
+            section_names.push(sym.section);
+        }
+    }
+
+    let mut segments_list = Vec::new();
+    let mut interner = Interner::default();
+
+    for section_name in section_names {
+        let segment_symbols: Vec<&ListedSymbol> = symbols
+            .iter()
+            .filter(|x| x.section == section_name)
+            .collect();
+
+        let mut new_segment = segment::Segment::new_default(section_name.to_string(), 0, 0, None);
+
+        for (index, sym) in segment_symbols.iter().enumerate() {
+            // Symbols without size get it inferred from the next symbol
+            let size = sym.size.unwrap_or_else(|| {
+                segment_symbols[index + 1..]
+                    .iter()
+                    .map(|x| x.vram)
+                    .find(|x| *x > sym.vram)
+                    .map_or(0, |x| x - sym.vram)
+            });
+
+            let section_index = match new_segment
+                .sections_list
+                .iter()
+                .position(|x| x.filepath.as_os_str() == sym.object)
+            {
+                Some(x) => x,
+                None => {
+                    new_segment
+                        .sections_list
+                        .push(section::Section::new_default(
+                            interner.intern_path(Path::new(sym.object)),
+                            sym.vram,
+                            0,
+                            interner.intern_str(section_name),
+                        ));
+                    new_segment.sections_list.last_mut().unwrap().vrom = sym.vrom;
+                    new_segment.sections_list.len() - 1
+                }
+            };
+            let current_section = &mut new_segment.sections_list[section_index];
+
+            let mut new_symbol =
+                symbol::Symbol::new_default(interner.intern_str(sym.name), sym.vram);
+            new_symbol.size = size;
+            new_symbol.vrom = sym.vrom;
+            current_section.symbols.push(new_symbol);
+
+            let section_end =
+                (current_section.vram + current_section.size).max(sym.vram.saturating_add(size));
+            current_section.size = section_end - current_section.vram;
+        }
+
+        new_segment.sections_list.sort_by_key(|x| x.vram);
+
+        let segment_start = new_segment.sections_list.first().map_or(0, |x| x.vram);
+        let segment_end = new_segment
+            .sections_list
+            .iter()
+            .map(|x| x.vram.saturating_add(x.size))
+            .max()
+            .unwrap_or(segment_start);
+        new_segment.vram = segment_start;
+        new_segment.size = segment_end - segment_start;
+        new_segment.vrom = new_segment.sections_list.first().and_then(|x| x.vrom);
+
+        for section in &mut new_segment.sections_list {
+            MapFile::fixup_non_matching_symbols_for_section(section);
+        }
+
+        segments_list.push(new_segment);
+    }
+
+    segments_list
+}
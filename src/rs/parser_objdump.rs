@@ -0,0 +1,88 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT */
+
+use regex::*;
+
+use crate::{
+    map_parse_error::MapParseError,
+    mapfile::MapFile,
+    parser::{parse_hex, BANNED_SYMBOL_NAMES},
+    parser_nm::{segments_from_symbol_listing, ListedSymbol},
+};
+
+impl MapFile {
+    /// Parses the symbol table printed by `objdump -t`.
+    ///
+    /// The `objdump_contents` argument must contain the output of
+    /// `objdump -t`.
+    ///
+    /// Each section column becomes a segment, and the symbols of each object
+    /// listed by `objdump` become a section of it. Section symbols, file
+    /// symbols and undefined, absolute and common symbols are skipped.
+    #[must_use]
+    pub fn new_from_objdump_str(objdump_contents: &str) -> Self {
+        Self::try_new_from_objdump_str(objdump_contents).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Same as [`MapFile::new_from_objdump_str`], but returns an error
+    /// instead of panicking if the listing is malformed.
+    pub fn try_new_from_objdump_str(objdump_contents: &str) -> Result<Self, MapParseError> {
+        let mut m = Self::new_impl();
+        m.try_parse_map_contents_objdump(objdump_contents)?;
+        Ok(m)
+    }
+
+    pub(crate) fn try_parse_map_contents_objdump(
+        &mut self,
+        map_contents: &str,
+    ) -> Result<(), MapParseError> {
+        let regex_object_header = Regex::new(r"^(?P<object>[^\s].*?):\s+file format\s").unwrap();
+        // The flags column is always 7 characters wide. Some targets print
+        // the `st_other` field or the visibility before the name.
+        let regex_symbol_entry = Regex::new(r"^(?P<vram>[0-9a-fA-F]+) (?P<flags>.{7}) (?P<section>[^\s]+)\s+(?P<size>[0-9a-fA-F]+)\s+(?:0x[0-9a-fA-F]+\s+|\.hidden\s+|\.protected\s+|\.internal\s+)?(?P<name>.+?)\s*$").unwrap();
+
+        let mut symbols: Vec<ListedSymbol> = Vec::new();
+
+        let mut current_object = "";
+
+        for (line_index, line) in map_contents.lines().enumerate() {
+            let line_number = line_index + 1;
+
+            if let Some(object_match) = regex_object_header.captures(line) {
+                current_object = object_match.name("object").unwrap().as_str();
+            } else if let Some(symbol_match) = regex_symbol_entry.captures(line) {
+                let flags = symbol_match["flags"].as_bytes();
+                let section = symbol_match.name("section").unwrap().as_str();
+                let name = symbol_match.name("name").unwrap().as_str();
+
+                // Debugging/section symbols and file symbols
+                if flags[5] == b'd' || flags[6] == b'f' {
+                    continue;
+                }
+                if matches!(section, "*UND*" | "*ABS*" | "*COM*") {
+                    continue;
+                }
+                if BANNED_SYMBOL_NAMES.contains(&name) {
+                    continue;
+                }
+
+                let size = parse_hex(&symbol_match["size"], line_number, line)?;
+
+                symbols.push(ListedSymbol {
+                    object: current_object,
+                    section,
+                    vram: parse_hex(&symbol_match["vram"], line_number, line)?,
+                    // Symbols without size, like hand-written asm ones, get
+                    // their size inferred from the next symbol.
+                    size: Some(size).filter(|x| *x != 0),
+                    vrom: None,
+                    name,
+                });
+            }
+        }
+
+        self.segments_list = segments_from_symbol_listing(symbols);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,204 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT */
+
+use std::path::Path;
+
+use regex::*;
+
+use crate::{
+    interned::Interner,
+    map_parse_error::MapParseError,
+    mapfile::MapFile,
+    parser::{parse_hex, BANNED_SYMBOL_NAMES},
+    section, segment, symbol, utils,
+};
+
+/// Size of the header of a PS-X EXE, the executable data starts right after
+/// it.
+const PSX_EXE_HEADER_SIZE: u64 = 0x800;
+
+impl MapFile {
+    /// Parses the contents of a Psy-Q psylink map.
+    ///
+    /// The `map_contents` argument must contain the contents of a psylink
+    /// mapfile.
+    ///
+    /// The rom addresses are calculated assuming the groups are laid out
+    /// sequentially on a PS-X EXE, following its header.
+    #[must_use]
+    pub fn new_from_psyq_map_str(map_contents: &str) -> Self {
+        Self::try_new_from_psyq_map_str(map_contents).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Same as [`MapFile::new_from_psyq_map_str`], but returns an error
+    /// instead of panicking if the map is malformed.
+    pub fn try_new_from_psyq_map_str(map_contents: &str) -> Result<Self, MapParseError> {
+        let mut m = Self::new_impl();
+        m.try_parse_map_contents_psyq(map_contents)?;
+        Ok(m)
+    }
+
+    pub(crate) fn try_parse_map_contents_psyq(
+        &mut self,
+        map_contents: &str,
+    ) -> Result<(), MapParseError> {
+        let regex_table_entry = Regex::new(r"^\s*(?P<start>[0-9a-fA-F]{8})\s+(?P<stop>[0-9a-fA-F]{8})\s+(?P<length>[0-9a-fA-F]{8})\s+(?P<rest>.+?)\s*$").unwrap();
+        let regex_symbol_entry =
+            Regex::new(r"^\s*(?P<address>[0-9a-fA-F]{8})\s+(?P<name>[^\s]+)\s*$").unwrap();
+
+        let mut in_symbols_list = false;
+        let mut temp_segment_list: Vec<segment::Segment> = Vec::new();
+        let mut symbols: Vec<(u64, &str)> = Vec::new();
+        let mut interner = Interner::default();
+
+        for (line_index, line) in map_contents.lines().enumerate() {
+            let line_number = line_index + 1;
+
+            if line.contains("Names alphabetical") || line.contains("Names in address order") {
+                in_symbols_list = true;
+                continue;
+            }
+
+            if in_symbols_list {
+                if let Some(symbol_match) = regex_symbol_entry.captures(line) {
+                    let vram = parse_hex(&symbol_match["address"], line_number, line)?;
+                    let name = symbol_match.name("name").unwrap().as_str();
+
+                    if !BANNED_SYMBOL_NAMES.contains(&name) {
+                        symbols.push((vram, name));
+                    }
+                }
+            } else if let Some(entry_match) = regex_table_entry.captures(line) {
+                let vram = parse_hex(&entry_match["start"], line_number, line)?;
+                let size = parse_hex(&entry_match["length"], line_number, line)?;
+
+                // The `Obj` column is optional, and objects may be listed
+                // at the end of the line.
+                let mut columns: Vec<&str> = entry_match
+                    .name("rest")
+                    .unwrap()
+                    .as_str()
+                    .split_whitespace()
+                    .collect();
+                if columns.len() > 1 && columns[0].chars().all(|c| c.is_ascii_digit()) {
+                    columns.remove(0);
+                }
+
+                let Some(&group) = columns.first() else {
+                    // A row without a group has nothing to attach to
+                    continue;
+                };
+                if temp_segment_list.last().map(|x| x.name.as_str()) != Some(group) {
+                    temp_segment_list.push(segment::Segment::new_default(
+                        group.to_string(),
+                        vram,
+                        size,
+                        None,
+                    ));
+                }
+                let current_segment = temp_segment_list.last_mut().unwrap();
+
+                if let Some(section_type) = columns.get(1) {
+                    let filepath = columns
+                        .get(2)
+                        .map(|x| x.trim_start_matches('(').trim_end_matches(')'))
+                        .unwrap_or(section_type);
+
+                    current_segment
+                        .sections_list
+                        .push(section::Section::new_default(
+                            interner.intern_path(Path::new(filepath)),
+                            vram,
+                            size,
+                            interner.intern_str(section_type),
+                        ));
+                }
+
+                let segment_end = current_segment.vram.saturating_add(current_segment.size);
+                current_segment.vram = current_segment.vram.min(vram);
+                current_segment.size =
+                    segment_end.max(vram.saturating_add(size)) - current_segment.vram;
+            }
+        }
+
+        // Both symbol lists contain the same symbols
+        symbols.sort();
+        symbols.dedup();
+
+        self.segments_list =
+            Self::post_process_segments_psyq(temp_segment_list, &symbols, &mut interner);
+
+        Ok(())
+    }
+
+    fn post_process_segments_psyq(
+        temp_segment_list: Vec<segment::Segment>,
+        symbols: &[(u64, &str)],
+        interner: &mut Interner,
+    ) -> Vec<segment::Segment> {
+        let mut segments_list = Vec::with_capacity(temp_segment_list.len());
+
+        let mut current_calculated_rom = PSX_EXE_HEADER_SIZE;
+
+        for segment in temp_segment_list {
+            let mut new_segment = segment.clone_no_sectionlist();
+
+            // Sections listed per object are more specific than the ones that
+            // only list the section type, so we drop the latter if both exist.
+            let has_objects = segment
+                .sections_list
+                .iter()
+                .any(|x| x.filepath.as_os_str() != x.section_type.as_str());
+
+            let is_noload_segment = segment.sections_list.iter().all(|x| x.is_noload_section())
+                || utils::is_noload_section(&segment.name)
+                || segment.name == "bss";
+            if !is_noload_segment {
+                new_segment.vrom = Some(current_calculated_rom);
+            }
+
+            for mut section in segment.sections_list {
+                if has_objects && section.filepath.as_os_str() == section.section_type.as_str() {
+                    continue;
+                }
+
+                let is_noload_section = is_noload_segment || section.is_noload_section();
+                if !is_noload_section {
+                    section.vrom = Some(current_calculated_rom + section.vram - segment.vram);
+                }
+
+                let section_end = section.vram.saturating_add(section.size);
+                let start_index = symbols.partition_point(|(vram, _)| *vram < section.vram);
+                let end_index = symbols.partition_point(|(vram, _)| *vram < section_end);
+                let section_symbols = &symbols[start_index..end_index];
+
+                for (i, (vram, name)) in section_symbols.iter().enumerate() {
+                    let next_vram = section_symbols
+                        .get(i + 1)
+                        .map_or(section_end, |(next_vram, _)| *next_vram);
+
+                    let mut new_symbol =
+                        symbol::Symbol::new_default(interner.intern_str(name), *vram);
+                    new_symbol.size = next_vram - vram;
+                    if let Some(section_vrom) = section.vrom {
+                        new_symbol.vrom = Some(section_vrom + vram - section.vram);
+                    }
+
+                    section.symbols.push(new_symbol);
+                }
+
+                Self::fixup_non_matching_symbols_for_section(&mut section);
+
+                new_segment.sections_list.push(section);
+            }
+
+            if !is_noload_segment {
+                current_calculated_rom += segment.size;
+            }
+
+            segments_list.push(new_segment);
+        }
+
+        segments_list
+    }
+}
@@ -0,0 +1,326 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT */
+
+use std::{
+    collections::BTreeSet,
+    path::{Path, PathBuf},
+};
+
+use regex::*;
+
+use crate::{
+    interned::Interner,
+    map_parse_error::MapParseError,
+    mapfile::MapFile,
+    parser::{parse_hex, symbol_outside_section, BANNED_SYMBOL_NAMES},
+    section, segment, symbol,
+};
+
+/// Size of each switchable ROM bank of the Game Boy.
+const GB_ROM_BANK_SIZE: u64 = 0x4000;
+
+/// Name, start address and size of each memory region of the Game Boy, and
+/// whether the region is part of the ROM.
+const GB_REGIONS: &[(&str, u64, u64, bool)] = &[
+    ("ROM0", 0x0000, 0x4000, true),
+    ("ROMX", 0x4000, 0x4000, true),
+    ("VRAM", 0x8000, 0x2000, false),
+    ("SRAM", 0xA000, 0x2000, false),
+    ("WRAM0", 0xC000, 0x1000, false),
+    ("WRAMX", 0xD000, 0x1000, false),
+    ("OAM", 0xFE00, 0x00A0, false),
+    ("HRAM", 0xFF80, 0x007F, false),
+];
+
+impl MapFile {
+    /// Parses the contents of a RGBDS map, as generated by `rgblink -m`.
+    ///
+    /// The `map_contents` argument must contain the contents of a rgblink
+    /// mapfile.
+    ///
+    /// Each bank becomes a segment. The rom address of symbols on ROM banks
+    /// is calculated as `bank * 0x4000 + offset`, which matches their offset
+    /// on the ROM file.
+    #[must_use]
+    pub fn new_from_rgbds_map_str(map_contents: &str) -> Self {
+        Self::try_new_from_rgbds_map_str(map_contents).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Same as [`MapFile::new_from_rgbds_map_str`], but returns an error
+    /// instead of panicking if the map is malformed.
+    pub fn try_new_from_rgbds_map_str(map_contents: &str) -> Result<Self, MapParseError> {
+        let mut m = Self::new_impl();
+        m.try_parse_map_contents_rgbds(map_contents)?;
+        Ok(m)
+    }
+
+    /// Parses the contents of a RGBDS symbol file, as generated by
+    /// `rgblink -n`.
+    ///
+    /// Symbol files don't contain information about the sections, so every
+    /// symbol of a bank is placed on the same section.
+    #[must_use]
+    pub fn new_from_rgbds_sym_str(sym_contents: &str) -> Self {
+        Self::try_new_from_rgbds_sym_str(sym_contents).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Same as [`MapFile::new_from_rgbds_sym_str`], but returns an error
+    /// instead of panicking if the file is malformed.
+    pub fn try_new_from_rgbds_sym_str(sym_contents: &str) -> Result<Self, MapParseError> {
+        let mut m = Self::new_impl();
+        m.try_add_rgbds_sym_str(sym_contents)?;
+        Ok(m)
+    }
+
+    /// Adds the symbols of a RGBDS symbol file to this `MapFile`, usually
+    /// one created from the mapfile of the same build.
+    ///
+    /// Symbols that already exist are left untouched. The rest of them are
+    /// added to the section that contains them, or to a section without a
+    /// name if no section of their bank contains them. The sizes of the
+    /// symbols of each updated bank are recalculated afterwards.
+    pub fn add_rgbds_sym_str(&mut self, sym_contents: &str) {
+        self.try_add_rgbds_sym_str(sym_contents)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Same as [`MapFile::add_rgbds_sym_str`], but returns an error instead
+    /// of panicking if the file is malformed.
+    pub fn try_add_rgbds_sym_str(&mut self, sym_contents: &str) -> Result<(), MapParseError> {
+        let regex_symbol_entry =
+            Regex::new(r"^\s*(?P<bank>[0-9a-fA-F]+):(?P<address>[0-9a-fA-F]+)\s+(?P<name>[^\s;]+)")
+                .unwrap();
+
+        let mut updated_segments = BTreeSet::new();
+        let mut interner = Interner::default();
+
+        for (line_index, line) in sym_contents.lines().enumerate() {
+            let line_number = line_index + 1;
+
+            let Some(symbol_match) = regex_symbol_entry.captures(line) else {
+                continue;
+            };
+            let bank = parse_hex(&symbol_match["bank"], line_number, line)?;
+            let vram = parse_hex(&symbol_match["address"], line_number, line)?;
+            let name = &symbol_match["name"];
+
+            if BANNED_SYMBOL_NAMES.contains(&name) {
+                continue;
+            }
+            let Some(region) = rgbds_region_of_address(vram) else {
+                // Echo RAM and hardware registers
+                continue;
+            };
+
+            let segment_name = rgbds_bank_name(region, bank);
+            let segment_index = match self
+                .segments_list
+                .iter()
+                .position(|x| x.name == segment_name)
+            {
+                Some(x) => x,
+                None => {
+                    self.segments_list.push(rgbds_new_bank(region, bank));
+                    self.segments_list.len() - 1
+                }
+            };
+            updated_segments.insert(segment_index);
+            let current_segment = &mut self.segments_list[segment_index];
+
+            // Sections without a name are the ones made up by us
+            let section_index = match current_segment.sections_list.iter().position(|x| {
+                !x.filepath.as_os_str().is_empty()
+                    && vram >= x.vram
+                    && (vram < x.vram + x.size || vram == x.vram)
+            }) {
+                Some(x) => x,
+                None => match current_segment
+                    .sections_list
+                    .iter()
+                    .position(|x| x.filepath.as_os_str().is_empty())
+                {
+                    Some(x) => x,
+                    None => {
+                        current_segment
+                            .sections_list
+                            .push(section::Section::new_default(
+                                PathBuf::new(),
+                                vram,
+                                0,
+                                interner.intern_str(region),
+                            ));
+                        current_segment.sections_list.len() - 1
+                    }
+                },
+            };
+            let current_section = &mut current_segment.sections_list[section_index];
+
+            if current_section.symbols.iter().any(|x| x.name == name) {
+                continue;
+            }
+            if current_section.filepath.as_os_str().is_empty() && vram < current_section.vram {
+                current_section.vram = vram;
+            }
+            current_section
+                .symbols
+                .push(symbol::Symbol::new_default(interner.intern_str(name), vram));
+        }
+
+        for segment_index in updated_segments {
+            let segment = &mut self.segments_list[segment_index];
+            let segment_end = segment.vram + segment.size;
+
+            segment.sections_list.sort_by_key(|x| x.vram);
+
+            // The sections we made up span until the next section
+            for index in 0..segment.sections_list.len() {
+                let next_vram = segment
+                    .sections_list
+                    .get(index + 1)
+                    .map_or(segment_end, |x| x.vram);
+                let section = &mut segment.sections_list[index];
+
+                if section.filepath.as_os_str().is_empty() {
+                    section.size = next_vram.saturating_sub(section.vram);
+                }
+            }
+
+            post_process_segment_rgbds(segment);
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn try_parse_map_contents_rgbds(
+        &mut self,
+        map_contents: &str,
+    ) -> Result<(), MapParseError> {
+        let regex_bank = Regex::new(
+            r"^(?P<region>ROM0|ROMX|VRAM|SRAM|WRAM0|WRAMX|OAM|HRAM) bank #(?P<bank>\d+):\s*$",
+        )
+        .unwrap();
+        let regex_section_entry = Regex::new(r#"^\s*SECTION: \$(?P<start>[0-9a-fA-F]+)(?:-\$[0-9a-fA-F]+)? \(\$(?P<size>[0-9a-fA-F]+) bytes?\) \["(?P<name>.*)"\]"#).unwrap();
+        let regex_symbol_entry =
+            Regex::new(r"^\s*\$(?P<address>[0-9a-fA-F]+) = (?P<name>[^\s]+)\s*$").unwrap();
+
+        let mut segments_list: Vec<segment::Segment> = Vec::new();
+        let mut current_region = None;
+        let mut interner = Interner::default();
+
+        for (line_index, line) in map_contents.lines().enumerate() {
+            let line_number = line_index + 1;
+
+            if let Some(bank_match) = regex_bank.captures(line) {
+                let region = bank_match.name("region").unwrap().as_str();
+                let bank = bank_match["bank"].parse::<u64>().unwrap_or(0);
+
+                segments_list.push(rgbds_new_bank(region, bank));
+                current_region = Some(region);
+            } else if let Some(section_match) = regex_section_entry.captures(line) {
+                let (Some(region), Some(current_segment)) =
+                    (current_region, segments_list.last_mut())
+                else {
+                    continue;
+                };
+
+                let vram = parse_hex(&section_match["start"], line_number, line)?;
+                let size = parse_hex(&section_match["size"], line_number, line)?;
+                let name = &section_match["name"];
+
+                let new_section = section::Section::new_default(
+                    interner.intern_path(Path::new(name)),
+                    vram,
+                    size,
+                    interner.intern_str(region),
+                );
+
+                // Banks can be bigger than expected, like ROM0 when using
+                // `rgblink -t`
+                let segment_end = current_segment.vram + current_segment.size;
+                current_segment.size = segment_end.max(vram + size) - current_segment.vram;
+
+                current_segment.sections_list.push(new_section);
+            } else if let Some(symbol_match) = regex_symbol_entry.captures(line) {
+                let vram = parse_hex(&symbol_match["address"], line_number, line)?;
+                let name = &symbol_match["name"];
+
+                let Some(current_section) = segments_list
+                    .last_mut()
+                    .and_then(|x| x.sections_list.last_mut())
+                else {
+                    return Err(symbol_outside_section(line_number, line));
+                };
+
+                if !BANNED_SYMBOL_NAMES.contains(&name) {
+                    current_section
+                        .symbols
+                        .push(symbol::Symbol::new_default(interner.intern_str(name), vram));
+                }
+            }
+        }
+
+        for segment in &mut segments_list {
+            post_process_segment_rgbds(segment);
+        }
+
+        self.segments_list = segments_list;
+
+        Ok(())
+    }
+}
+
+fn rgbds_bank_name(region: &str, bank: u64) -> String {
+    format!("{} bank #{}", region, bank)
+}
+
+fn rgbds_new_bank(region: &str, bank: u64) -> segment::Segment {
+    let (_, vram, size, is_rom) = GB_REGIONS
+        .iter()
+        .find(|(name, _, _, _)| *name == region)
+        .copied()
+        .unwrap_or((region, 0, 0, false));
+
+    // The first bank is always mapped at the start of the ROM, the rest of
+    // them are mapped into the switchable area.
+    let vrom = if region == "ROM0" {
+        Some(vram)
+    } else if is_rom {
+        Some(bank * GB_ROM_BANK_SIZE)
+    } else {
+        None
+    };
+
+    segment::Segment::new_default(rgbds_bank_name(region, bank), vram, size, vrom)
+}
+
+fn rgbds_region_of_address(address: u64) -> Option<&'static str> {
+    GB_REGIONS
+        .iter()
+        .find(|(_, start, size, _)| address >= *start && address < start + size)
+        .map(|(name, _, _, _)| *name)
+}
+
+/// Sorts the symbols of every section of the bank and infers their sizes
+/// from the next symbol, since RGBDS doesn't tell them.
+fn post_process_segment_rgbds(segment: &mut segment::Segment) {
+    for section in &mut segment.sections_list {
+        section.vrom = segment
+            .vrom
+            .map(|segment_vrom| segment_vrom + section.vram - segment.vram);
+        section.symbols.sort_by_key(|x| x.vram);
+
+        let section_end = section.vram + section.size;
+        for index in 0..section.symbols.len() {
+            let next_vram = section
+                .symbols
+                .get(index + 1)
+                .map_or(section_end, |x| x.vram);
+            let sym = &mut section.symbols[index];
+
+            sym.size = next_vram.saturating_sub(sym.vram);
+            sym.vrom = section.vrom.map(|x| x + sym.vram - section.vram);
+        }
+
+        MapFile::fixup_non_matching_symbols_for_section(section);
+    }
+}
@@ -0,0 +1,284 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT */
+
+use std::{collections::HashMap, path::Path};
+
+use regex::*;
+
+use crate::{
+    interned::Interner,
+    map_parse_error::MapParseError,
+    mapfile::MapFile,
+    parser::{parse_hex, BANNED_SYMBOL_NAMES},
+    section, segment, symbol,
+};
+
+/// Size of a real mode paragraph, the default distance between selectors.
+const WATCOM_PARAGRAPH_SIZE: u64 = 0x10;
+
+enum WatcomBlock {
+    None,
+    Segments,
+    MemoryMap,
+    ModuleSegments,
+}
+
+impl MapFile {
+    /// Parses the contents of an Open Watcom wlink map.
+    ///
+    /// The `map_contents` argument must contain the contents of a wlink
+    /// mapfile.
+    ///
+    /// The `seg:off` addresses of the map are flattened assuming real mode
+    /// addressing, where each selector is a paragraph. Use
+    /// [`MapFile::new_from_watcom_map_str_with_segment_bases`] to pick the
+    /// base address of each selector instead. The original selector is kept
+    /// on [`Segment::selector`](crate::Segment::selector).
+    #[must_use]
+    pub fn new_from_watcom_map_str(map_contents: &str) -> Self {
+        Self::try_new_from_watcom_map_str(map_contents).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Same as [`MapFile::new_from_watcom_map_str`], but returns an error
+    /// instead of panicking if the map is malformed.
+    pub fn try_new_from_watcom_map_str(map_contents: &str) -> Result<Self, MapParseError> {
+        Self::try_new_from_watcom_map_str_with_segment_bases(map_contents, &HashMap::new())
+    }
+
+    /// Parses the contents of an Open Watcom wlink map, using the given base
+    /// address for each selector to flatten the `seg:off` addresses of the
+    /// map.
+    ///
+    /// Selectors missing from `segment_bases` are flattened assuming real
+    /// mode addressing.
+    #[must_use]
+    pub fn new_from_watcom_map_str_with_segment_bases(
+        map_contents: &str,
+        segment_bases: &HashMap<u64, u64>,
+    ) -> Self {
+        Self::try_new_from_watcom_map_str_with_segment_bases(map_contents, segment_bases)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Same as [`MapFile::new_from_watcom_map_str_with_segment_bases`], but
+    /// returns an error instead of panicking if the map is malformed.
+    pub fn try_new_from_watcom_map_str_with_segment_bases(
+        map_contents: &str,
+        segment_bases: &HashMap<u64, u64>,
+    ) -> Result<Self, MapParseError> {
+        let mut m = Self::new_impl();
+        m.try_parse_map_contents_watcom(map_contents, segment_bases)?;
+        Ok(m)
+    }
+
+    pub(crate) fn try_parse_map_contents_watcom(
+        &mut self,
+        map_contents: &str,
+        segment_bases: &HashMap<u64, u64>,
+    ) -> Result<(), MapParseError> {
+        let regex_segment_entry = Regex::new(r"^(?P<name>[^\s]+)\s+(?P<class>[^\s]+)\s+(?P<group>[^\s]+)\s+(?P<selector>[0-9a-fA-F]{4}):(?P<offset>[0-9a-fA-F]+)\s+(?P<size>[0-9a-fA-F]+)").unwrap();
+        let regex_module = Regex::new(r"^Module: (?P<module>.+?)\s*$").unwrap();
+        let regex_symbol_entry = Regex::new(
+            r"^(?P<selector>[0-9a-fA-F]{4}):(?P<offset>[0-9a-fA-F]+)[*+s]?\s+(?P<name>.+?)\s*$",
+        )
+        .unwrap();
+        // The module column is left empty for every segment of the module
+        // but the first one.
+        let regex_module_segment_entry = Regex::new(r"^(?P<module>[^\s]+)?\s+(?P<segment>[^\s]+)\s+(?P<class>[^\s]+)\s+(?P<selector>[0-9a-fA-F]{4}):(?P<offset>[0-9a-fA-F]+)\s+(?P<size>[0-9a-fA-F]+)").unwrap();
+
+        let flatten = |selector: u64, offset: u64| {
+            segment_bases
+                .get(&selector)
+                .copied()
+                .unwrap_or(selector * WATCOM_PARAGRAPH_SIZE)
+                + offset
+        };
+
+        let mut block = WatcomBlock::None;
+
+        let mut segments_list: Vec<segment::Segment> = Vec::new();
+        let mut interner = Interner::default();
+        // Module, segment name, vram and size of each module contribution
+        let mut module_segments: Vec<(&str, &str, u64, u64)> = Vec::new();
+        // Module, vram and name of each symbol
+        let mut symbols: Vec<(&str, u64, &str)> = Vec::new();
+
+        let mut current_module = "";
+
+        for (line_index, line) in map_contents.lines().enumerate() {
+            let line_number = line_index + 1;
+
+            if line.trim_start().starts_with('|') {
+                block = match line.trim().trim_matches('|').trim() {
+                    "Segments" => WatcomBlock::Segments,
+                    "Memory Map" => WatcomBlock::MemoryMap,
+                    "Module Segments" => WatcomBlock::ModuleSegments,
+                    _ => WatcomBlock::None,
+                };
+                continue;
+            }
+
+            match block {
+                WatcomBlock::None => {}
+                WatcomBlock::Segments => {
+                    if let Some(segment_match) = regex_segment_entry.captures(line) {
+                        let name = &segment_match["name"];
+                        let selector = parse_hex(&segment_match["selector"], line_number, line)?;
+                        let offset = parse_hex(&segment_match["offset"], line_number, line)?;
+                        let size = parse_hex(&segment_match["size"], line_number, line)?;
+
+                        let mut new_segment = segment::Segment::new_default(
+                            name.to_string(),
+                            flatten(selector, offset),
+                            size,
+                            None,
+                        );
+                        new_segment.selector = Some(selector);
+                        segments_list.push(new_segment);
+                    }
+                }
+                WatcomBlock::MemoryMap => {
+                    if let Some(module_match) = regex_module.captures(line) {
+                        current_module = module_match.name("module").unwrap().as_str();
+                    } else if let Some(symbol_match) = regex_symbol_entry.captures(line) {
+                        let selector = parse_hex(&symbol_match["selector"], line_number, line)?;
+                        let offset = parse_hex(&symbol_match["offset"], line_number, line)?;
+                        let name = symbol_match.name("name").unwrap().as_str();
+
+                        if !BANNED_SYMBOL_NAMES.contains(&name) {
+                            symbols.push((current_module, flatten(selector, offset), name));
+                        }
+                    }
+                }
+                WatcomBlock::ModuleSegments => {
+                    if let Some(entry_match) = regex_module_segment_entry.captures(line) {
+                        if let Some(module) = entry_match.name("module") {
+                            current_module = module.as_str();
+                        }
+                        let segment_name = entry_match.name("segment").unwrap().as_str();
+                        let selector = parse_hex(&entry_match["selector"], line_number, line)?;
+                        let offset = parse_hex(&entry_match["offset"], line_number, line)?;
+                        let size = parse_hex(&entry_match["size"], line_number, line)?;
+
+                        module_segments.push((
+                            current_module,
+                            segment_name,
+                            flatten(selector, offset),
+                            size,
+                        ));
+                    }
+                }
+            }
+        }
+
+        let segment_index_of =
+            |segments_list: &[segment::Segment], vram: u64, name: Option<&str>| {
+                segments_list.iter().position(|x| {
+                    name.map_or(true, |name| x.name == name)
+                        && vram >= x.vram
+                        && (vram < x.vram + x.size || vram == x.vram)
+                })
+            };
+
+        if module_segments.is_empty() {
+            // Without the module segments table, we group every consecutive
+            // symbol of the same module into a section.
+            symbols.sort_by_key(|(_, vram, _)| *vram);
+
+            for (module, vram, _) in &symbols {
+                let Some(segment_index) = segment_index_of(&segments_list, *vram, None) else {
+                    continue;
+                };
+                let current_segment = &mut segments_list[segment_index];
+
+                if current_segment
+                    .sections_list
+                    .last()
+                    .map_or(true, |x| x.filepath.as_os_str() != *module)
+                {
+                    let section_type = interner.intern_str(&current_segment.name);
+                    current_segment
+                        .sections_list
+                        .push(section::Section::new_default(
+                            interner.intern_path(Path::new(module)),
+                            *vram,
+                            0,
+                            section_type,
+                        ));
+                }
+            }
+
+            for segment in &mut segments_list {
+                let segment_end = segment.vram + segment.size;
+
+                for index in 0..segment.sections_list.len() {
+                    let next_vram = segment
+                        .sections_list
+                        .get(index + 1)
+                        .map_or(segment_end, |x| x.vram);
+                    let section = &mut segment.sections_list[index];
+
+                    section.size = next_vram.saturating_sub(section.vram);
+                }
+            }
+        } else {
+            for (module, segment_name, vram, size) in module_segments {
+                let Some(segment_index) =
+                    segment_index_of(&segments_list, vram, Some(segment_name))
+                else {
+                    continue;
+                };
+
+                segments_list[segment_index]
+                    .sections_list
+                    .push(section::Section::new_default(
+                        interner.intern_path(Path::new(module)),
+                        vram,
+                        size,
+                        interner.intern_str(segment_name),
+                    ));
+            }
+        }
+
+        for (_, vram, name) in symbols {
+            // Absolute symbols don't belong to any segment
+            let Some(current_section) = segments_list
+                .iter_mut()
+                .flat_map(|x| x.sections_list.iter_mut())
+                .find(|x| vram >= x.vram && vram < x.vram + x.size)
+            else {
+                continue;
+            };
+
+            current_section
+                .symbols
+                .push(symbol::Symbol::new_default(interner.intern_str(name), vram));
+        }
+
+        for segment in &mut segments_list {
+            segment.sections_list.sort_by_key(|x| x.vram);
+
+            for section in &mut segment.sections_list {
+                section.symbols.sort_by_key(|x| x.vram);
+
+                // wlink doesn't tell the size of the symbols, so we infer
+                // them from the next symbol.
+                let section_end = section.vram + section.size;
+                for index in 0..section.symbols.len() {
+                    let next_vram = section
+                        .symbols
+                        .get(index + 1)
+                        .map_or(section_end, |x| x.vram);
+
+                    section.symbols[index].size = next_vram - section.symbols[index].vram;
+                }
+
+                Self::fixup_non_matching_symbols_for_section(section);
+            }
+        }
+
+        self.segments_list = segments_list;
+
+        Ok(())
+    }
+}
@@ -253,7 +253,7 @@ fn report_from_section(
             measures.total_functions += 1;
 
             functions.push(report::ReportItem {
-                name: sym.name.clone(),
+                name: sym.name.to_string(),
                 size: sym.size,
                 fuzzy_match_percent,
                 metadata: Some(report::ReportItemMetadata {
@@ -346,7 +346,7 @@ fn merge_measures(
 
 fn report_item_from_section(section: &section::Section) -> report::ReportItem {
     report::ReportItem {
-        name: section.section_type.clone(),
+        name: section.section_type.to_string(),
         size: section.size,
         fuzzy_match_percent: 0.0,
         metadata: Some(report::ReportItemMetadata {
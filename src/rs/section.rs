@@ -13,20 +13,23 @@ use pyo3::prelude::*;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-use crate::{symbol, utils, SymbolDecompStateIter};
+use crate::{
+    interned::{InternedPath, InternedStr},
+    symbol, utils, SymbolDecompStateIter,
+};
 
 #[derive(Debug, Clone)]
 #[non_exhaustive]
 #[cfg_attr(feature = "python_bindings", pyclass(module = "mapfile_parser"))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Section {
-    pub filepath: PathBuf,
+    pub filepath: InternedPath,
 
     pub vram: u64,
 
     pub size: u64,
 
-    pub section_type: String,
+    pub section_type: InternedStr,
 
     pub vrom: Option<u64>,
 
@@ -39,21 +42,29 @@ pub struct Section {
 
 impl Section {
     pub fn new(
-        filepath: PathBuf,
+        filepath: impl Into<InternedPath>,
         vram: u64,
         size: u64,
-        section_type: String,
+        section_type: impl Into<InternedStr>,
         vrom: Option<u64>,
         align: Option<u64>,
     ) -> Self {
-        Self::new_impl(filepath, vram, size, section_type, vrom, align, false)
+        Self::new_impl(
+            filepath.into(),
+            vram,
+            size,
+            section_type.into(),
+            vrom,
+            align,
+            false,
+        )
     }
 
     pub(crate) fn new_impl(
-        filepath: PathBuf,
+        filepath: InternedPath,
         vram: u64,
         size: u64,
-        section_type: String,
+        section_type: InternedStr,
         vrom: Option<u64>,
         align: Option<u64>,
         is_fill: bool,
@@ -303,20 +314,41 @@ impl Section {
 
 impl Section {
     pub(crate) fn new_default(
-        filepath: PathBuf,
+        filepath: impl Into<InternedPath>,
         vram: u64,
         size: u64,
-        section_type: String,
+        section_type: impl Into<InternedStr>,
     ) -> Self {
-        Self::new_impl(filepath, vram, size, section_type, None, None, false)
+        Self::new_impl(
+            filepath.into(),
+            vram,
+            size,
+            section_type.into(),
+            None,
+            None,
+            false,
+        )
     }
 
     pub(crate) fn new_placeholder() -> Self {
         Self::new_impl("".into(), 0, 0, "".into(), None, None, false)
     }
 
-    pub(crate) fn new_fill(filepath: PathBuf, vram: u64, size: u64, section_type: String) -> Self {
-        Self::new_impl(filepath, vram, size, section_type, None, None, true)
+    pub(crate) fn new_fill(
+        filepath: impl Into<InternedPath>,
+        vram: u64,
+        size: u64,
+        section_type: impl Into<InternedStr>,
+    ) -> Self {
+        Self::new_impl(
+            filepath.into(),
+            vram,
+            size,
+            section_type.into(),
+            None,
+            None,
+            true,
+        )
     }
 
     pub fn is_placeholder(&self) -> bool {
@@ -332,7 +364,7 @@ impl Section {
     pub fn symbol_match_state_iter(
         &self,
         path_decomp_settings: Option<&PathDecompSettings>,
-    ) -> SymbolDecompStateIter {
+    ) -> SymbolDecompStateIter<'_> {
         let mut whole_file_is_undecomped = false;
         let mut functions_path = None;
 
@@ -410,7 +442,15 @@ pub(crate) mod python_bindings {
             align: Option<u64>,
             is_fill: bool,
         ) -> Self {
-            Self::new_impl(filepath, vram, size, section_type, vrom, align, is_fill)
+            Self::new_impl(
+                filepath.into(),
+                vram,
+                size,
+                section_type.into(),
+                vrom,
+                align,
+                is_fill,
+            )
         }
 
         /* Getters and setters */
@@ -421,7 +461,7 @@ pub(crate) mod python_bindings {
             Python::with_gil(|py| {
                 let pathlib = py.import("pathlib")?;
                 let pathlib_path = pathlib.getattr(intern!(py, "Path"))?;
-                let args = (self.filepath.clone(),);
+                let args = (self.filepath.as_path(),);
 
                 pathlib_path.call1(args)?.into_py_any(py)
             })
@@ -429,7 +469,7 @@ pub(crate) mod python_bindings {
 
         #[setter]
         fn set_filepath(&mut self, value: PathBuf) -> PyResult<()> {
-            self.filepath = value;
+            self.filepath = value.into();
             Ok(())
         }
 
@@ -456,13 +496,13 @@ pub(crate) mod python_bindings {
         }
 
         #[getter]
-        fn get_sectionType(&self) -> PyResult<String> {
-            Ok(self.section_type.clone())
+        fn get_sectionType(&self) -> PyResult<&str> {
+            Ok(&self.section_type)
         }
 
         #[setter]
         fn set_sectionType(&mut self, value: String) -> PyResult<()> {
-            self.section_type = value;
+            self.section_type = value.into();
             Ok(())
         }
 
@@ -31,6 +31,10 @@ pub struct Segment {
 
     pub align: Option<u64>,
 
+    /// The segment selector of maps using segmented (`seg:off`) addresses.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub selector: Option<u64>,
+
     pub sections_list: Vec<section::Section>,
 }
 
@@ -42,6 +46,7 @@ impl Segment {
             size,
             vrom,
             align,
+            selector: None,
             sections_list: Vec::new(),
         }
     }
@@ -81,7 +86,7 @@ impl Segment {
     pub fn find_symbol_by_name(
         &self,
         sym_name: &str,
-    ) -> Option<found_symbol_info::FoundSymbolInfo> {
+    ) -> Option<found_symbol_info::FoundSymbolInfo<'_>> {
         for section in &self.sections_list {
             if let Some(sym) = section.find_symbol_by_name(sym_name) {
                 return Some(found_symbol_info::FoundSymbolInfo::new_default(
@@ -99,7 +104,7 @@ impl Segment {
     pub fn find_symbol_by_vram_or_vrom(
         &self,
         address: u64,
-    ) -> Option<found_symbol_info::FoundSymbolInfo> {
+    ) -> Option<found_symbol_info::FoundSymbolInfo<'_>> {
         for section in &self.sections_list {
             #[allow(deprecated)]
             if let Some((sym, offset)) = section.find_symbol_by_vram_or_vrom(address) {
@@ -115,7 +120,7 @@ impl Segment {
         &self,
         address: u64,
     ) -> (
-        Option<found_symbol_info::FoundSymbolInfo>,
+        Option<found_symbol_info::FoundSymbolInfo<'_>>,
         Vec<&section::Section>,
     ) {
         let mut possible_sections = Vec::new();
@@ -139,7 +144,7 @@ impl Segment {
         &self,
         address: u64,
     ) -> (
-        Option<found_symbol_info::FoundSymbolInfo>,
+        Option<found_symbol_info::FoundSymbolInfo<'_>>,
         Vec<&section::Section>,
     ) {
         let mut possible_sections = Vec::new();
@@ -268,6 +273,7 @@ impl Segment {
             size,
             vrom,
             align: None,
+            selector: None,
             sections_list: Vec::new(),
         }
     }
@@ -279,6 +285,7 @@ impl Segment {
             size: self.size,
             vrom: self.vrom,
             align: self.align,
+            selector: self.selector,
             sections_list: Vec::new(),
         }
     }
@@ -290,6 +297,7 @@ impl Segment {
             size: 0,
             vrom: None,
             align: None,
+            selector: None,
             sections_list: vec![section::Section::new_placeholder()],
         }
     }
@@ -421,6 +429,17 @@ pub(crate) mod python_bindings {
             Ok(())
         }
 
+        #[getter]
+        fn get_selector(&self) -> PyResult<Option<u64>> {
+            Ok(self.selector)
+        }
+
+        #[setter]
+        fn set_selector(&mut self, value: Option<u64>) -> PyResult<()> {
+            self.selector = value;
+            Ok(())
+        }
+
         /* Methods */
 
         fn filterBySectionType(&self, section_type: &str) -> Self {
@@ -10,12 +10,14 @@ use pyo3::prelude::*;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
+use crate::interned::InternedStr;
+
 #[derive(Debug, Clone)]
 #[non_exhaustive]
 #[cfg_attr(feature = "python_bindings", pyclass(module = "mapfile_parser"))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Symbol {
-    pub name: String,
+    pub name: InternedStr,
 
     pub vram: u64,
 
@@ -31,11 +33,20 @@ pub struct Symbol {
     /// Note the symbol with the actual `.NON_MATCHING` will have this member
     /// set to `false`.
     pub nonmatching_sym_exists: bool,
+
+    /// `true` if the size of this symbol couldn't be inferred from the
+    /// mapfile, like when the symbol that follows it has a lower address. The
+    /// `size` of these symbols is left as zero, and a [`MapParseWarning`]
+    /// describing the problem is added to the `MapFile`.
+    ///
+    /// [`MapParseWarning`]: crate::MapParseWarning
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub size_is_unknown: bool,
 }
 
 impl Symbol {
     fn new_impl(
-        name: String,
+        name: InternedStr,
         vram: u64,
         size: u64,
         vrom: Option<u64>,
@@ -49,15 +60,22 @@ impl Symbol {
             vrom,
             align,
             nonmatching_sym_exists,
+            size_is_unknown: false,
         }
     }
 
-    pub fn new(name: String, vram: u64, size: u64, vrom: Option<u64>, align: Option<u64>) -> Self {
-        Self::new_impl(name, vram, size, vrom, align, false)
+    pub fn new(
+        name: impl Into<InternedStr>,
+        vram: u64,
+        size: u64,
+        vrom: Option<u64>,
+        align: Option<u64>,
+    ) -> Self {
+        Self::new_impl(name.into(), vram, size, vrom, align, false)
     }
 
-    pub fn new_default(name: String, vram: u64) -> Self {
-        Self::new_impl(name, vram, 0, None, None, false)
+    pub fn new_default(name: impl Into<InternedStr>, vram: u64) -> Self {
+        Self::new_impl(name.into(), vram, 0, None, None, false)
     }
 
     pub fn get_vram_str(&self) -> String {
@@ -137,7 +155,7 @@ pub(crate) mod python_bindings {
             align: Option<u64>,
             nonmatchingSymExists: bool,
         ) -> Self {
-            Self::new_impl(name, vram, size, vrom, align, nonmatchingSymExists)
+            Self::new_impl(name.into(), vram, size, vrom, align, nonmatchingSymExists)
         }
 
         /* Getters and setters */
@@ -149,7 +167,7 @@ pub(crate) mod python_bindings {
 
         #[setter]
         fn set_name(&mut self, value: String) -> PyResult<()> {
-            self.name = value;
+            self.name = value.into();
             Ok(())
         }
 
@@ -208,6 +226,17 @@ pub(crate) mod python_bindings {
             Ok(())
         }
 
+        #[getter]
+        fn get_sizeIsUnknown(&self) -> PyResult<bool> {
+            Ok(self.size_is_unknown)
+        }
+
+        #[setter]
+        fn set_sizeIsUnknown(&mut self, value: bool) -> PyResult<()> {
+            self.size_is_unknown = value;
+            Ok(())
+        }
+
         /* Serializers */
 
         #[pyo3(signature=(_humanReadable=true))]
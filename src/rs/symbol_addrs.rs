@@ -0,0 +1,208 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT */
+
+use std::fmt::Write;
+
+use regex::*;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    map_parse_error::{MapParseError, MapParseErrorKind},
+    mapfile::MapFile,
+    parser_nm::{segments_from_symbol_listing, ListedSymbol},
+};
+
+/// An entry of a splat `symbol_addrs.txt` file, like
+/// `name = 0x80001234; // type:func size:0x40 rom:0x1234`.
+///
+/// Only the attributes relevant to this crate are kept.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SymbolAddrsEntry {
+    pub name: String,
+
+    pub vram: u64,
+
+    /// The `size:` attribute.
+    pub size: Option<u64>,
+
+    /// The `rom:` attribute.
+    pub vrom: Option<u64>,
+
+    /// The `type:` attribute, like `func` or `data`.
+    pub sym_type: Option<String>,
+
+    /// The `segment:` attribute.
+    pub segment: Option<String>,
+
+    /// The 1-based line number of the entry on the parsed file.
+    pub line_number: usize,
+}
+
+impl SymbolAddrsEntry {
+    /// Parses every entry of a splat `symbol_addrs.txt` file.
+    ///
+    /// Comment lines, blank lines and lines that are not a symbol assignment
+    /// are skipped. Unknown attributes are ignored.
+    pub fn parse_list(symbol_addrs_contents: &str) -> Result<Vec<Self>, MapParseError> {
+        let regex_entry = Regex::new(r"^\s*(?P<name>[^\s=]+)\s*=\s*(?P<vram>0[xX][0-9a-fA-F]+|[0-9]+)\s*;(?:\s*//(?P<attributes>.*))?").unwrap();
+
+        let mut entries = Vec::new();
+
+        for (line_index, line) in symbol_addrs_contents.lines().enumerate() {
+            let line_number = line_index + 1;
+
+            let Some(entry_match) = regex_entry.captures(line) else {
+                continue;
+            };
+
+            let mut entry = Self {
+                name: entry_match["name"].to_string(),
+                vram: parse_int(&entry_match["vram"], line_number, line)?,
+                size: None,
+                vrom: None,
+                sym_type: None,
+                segment: None,
+                line_number,
+            };
+
+            if let Some(attributes) = entry_match.name("attributes") {
+                for attribute in attributes.as_str().split_whitespace() {
+                    let Some((key, value)) = attribute.split_once(':') else {
+                        continue;
+                    };
+
+                    match key {
+                        "size" => entry.size = Some(parse_int(value, line_number, line)?),
+                        "rom" => entry.vrom = Some(parse_int(value, line_number, line)?),
+                        "type" => entry.sym_type = Some(value.to_string()),
+                        "segment" => entry.segment = Some(value.to_string()),
+                        _ => {}
+                    }
+                }
+            }
+
+            entries.push(entry);
+        }
+
+        Ok(entries)
+    }
+
+    /// Formats this entry as a `symbol_addrs.txt` line.
+    pub fn to_line(&self) -> String {
+        let mut attributes = String::new();
+        if let Some(sym_type) = &self.sym_type {
+            write!(attributes, " type:{}", sym_type).unwrap();
+        }
+        if let Some(size) = self.size {
+            write!(attributes, " size:0x{:X}", size).unwrap();
+        }
+        if let Some(vrom) = self.vrom {
+            write!(attributes, " rom:0x{:X}", vrom).unwrap();
+        }
+        if let Some(segment) = &self.segment {
+            write!(attributes, " segment:{}", segment).unwrap();
+        }
+
+        if attributes.is_empty() {
+            format!("{} = 0x{:08X};", self.name, self.vram)
+        } else {
+            format!("{} = 0x{:08X}; //{}", self.name, self.vram, attributes)
+        }
+    }
+}
+
+impl MapFile {
+    /// Builds a symbol-only `MapFile` from the contents of a splat
+    /// `symbol_addrs.txt` file.
+    ///
+    /// Every symbol is placed on a single segment and section, unless the
+    /// entries have a `segment:` attribute, in which case each segment
+    /// attribute becomes a segment.
+    ///
+    /// Symbols without a `size:` attribute get their size inferred from the
+    /// next symbol.
+    #[must_use]
+    pub fn new_from_splat_symbol_addrs_str(symbol_addrs_contents: &str) -> Self {
+        Self::try_new_from_splat_symbol_addrs_str(symbol_addrs_contents)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Same as [`MapFile::new_from_splat_symbol_addrs_str`], but returns an
+    /// error instead of panicking if a number is malformed.
+    pub fn try_new_from_splat_symbol_addrs_str(
+        symbol_addrs_contents: &str,
+    ) -> Result<Self, MapParseError> {
+        let entries = SymbolAddrsEntry::parse_list(symbol_addrs_contents)?;
+
+        let symbols = entries
+            .iter()
+            .map(|x| ListedSymbol {
+                object: "",
+                section: x.segment.as_deref().unwrap_or(""),
+                vram: x.vram,
+                size: x.size,
+                vrom: x.vrom,
+                name: &x.name,
+            })
+            .collect();
+
+        let mut m = Self::new_impl();
+        m.segments_list = segments_from_symbol_listing(symbols);
+        Ok(m)
+    }
+
+    /// Generates the contents of a splat `symbol_addrs.txt` file listing
+    /// every symbol of this map.
+    ///
+    /// The `size:` and `rom:` attributes are taken from the size and rom
+    /// address of each symbol. Symbols with a size of zero don't get a size
+    /// attribute.
+    pub fn to_splat_symbol_addrs(&self) -> String {
+        let mut ret = String::new();
+
+        for segment in &self.segments_list {
+            for section in &segment.sections_list {
+                for sym in &section.symbols {
+                    let entry = SymbolAddrsEntry {
+                        name: sym.name.to_string(),
+                        vram: sym.vram,
+                        size: (sym.size != 0).then_some(sym.size),
+                        vrom: sym.vrom,
+                        sym_type: None,
+                        segment: None,
+                        line_number: 0,
+                    };
+
+                    writeln!(ret, "{}", entry.to_line()).unwrap();
+                }
+            }
+        }
+
+        ret
+    }
+}
+
+/// Parses a number written either in hexadecimal with a `0x` prefix or in
+/// decimal, as splat does.
+fn parse_int(src: &str, line_number: usize, line: &str) -> Result<u64, MapParseError> {
+    if let Some(digits) = src.strip_prefix("0x").or_else(|| src.strip_prefix("0X")) {
+        u64::from_str_radix(digits, 16).map_err(|_| {
+            MapParseError::new_at_line(
+                line_number,
+                line,
+                MapParseErrorKind::InvalidHexNumber(src.to_string()),
+            )
+        })
+    } else {
+        src.parse().map_err(|_| {
+            MapParseError::new_at_line(
+                line_number,
+                line,
+                MapParseErrorKind::InvalidDecimalNumber(src.to_string()),
+            )
+        })
+    }
+}
@@ -0,0 +1,308 @@
+/* SPDX-FileCopyrightText: © 2025 Decompollaborate */
+/* SPDX-License-Identifier: MIT */
+
+use std::fmt;
+
+use crate::{
+    found_symbol_info::FoundSymbolInfo, mapfile::MapFile, segment::Segment,
+    symbol_addrs::SymbolAddrsEntry,
+};
+
+/// How an entry of a `symbol_addrs.txt` file compares to the symbol with the
+/// same name on a map.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum SymbolAddrsStatus {
+    /// The address, and the size and rom address if the entry has them,
+    /// match the ones of the map.
+    Matching,
+
+    /// The symbol was moved.
+    AddressMismatch { map_vram: u64 },
+
+    /// The entry has a `size:` attribute that differs from the size of the
+    /// symbol.
+    SizeMismatch { map_size: u64 },
+
+    /// The entry has a `rom:` attribute that differs from the rom address of
+    /// the symbol.
+    RomMismatch { map_vrom: Option<u64> },
+
+    /// There's no symbol with this name on the map.
+    Absent,
+}
+
+impl fmt::Display for SymbolAddrsStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SymbolAddrsStatus::Matching => write!(f, "matching"),
+            SymbolAddrsStatus::AddressMismatch { map_vram } => {
+                write!(f, "address is 0x{:08X} on the map", map_vram)
+            }
+            SymbolAddrsStatus::SizeMismatch { map_size } => {
+                write!(f, "size is 0x{:X} on the map", map_size)
+            }
+            SymbolAddrsStatus::RomMismatch { map_vrom: Some(x) } => {
+                write!(f, "rom address is 0x{:X} on the map", x)
+            }
+            SymbolAddrsStatus::RomMismatch { map_vrom: None } => {
+                write!(f, "symbol has no rom address on the map")
+            }
+            SymbolAddrsStatus::Absent => write!(f, "missing from the map"),
+        }
+    }
+}
+
+/// The result of checking a single `symbol_addrs.txt` entry against a map.
+#[derive(Debug, Clone)]
+pub struct SymbolAddrsCheck<'a> {
+    pub entry: &'a SymbolAddrsEntry,
+
+    /// Either a single [`SymbolAddrsStatus::Matching`] or
+    /// [`SymbolAddrsStatus::Absent`], or every field that differs, in the
+    /// order address, size and rom address.
+    pub statuses: Vec<SymbolAddrsStatus>,
+
+    /// The symbol of the map with the same name as the entry.
+    ///
+    /// For [`SymbolAddrsStatus::Absent`] entries, this is the symbol placed
+    /// at the address of the entry instead, if any, since it may have been
+    /// renamed.
+    pub map_symbol: Option<FoundSymbolInfo<'a>>,
+}
+
+impl<'a> SymbolAddrsCheck<'a> {
+    pub fn new(
+        entry: &'a SymbolAddrsEntry,
+        statuses: Vec<SymbolAddrsStatus>,
+        map_symbol: Option<FoundSymbolInfo<'a>>,
+    ) -> Self {
+        Self {
+            entry,
+            statuses,
+            map_symbol,
+        }
+    }
+}
+
+impl fmt::Display for SymbolAddrsCheck<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: ", self.entry.line_number, self.entry.name)?;
+        for (i, status) in self.statuses.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", status)?;
+        }
+
+        if let (true, Some(info)) = (
+            self.statuses.contains(&SymbolAddrsStatus::Absent),
+            &self.map_symbol,
+        ) {
+            write!(f, " (the map has '{}' at its address)", info.symbol.name)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl MapFile {
+    /// Checks every entry of a splat `symbol_addrs.txt` file against the
+    /// symbols of this map, to find stale entries.
+    ///
+    /// Entries are looked up by name. If more than one segment has a symbol
+    /// with that name, like overlays, the one from the segment given by the
+    /// `segment:` attribute is used, or the one at the address of the entry
+    /// otherwise.
+    ///
+    /// The size and rom address are only checked if the entry has a `size:`
+    /// or `rom:` attribute.
+    pub fn check_symbol_addrs<'a>(
+        &'a self,
+        entries: &'a [SymbolAddrsEntry],
+    ) -> Vec<SymbolAddrsCheck<'a>> {
+        let mut checks = Vec::with_capacity(entries.len());
+
+        for entry in entries {
+            let Some(info) = self.find_symbol_addrs_entry(entry) else {
+                let at_address = self
+                    .find_symbol_by_vram(entry.vram)
+                    .0
+                    .filter(|x| x.offset == 0);
+                checks.push(SymbolAddrsCheck::new(
+                    entry,
+                    vec![SymbolAddrsStatus::Absent],
+                    at_address,
+                ));
+                continue;
+            };
+            let sym = info.symbol;
+
+            let mut statuses = Vec::new();
+            if sym.vram != entry.vram {
+                statuses.push(SymbolAddrsStatus::AddressMismatch { map_vram: sym.vram });
+            }
+            if entry.size.is_some_and(|x| x != sym.size) {
+                statuses.push(SymbolAddrsStatus::SizeMismatch { map_size: sym.size });
+            }
+            if entry.vrom.is_some() && entry.vrom != sym.vrom {
+                statuses.push(SymbolAddrsStatus::RomMismatch { map_vrom: sym.vrom });
+            }
+            if statuses.is_empty() {
+                statuses.push(SymbolAddrsStatus::Matching);
+            }
+
+            checks.push(SymbolAddrsCheck::new(entry, statuses, Some(info)));
+        }
+
+        checks
+    }
+
+    fn find_symbol_addrs_entry<'a>(
+        &'a self,
+        entry: &SymbolAddrsEntry,
+    ) -> Option<FoundSymbolInfo<'a>> {
+        let candidates: Vec<(&Segment, FoundSymbolInfo<'a>)> = self
+            .segments_list
+            .iter()
+            .filter_map(|segment| {
+                segment
+                    .find_symbol_by_name(&entry.name)
+                    .map(|info| (segment, info))
+            })
+            .collect();
+
+        // splat segment names don't have the leading dot of the output
+        // sections
+        let in_segment = entry.segment.as_deref().and_then(|name| {
+            candidates.iter().position(|(segment, _)| {
+                segment.name.trim_start_matches('.') == name.trim_start_matches('.')
+            })
+        });
+        let index = in_segment
+            .or_else(|| {
+                candidates
+                    .iter()
+                    .position(|(_, info)| info.symbol.vram == entry.vram)
+            })
+            .unwrap_or(0);
+
+        candidates.into_iter().nth(index).map(|(_, info)| info)
+    }
+}
+
+#[cfg(feature = "python_bindings")]
+#[allow(non_snake_case)]
+pub(crate) mod python_bindings {
+    use pyo3::prelude::*;
+
+    use crate::found_symbol_info::python_bindings::PyFoundSymbolInfo;
+
+    use super::SymbolAddrsStatus;
+
+    /// Owned version of [`super::SymbolAddrsCheck`], since Python objects
+    /// can't borrow from the map.
+    #[derive(Debug, Clone)]
+    #[pyclass(module = "mapfile_parser", name = "SymbolAddrsCheck", frozen)]
+    pub struct PySymbolAddrsCheck {
+        name: String,
+
+        vram: u64,
+
+        line_number: usize,
+
+        statuses: Vec<SymbolAddrsStatus>,
+
+        map_symbol: Option<PyFoundSymbolInfo>,
+
+        description: String,
+    }
+
+    #[pymethods]
+    impl PySymbolAddrsCheck {
+        /* Getters */
+
+        #[getter]
+        fn get_name(&self) -> &str {
+            &self.name
+        }
+
+        #[getter]
+        fn get_vram(&self) -> u64 {
+            self.vram
+        }
+
+        #[getter]
+        fn get_lineNumber(&self) -> usize {
+            self.line_number
+        }
+
+        /// The names of the `SymbolAddrsStatus` variants of the entry, like
+        /// `AddressMismatch`.
+        #[getter]
+        fn get_statuses(&self) -> Vec<&'static str> {
+            self.statuses
+                .iter()
+                .map(|x| match x {
+                    SymbolAddrsStatus::Matching => "Matching",
+                    SymbolAddrsStatus::AddressMismatch { .. } => "AddressMismatch",
+                    SymbolAddrsStatus::SizeMismatch { .. } => "SizeMismatch",
+                    SymbolAddrsStatus::RomMismatch { .. } => "RomMismatch",
+                    SymbolAddrsStatus::Absent => "Absent",
+                })
+                .collect()
+        }
+
+        #[getter]
+        fn get_mapVram(&self) -> Option<u64> {
+            self.statuses.iter().find_map(|x| match x {
+                SymbolAddrsStatus::AddressMismatch { map_vram } => Some(*map_vram),
+                _ => None,
+            })
+        }
+
+        #[getter]
+        fn get_mapSize(&self) -> Option<u64> {
+            self.statuses.iter().find_map(|x| match x {
+                SymbolAddrsStatus::SizeMismatch { map_size } => Some(*map_size),
+                _ => None,
+            })
+        }
+
+        #[getter]
+        fn get_mapVrom(&self) -> Option<u64> {
+            self.statuses.iter().find_map(|x| match x {
+                SymbolAddrsStatus::RomMismatch { map_vrom } => *map_vrom,
+                _ => None,
+            })
+        }
+
+        #[getter]
+        fn get_mapSymbol(&self) -> Option<PyFoundSymbolInfo> {
+            self.map_symbol.clone()
+        }
+
+        /* Methods */
+
+        fn isMatching(&self) -> bool {
+            self.statuses == [SymbolAddrsStatus::Matching]
+        }
+
+        fn __str__(&self) -> &str {
+            &self.description
+        }
+    }
+
+    impl From<super::SymbolAddrsCheck<'_>> for PySymbolAddrsCheck {
+        fn from(value: super::SymbolAddrsCheck) -> Self {
+            Self {
+                name: value.entry.name.clone(),
+                vram: value.entry.vram,
+                line_number: value.entry.line_number,
+                description: value.to_string(),
+                statuses: value.statuses,
+                map_symbol: value.map_symbol.map(PyFoundSymbolInfo::from),
+            }
+        }
+    }
+}
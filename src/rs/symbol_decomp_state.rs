@@ -56,7 +56,7 @@ impl<'sect> Iterator for SymbolDecompStateIter<'sect> {
         if self.whole_file_is_undecomped || sym.nonmatching_sym_exists {
             return Some(SymbolDecompState::Undecomped(sym));
         } else if let Some(functions_path) = &self.functions_path {
-            if functions_path.join(sym.name.clone() + ".s").exists() {
+            if functions_path.join(sym.name.to_string() + ".s").exists() {
                 return Some(SymbolDecompState::Undecomped(sym));
             }
         }
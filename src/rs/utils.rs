@@ -3,22 +3,29 @@
 
 use std::{
     fs::File,
-    io::{BufReader, Read},
+    io::{self, BufReader, Read},
+    num::ParseIntError,
     path::Path,
 };
 
 pub fn parse_hex(src: &str) -> u64 {
-    u64::from_str_radix(src.trim_start_matches("0x"), 16).unwrap()
+    try_parse_hex(src).unwrap()
+}
+
+pub fn try_parse_hex(src: &str) -> Result<u64, ParseIntError> {
+    u64::from_str_radix(src.trim_start_matches("0x"), 16)
 }
 
 pub fn read_file_contents(file_path: &Path) -> String {
+    try_read_file_contents(file_path).expect("Not able to read the whole contents of the file")
+}
+
+pub fn try_read_file_contents(file_path: &Path) -> io::Result<String> {
     let mut file_contents = String::new();
-    let f = File::open(file_path).expect("Could not open input file");
-    BufReader::new(f)
-        .read_to_string(&mut file_contents)
-        .expect("Not able to read the whole contents of the file");
+    let f = File::open(file_path)?;
+    BufReader::new(f).read_to_string(&mut file_contents)?;
 
-    file_contents
+    Ok(file_contents)
 }
 
 pub fn is_noload_section(section_name: &str) -> bool {
@@ -34,6 +41,13 @@ pub fn is_noload_section(section_name: &str) -> bool {
     if section_name == ".scommon" {
         return true;
     }
+    // Mach-O
+    if section_name == "__bss" {
+        return true;
+    }
+    if section_name == "__common" {
+        return true;
+    }
 
     false
 }
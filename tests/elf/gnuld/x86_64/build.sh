@@ -0,0 +1,14 @@
+#!/bin/sh
+
+# Rebuilds game.elf and game.map from the sources on this folder.
+
+set -e
+cd "$(dirname "$0")"
+
+mkdir -p build/src
+for f in main util; do
+    gcc -O1 -fno-pic -fno-asynchronous-unwind-tables -fno-stack-protector -fno-inline -ffreestanding \
+        -c src/$f.c -o build/src/$f.o
+done
+ld -static -nostdlib -Map game.map -o game.elf build/src/main.o build/src/util.o
+rm -rf build
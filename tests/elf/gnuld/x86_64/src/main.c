@@ -0,0 +1,17 @@
+extern int util(int x);
+
+int gCounter = 1;
+static int sState[4];
+
+static int step(int x)
+{
+    sState[x & 3] += x;
+    return sState[x & 3];
+}
+
+void _start(void)
+{
+    gCounter = util(step(gCounter));
+    for (;;) {
+    }
+}
@@ -0,0 +1,13 @@
+static const int sTable[8] = { 1, 2, 3, 5, 8, 13, 21, 34 };
+static int sCalls;
+
+static int scale(int x)
+{
+    return x * sTable[x & 7];
+}
+
+int util(int x)
+{
+    sCalls++;
+    return scale(x) + sCalls;
+}